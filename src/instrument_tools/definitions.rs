@@ -0,0 +1,288 @@
+use std::{collections::HashMap, io, path::Path};
+
+use crate::note::{Instrument, Partial};
+
+/// Loads instruments from a definition file, so they can be changed without recompiling.
+///
+/// The file is written in a small subset of TOML. Each instrument is a table named after it, with its
+/// partials as `[cents, level]` pairs and any of the other [`Instrument`] fields as whole numbers:
+///
+/// ```toml
+/// # A hollow, clarinet-like tone
+/// [reed]
+/// partials = [[0, 100], [1902, 40], [2786, 20]]
+/// attack_ms = 30
+/// release_ms = 60
+/// low_pass_hz = 3000
+/// midi_program = 71
+/// transpose = -2
+/// ```
+///
+/// Only `partials` is required. The rest default to the same short envelope as
+/// [`presets::additive`](crate::instrument_tools::presets::additive): a 5 ms attack, no decay, full sustain,
+/// a 30 ms release, no filter, MIDI program 80, and no transposition. Table names can be quoted to use
+/// spaces or other characters, as in `["Lead synth"]`, and each instrument is named after its table.
+///
+/// Instruments store their names and partials as `&'static` values, so loading them leaks a small amount
+/// of memory for each one. Load the file once and reuse the instruments.
+///
+/// # Errors
+/// Returns an error if the file can't be read, or [`io::ErrorKind::InvalidData`] if it isn't a valid
+/// definition file. The error's message says which line is wrong.
+pub fn load_instruments(path: impl AsRef<Path>) -> io::Result<HashMap<String, Instrument>> {
+    parse_instruments(&std::fs::read_to_string(path)?)
+}
+
+/// Parses instruments from the text of a definition file. See [`load_instruments`] for the format.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::definitions::parse_instruments;
+///
+/// let instruments = parse_instruments(
+///     "[flute]\npartials = [[0, 100], [1200, 10]]\nattack_ms = 40\n\n[\"soft bell\"]\npartials = [[0, 100], [2400, 60]]\nsustain = 0\ndecay_ms = 800\n",
+/// )
+/// .unwrap();
+///
+/// let flute = instruments["flute"];
+/// assert_eq!(flute.partials[1], Partial { cents: 1200, level: 10 });
+/// assert_eq!((flute.attack_ms, flute.release_ms), (40, 30));
+/// assert_eq!(instruments["soft bell"].name, "soft bell");
+///
+/// let melody = instrument(flute, quarter(C4) + quarter(A4));
+/// ```
+///
+/// # Errors
+/// Returns [`io::ErrorKind::InvalidData`] if the text isn't a valid definition file.
+pub fn parse_instruments(text: &str) -> io::Result<HashMap<String, Instrument>> {
+    let mut instruments = HashMap::new();
+    // The instrument being read, and the line its name is on
+    let mut current: Option<(String, usize, Definition)> = None;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index.saturating_add(1), line));
+
+    while let Some((number, line)) = lines.next() {
+        let error = |message: String| io::Error::new(io::ErrorKind::InvalidData, format!("line {number}: {message}"));
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            let name = name.trim();
+            let name = name
+                .strip_prefix('"')
+                .and_then(|name| name.strip_suffix('"'))
+                .unwrap_or(name);
+            if name.is_empty() {
+                return Err(error("instruments need a name".to_string()));
+            }
+            if let Some((name, line, definition)) = current.take() {
+                instruments.insert(name.clone(), definition.finish(name, line)?);
+            }
+            if instruments.contains_key(name) {
+                return Err(error(format!("`{name}` is defined twice")));
+            }
+            current = Some((name.to_string(), number, Definition::default()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(error(format!("expected `key = value` or `[name]`, found `{line}`")));
+        };
+        let Some((_, _, definition)) = current.as_mut() else {
+            return Err(error("settings must come after an `[instrument]` name".to_string()));
+        };
+        let mut value = value.trim().to_string();
+        // Arrays can be split over several lines
+        while value.matches('[').count() > value.matches(']').count() {
+            let Some((_, line)) = lines.next() else {
+                return Err(error("the array is never closed".to_string()));
+            };
+            value.push_str(strip_comment(line).trim());
+        }
+        definition.set(key.trim(), &value).map_err(error)?;
+    }
+
+    if let Some((name, line, definition)) = current {
+        instruments.insert(name.clone(), definition.finish(name, line)?);
+    }
+    Ok(instruments)
+}
+
+/// Removes a `#` comment from the end of a line, leaving any `#` inside a quoted name
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, character) in line.char_indices() {
+        match character {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// The settings read so far for one instrument
+#[derive(Default)]
+struct Definition {
+    partials: Option<Vec<Partial>>,
+    attack_ms: Option<u16>,
+    decay_ms: Option<u16>,
+    sustain: Option<u8>,
+    release_ms: Option<u16>,
+    low_pass_hz: Option<u16>,
+    midi_program: Option<u8>,
+    transpose: Option<i16>,
+}
+
+impl Definition {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "partials" => self.partials = Some(parse_partials(value)?),
+            "attack_ms" => self.attack_ms = Some(parse_number(key, value)?),
+            "decay_ms" => self.decay_ms = Some(parse_number(key, value)?),
+            "sustain" => self.sustain = Some(parse_number(key, value)?),
+            "release_ms" => self.release_ms = Some(parse_number(key, value)?),
+            "low_pass_hz" => self.low_pass_hz = Some(parse_number(key, value)?),
+            "midi_program" => self.midi_program = Some(parse_number(key, value)?),
+            "transpose" => self.transpose = Some(parse_number(key, value)?),
+            _ => return Err(format!("unknown setting `{key}`")),
+        }
+        Ok(())
+    }
+
+    /// Makes the instrument named on line `line`, once all of its settings have been read
+    fn finish(self, name: String, line: usize) -> io::Result<Instrument> {
+        let Some(partials) = self.partials else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {line}: `{name}` has no partials"),
+            ));
+        };
+        Ok(Instrument {
+            name: name.leak(),
+            partials: partials.leak(),
+            attack_ms: self.attack_ms.unwrap_or(5),
+            decay_ms: self.decay_ms.unwrap_or(0),
+            sustain: self.sustain.unwrap_or(100),
+            release_ms: self.release_ms.unwrap_or(30),
+            low_pass_hz: self.low_pass_hz,
+            midi_program: self.midi_program.unwrap_or(80),
+            transpose: self.transpose.unwrap_or(0),
+        })
+    }
+}
+
+/// Parses a whole number that has to fit in `T`
+fn parse_number<T: TryFrom<i64>>(key: &str, value: &str) -> Result<T, String> {
+    let number: i64 = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{key}` should be a whole number, not `{}`", value.trim()))?;
+    T::try_from(number).map_err(|_| format!("`{key}` is out of range: {number}"))
+}
+
+/// Parses partials written as `[[cents, level], [cents, level], ...]`
+fn parse_partials(value: &str) -> Result<Vec<Partial>, String> {
+    let inner = value
+        .trim()
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .ok_or_else(|| "`partials` should be a list of `[cents, level]` pairs".to_string())?;
+
+    inner
+        .split(']')
+        .map(|pair| pair.trim().trim_start_matches(',').trim())
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let numbers = pair
+                .strip_prefix('[')
+                .ok_or_else(|| format!("each partial should be a `[cents, level]` pair, not `{pair}`"))?;
+            let Some((cents, level)) = numbers.split_once(',') else {
+                return Err(format!("each partial should be a `[cents, level]` pair, not `{pair}]`"));
+            };
+            Ok(Partial {
+                cents: parse_number("cents", cents)?,
+                level: parse_number("level", level)?,
+            })
+        })
+        .collect()
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_loaded_instruments_render_like_built_ones() {
+    use crate::prelude::*;
+
+    // Named after the process, so test runs at the same time don't share the file
+    let path = std::env::temp_dir().join(format!("symphoxy_instruments_test_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "# Definitions for the test\n\
+         [reed]\n\
+         partials = [\n    [0, 100],\n    [1902, 40], # the third harmonic\n    [2786, 20],\n]\n\
+         attack_ms = 30\n\
+         release_ms = 60\n\
+         low_pass_hz = 3000\n\
+         midi_program = 71\n\
+         \n\
+         [\"C# pluck\"] # quoted, so the name can have a #\n\
+         partials = [[0, 100]]\n\
+         sustain = 0\n\
+         decay_ms = 200\n",
+    )
+    .unwrap();
+    let instruments = load_instruments(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    const PARTIALS: &[Partial] = &[
+        Partial { cents: 0, level: 100 },
+        Partial { cents: 1902, level: 40 },
+        Partial { cents: 2786, level: 20 },
+    ];
+    let reed = Instrument {
+        name: "reed",
+        partials: PARTIALS,
+        attack_ms: 30,
+        decay_ms: 0,
+        sustain: 100,
+        release_ms: 60,
+        low_pass_hz: Some(3000),
+        midi_program: 71,
+        transpose: 0,
+    };
+    assert_eq!(instruments.len(), 2);
+    assert_eq!(instruments["reed"], reed);
+    assert_eq!(instruments["C# pluck"].decay_ms, 200);
+
+    let player = crate::MusicPlayer::new_file(300, 1.0, 8000);
+    let loaded = player.render_channels(instrument(instruments["reed"], quarter(C4) + quarter(A4)));
+    assert!(loaded[0].iter().any(|&sample| sample.abs() > 0.1));
+    assert_eq!(
+        loaded,
+        player.render_channels(instrument(reed, quarter(C4) + quarter(A4)))
+    );
+
+    // Mistakes are reported with the line they're on
+    for (text, message) in [
+        ("attack_ms = 5", "line 1: settings must come after"),
+        (
+            "[a]\npartials = [[0, 100]]\nsustain = 300",
+            "line 3: `sustain` is out of range",
+        ),
+        (
+            "[a]\npartials = [[0, 100]]\nvolume = 1",
+            "line 3: unknown setting `volume`",
+        ),
+        ("\n[a]\nattack_ms = 5\n[b]", "line 2: `a` has no partials"),
+        ("[a]\npartials = [[0 100]]", "line 2: each partial should be"),
+    ] {
+        let error = parse_instruments(text).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains(message), "{error}");
+    }
+}
@@ -3,3 +3,14 @@
 /// Contains fret mapping, string tuning systems, and chord generation
 /// tools for string instruments like guitars.
 pub mod strings;
+
+/// Ready-made synthesized instruments.
+///
+/// Contains presets such as strings, organ, bell, and bass, for good sounds without building
+/// an [`Instrument`](crate::Instrument) by hand, and `additive` for building one from harmonics.
+pub mod presets;
+
+/// Instruments defined in files rather than code.
+///
+/// Contains `load_instruments`, which reads [`Instrument`](crate::Instrument)s from a TOML definition file.
+pub mod definitions;
@@ -0,0 +1,291 @@
+use crate::note::{Instrument, Partial};
+
+/// A sawtooth-like series of harmonics, each doubled slightly sharp and flat for the sound of a section
+const STRINGS_PARTIALS: &[Partial] = &[
+    Partial { cents: -6, level: 100 },
+    Partial { cents: 6, level: 100 },
+    Partial { cents: 1194, level: 50 },
+    Partial { cents: 1206, level: 50 },
+    Partial { cents: 1896, level: 33 },
+    Partial { cents: 1908, level: 33 },
+    Partial { cents: 2400, level: 25 },
+    Partial { cents: 2786, level: 20 },
+    Partial { cents: 3102, level: 16 },
+];
+
+/// Drawbars at 16', 8', 5⅓', 4', 2⅔', and 2', like a jazz organ registration
+const ORGAN_PARTIALS: &[Partial] = &[
+    Partial {
+        cents: -1200,
+        level: 80,
+    },
+    Partial { cents: 0, level: 100 },
+    Partial { cents: 702, level: 60 },
+    Partial { cents: 1200, level: 70 },
+    Partial { cents: 1902, level: 40 },
+    Partial { cents: 2400, level: 50 },
+];
+
+/// The hum, prime, tierce, quint, nominal, and upper partials of a tuned church bell
+const BELL_PARTIALS: &[Partial] = &[
+    Partial {
+        cents: -1200,
+        level: 50,
+    },
+    Partial { cents: 0, level: 80 },
+    Partial { cents: 300, level: 60 },
+    Partial { cents: 702, level: 30 },
+    Partial {
+        cents: 1200,
+        level: 100,
+    },
+    Partial { cents: 1586, level: 35 },
+    Partial { cents: 1902, level: 25 },
+    Partial { cents: 2400, level: 15 },
+];
+
+/// A strong fundamental with a few harmonics for definition, which the filter rounds off
+const BASS_PARTIALS: &[Partial] = &[
+    Partial { cents: 0, level: 100 },
+    Partial { cents: 1200, level: 60 },
+    Partial { cents: 1902, level: 35 },
+    Partial { cents: 2400, level: 20 },
+    Partial { cents: 2786, level: 10 },
+];
+
+/// A string section: a bright, slightly detuned tone that swells in and lingers after the note ends.
+///
+/// Best for sustained chords and slow melodies, where the slow attack has time to bloom.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// let pad = instrument(presets::strings(), whole_chord([C4, C4.semitone(4), C4.semitone(7)]));
+/// ```
+pub const fn strings() -> Instrument {
+    Instrument {
+        name: "strings",
+        partials: STRINGS_PARTIALS,
+        attack_ms: 150,
+        decay_ms: 0,
+        sustain: 100,
+        release_ms: 200,
+        low_pass_hz: Some(4000),
+        midi_program: 48,
+        transpose: 0,
+    }
+}
+
+/// An electric organ: a steady, reedy tone with a sub-octave, that starts and stops almost instantly.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// let hymn = instrument(presets::organ(), half(C4) + half(C4.semitone(5)));
+/// ```
+pub const fn organ() -> Instrument {
+    Instrument {
+        name: "organ",
+        partials: ORGAN_PARTIALS,
+        attack_ms: 8,
+        decay_ms: 0,
+        sustain: 100,
+        release_ms: 30,
+        low_pass_hz: None,
+        midi_program: 16,
+        transpose: 0,
+    }
+}
+
+/// A church bell: a sharp strike with clashing, bell-like partials that die away slowly.
+///
+/// Bells sound an octave above the pitch they're written at, so they suit notes from around C4 upwards.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// let chime = instrument(presets::bell(), whole(C4) + whole(C4.semitone(-5)));
+/// ```
+pub const fn bell() -> Instrument {
+    Instrument {
+        name: "bell",
+        partials: BELL_PARTIALS,
+        attack_ms: 2,
+        decay_ms: 2500,
+        sustain: 0,
+        release_ms: 100,
+        low_pass_hz: None,
+        midi_program: 14,
+        transpose: 0,
+    }
+}
+
+/// A synth bass: a round, filtered tone with a punchy start that settles to a steady level.
+///
+/// This is softer and darker than the built-in [`bass`](crate::bass) timbre.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// let groove = instrument(presets::bass(), eighth(C4.octave(-2)) + eighth(C4.octave(-1)));
+/// ```
+pub const fn bass() -> Instrument {
+    Instrument {
+        name: "synth_bass",
+        partials: BASS_PARTIALS,
+        attack_ms: 5,
+        decay_ms: 250,
+        sustain: 60,
+        release_ms: 60,
+        low_pass_hz: Some(600),
+        midi_program: 38,
+        transpose: 0,
+    }
+}
+
+/// Builds an instrument from harmonics, each a `(harmonic, amplitude)` pair.
+///
+/// Harmonic 1 is the note's own pitch, 2 an octave above it, 3 an octave and a fifth, and so on, and each
+/// is a sine wave with the given amplitude relative to the others. The harmonics are summed with a short
+/// attack and release, and no filter, so what you hear is exactly the spectrum you asked for. Harmonics
+/// above the Nyquist frequency are skipped when a note is rendered, since they would alias down to
+/// unrelated pitches. Harmonic 0, and harmonics with no amplitude, are ignored.
+///
+/// Instruments store their partials as a `&'static` slice, so this leaks a small amount of memory for each
+/// instrument it builds. Build an instrument once and reuse it, rather than calling this for every note.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// // A square-like wave, from odd harmonics falling away as 1/n
+/// let square = presets::additive(vec![(1, 1.0), (3, 1.0 / 3.0), (5, 1.0 / 5.0), (7, 1.0 / 7.0)]);
+/// let melody = instrument(square, quarter(C4) + quarter(A4));
+///
+/// assert_eq!(square.partials.len(), 4);
+/// assert_eq!(square.partials[1].cents, 1902);
+/// ```
+pub fn additive(partials: Vec<(u32, f32)>) -> Instrument {
+    let loudest = partials
+        .iter()
+        .fold(0.0f32, |loudest, &(_, amplitude)| loudest.max(amplitude));
+    let partials: Vec<Partial> = partials
+        .into_iter()
+        .filter(|&(harmonic, amplitude)| harmonic > 0 && amplitude > 0.0)
+        .filter_map(|(harmonic, amplitude)| {
+            #[expect(clippy::cast_possible_truncation, reason = "Rounded, and checked against the range")]
+            let cents = (1200.0 * f64::from(harmonic).log2()).round() as i32;
+            // Levels are out of 255 for the loudest harmonic, for the finest control over the rest
+            #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Between 0 and 255")]
+            let level = (amplitude / loudest * 255.0).round() as u8;
+            Some(Partial {
+                cents: i16::try_from(cents).ok()?,
+                level,
+            })
+        })
+        .collect();
+
+    Instrument {
+        name: "",
+        partials: partials.leak(),
+        attack_ms: 5,
+        decay_ms: 0,
+        sustain: 100,
+        release_ms: 30,
+        low_pass_hz: None,
+        midi_program: 80,
+        transpose: 0,
+    }
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_presets_have_plausible_spectra() {
+    use crate::{instrument, note::quarter, note::whole, MusicPlayer, Note, Tet12, C4};
+
+    let render = |preset: Instrument, note: Note| {
+        let player = MusicPlayer::new_file(300, 1.0, 48000);
+        player.render_channels(instrument(preset, note))[0].clone()
+    };
+    // Estimates the energy within 8 cents of a frequency, allowing for detuned partials
+    let energy_at = |samples: &[f32], frequency: f32| {
+        (-4i8..=4)
+            .map(|offset| {
+                let frequency = frequency * 2.0f32.powf(f32::from(offset) * 2.0 / 1200.0);
+                crate::play::spectrum::energy_at(samples, frequency, 48000)
+            })
+            .fold(0.0f32, f32::max)
+    };
+
+    for preset in [strings(), organ(), bell(), bass()] {
+        let samples = render(preset, quarter(C4));
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(peak > 0.1, "{} is silent", preset.name);
+    }
+
+    // Strings have a full series of harmonics, and nothing in between them
+    let frequency = C4.0;
+    let samples = render(strings(), quarter(C4));
+    let fundamental = energy_at(&samples, frequency);
+    assert!(energy_at(&samples, 2.0 * frequency) > fundamental / 10.0);
+    assert!(energy_at(&samples, 3.0 * frequency) > fundamental / 20.0);
+    assert!(energy_at(&samples, 1.5 * frequency) < fundamental / 1000.0);
+
+    // The organ has a sub-octave from its 16' drawbar
+    let samples = render(organ(), quarter(C4));
+    assert!(energy_at(&samples, frequency / 2.0) > energy_at(&samples, frequency) / 4.0);
+
+    // The bell's minor third is what makes it sound like a bell, and it dies away after the strike
+    let samples = render(bell(), whole(C4));
+    let tierce = C4.semitone(3).0;
+    assert!(energy_at(&samples, tierce) > energy_at(&samples, frequency) / 10.0);
+    let (start, end) = samples.split_at(samples.len() / 2);
+    assert!(energy_at(end, 2.0 * frequency) < energy_at(start, 2.0 * frequency) / 2.0);
+
+    // The bass filter leaves the fundamental, but takes most of the upper harmonics away
+    let low = C4.octave(-2);
+    let samples = render(bass(), quarter(low));
+    assert!(energy_at(&samples, 5.0 * low.0) < energy_at(&samples, low.0) / 100.0);
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_additive_harmonics() {
+    use crate::{instrument, note::half, MusicPlayer, C4};
+
+    let player = MusicPlayer::new_file(300, 1.0, 48000);
+    let render = |preset: Instrument| player.render_channels(instrument(preset, half(C4)))[0].clone();
+    let energy_at = |samples: &[f32], frequency: f32| crate::play::spectrum::energy_at(samples, frequency, 48000);
+
+    // Only the fundamental is a single sine partial at the note's pitch, whatever its amplitude
+    let fundamental = additive(vec![(1, 0.3)]);
+    let sine = Instrument {
+        partials: &[Partial { cents: 0, level: 100 }],
+        ..fundamental
+    };
+    let samples = render(fundamental);
+    assert_eq!(samples, render(sine));
+    assert!(energy_at(&samples, 2.0 * C4.0) < energy_at(&samples, C4.0) / 1000.0);
+
+    // Adding the second harmonic puts an overtone an octave up
+    let samples = render(additive(vec![(1, 1.0), (2, 0.5)]));
+    let ratio = energy_at(&samples, 2.0 * C4.0) / energy_at(&samples, C4.0);
+    assert!(
+        (0.15..0.35).contains(&ratio),
+        "octave to fundamental energy ratio {ratio}"
+    );
+
+    // Harmonic 0 and silent harmonics are dropped, and harmonics above the Nyquist frequency aren't heard
+    assert_eq!(additive(vec![(0, 1.0), (1, 1.0), (2, 0.0)]).partials.len(), 1);
+    let samples = render(additive(vec![(1, 1.0), (200, 1.0)]));
+    assert_eq!(samples, render(additive(vec![(1, 1.0)])));
+}
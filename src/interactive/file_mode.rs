@@ -1,50 +1,119 @@
+use std::io;
+
+#[cfg(feature = "live-output")]
+use crate::interactive::interrupt::InterruptGuard;
 use crate::{
-    interactive::{InteractiveTui, PlayResult, SelectionInfo, Selections, TuiSelectable},
+    interactive::{InteractiveTui, PlayResult, SelectionInfo, Selections, TuiIo, TuiSelectable},
     play::FileOutputConfig,
-    MusicPlayer, Piece,
+    MusicPlayer, NotePitch, Piece,
 };
 
 impl InteractiveTui {
-    pub(super) fn handle_file_mode(piece: &Piece) -> PlayResult {
+    pub(super) fn handle_file_mode(piece: &Piece, io: &mut TuiIo) -> io::Result<PlayResult> {
         let mut player = MusicPlayer::new_file(300, 1.0, 44100);
         let mut path = InteractiveTui::get_absolute_path("./output.wav");
 
         loop {
-            let choice = InteractiveTui::get_input::<FileModeSelection>(FileModeSelectionContext {
-                tempo: player.tempo_bpm,
-                path: path.as_ref().ok().cloned(),
-                output_config: player.output_config.clone(),
-            });
+            let Some(choice) = InteractiveTui::get_input::<FileModeSelection>(
+                FileModeSelectionContext {
+                    tempo: player.tempo_bpm,
+                    reference_pitch: player.reference_pitch,
+                    path: path.as_ref().ok().cloned(),
+                    output_config: player.output_config.clone(),
+                },
+                io,
+            )?
+            else {
+                return Ok(PlayResult::Continue);
+            };
 
             match choice {
                 FileModeSelection::Render => {
                     if let Ok(ref path) = path.as_ref() {
-                        println!("Rendering piece to {path}.");
+                        let summary = piece.summary();
+                        say!(
+                            io,
+                            "The piece is {:.1} seconds long, so the file will be about {} KB.",
+                            summary.duration(&player).as_secs_f32(),
+                            summary.estimated_file_size(&player).div_ceil(1000)
+                        );
+                        say!(io, "Rendering piece to {path}.");
                         player.render_to_wav(piece.clone(), path);
-                        println!("Rendering complete. Saved to {path}.");
+                        say!(io, "Rendering complete. Saved to {path}.");
                     } else {
-                        println!("No valid output path set. Please set a valid path first.");
+                        say!(io, "No valid output path set. Please set a valid path first.");
                         continue;
                     }
                 }
+                #[cfg(feature = "live-output")]
+                FileModeSelection::RenderAndPlay => {
+                    let Ok(ref path) = path.as_ref() else {
+                        say!(io, "No valid output path set. Please set a valid path first.");
+                        continue;
+                    };
+                    let Ok((_output_stream, output_handle)) = rodio::OutputStream::try_default() else {
+                        say!(
+                            io,
+                            "Failed to get default output stream. Please ensure your audio output is configured correctly."
+                        );
+                        continue;
+                    };
+
+                    say!(io, "Rendering piece to {path}, then playing it.");
+                    say!(io, "Press Ctrl-C to stop playback.");
+                    io.writer.flush()?;
+                    let guard = InterruptGuard::new();
+                    if let Err(err) = piece.render_and_play(&player, path, &output_handle) {
+                        say!(io, "Failed to write {path}: {err}");
+                        continue;
+                    }
+                    if guard.was_interrupted() {
+                        say!(io);
+                        say!(io, "Playback stopped.");
+                    }
+                    say!(io, "Saved to {path}.");
+                }
+                FileModeSelection::RenderPreview => {
+                    let Ok(ref path) = path.as_ref() else {
+                        say!(io, "No valid output path set. Please set a valid path first.");
+                        continue;
+                    };
+                    let seconds =
+                        InteractiveTui::get_range_input("Enter how many seconds to preview", 1, 3600, Some(10), io)?;
+                    let end = player.time_unit_at(std::time::Duration::from_secs(u64::from(seconds)));
+                    say!(io, "Rendering the first {seconds} seconds of the piece to {path}.");
+                    player.render_to_wav(piece.slice(0..end), path);
+                    say!(io, "Rendering complete. Saved to {path}.");
+                }
                 FileModeSelection::ChangeTempo => {
-                    let new_tempo = InteractiveTui::get_range_input::<10, 1000>("Enter tempo in BPM");
+                    let new_tempo =
+                        InteractiveTui::get_range_input("Enter tempo in BPM", 10, 1000, Some(player.tempo_bpm), io)?;
                     player.tempo_bpm = new_tempo;
                 }
+                FileModeSelection::ChangeTuning => {
+                    let new_reference = InteractiveTui::get_pitch_input("Enter A4 reference pitch in Hz", io)?;
+                    player = player.with_reference_pitch(NotePitch::new(new_reference));
+                }
                 FileModeSelection::ChangeOutputGain => {
-                    let new_gain = InteractiveTui::get_positive_float_input("Enter output gain");
+                    let new_gain = InteractiveTui::get_positive_float_input("Enter output gain", io)?;
                     player.output_config.output_gain = new_gain;
                 }
                 FileModeSelection::ChangeSampleRate => {
-                    let new_sample_rate = InteractiveTui::get_range_input::<8000, 192000>("Enter sample rate");
+                    let new_sample_rate = InteractiveTui::get_range_input(
+                        "Enter sample rate",
+                        8000,
+                        192000,
+                        Some(player.output_config.sample_rate),
+                        io,
+                    )?;
                     player.output_config.sample_rate = new_sample_rate;
                 }
                 FileModeSelection::ChangeOutputPath => {
-                    let new_path = InteractiveTui::get_path_input("Enter output file path");
+                    let new_path = InteractiveTui::get_path_input("Enter output file path", io)?;
                     path = Ok(new_path);
                 }
-                FileModeSelection::Exit => return PlayResult::Exit,
-                FileModeSelection::Continue => return PlayResult::Continue,
+                FileModeSelection::Exit => return Ok(PlayResult::Exit),
+                FileModeSelection::Continue => return Ok(PlayResult::Continue),
             }
         }
     }
@@ -53,7 +122,11 @@ impl InteractiveTui {
 #[derive(Clone, Copy)]
 enum FileModeSelection {
     Render,
+    #[cfg(feature = "live-output")]
+    RenderAndPlay,
+    RenderPreview,
     ChangeTempo,
+    ChangeTuning,
     ChangeOutputGain,
     ChangeSampleRate,
     ChangeOutputPath,
@@ -63,6 +136,7 @@ enum FileModeSelection {
 
 struct FileModeSelectionContext {
     tempo: u32,
+    reference_pitch: NotePitch,
     path: Option<String>,
     output_config: FileOutputConfig,
 }
@@ -81,6 +155,21 @@ impl TuiSelectable for FileModeSelection {
                     },
                     FileModeSelection::Render,
                 ),
+                #[cfg(feature = "live-output")]
+                (
+                    SelectionInfo {
+                        name: "Write and Play".to_string(),
+                        description: "Write the piece to a file, then play it".to_string(),
+                    },
+                    FileModeSelection::RenderAndPlay,
+                ),
+                (
+                    SelectionInfo {
+                        name: "Write Preview".to_string(),
+                        description: "Write the first few seconds of the piece to a file".to_string(),
+                    },
+                    FileModeSelection::RenderPreview,
+                ),
                 (
                     SelectionInfo {
                         name: "Change Tempo".to_string(),
@@ -88,6 +177,13 @@ impl TuiSelectable for FileModeSelection {
                     },
                     FileModeSelection::ChangeTempo,
                 ),
+                (
+                    SelectionInfo {
+                        name: "Change Tuning".to_string(),
+                        description: format!("Current: A4 = {} Hz", context.reference_pitch.0),
+                    },
+                    FileModeSelection::ChangeTuning,
+                ),
                 (
                     SelectionInfo {
                         name: "Change Output Gain".to_string(),
@@ -132,6 +228,64 @@ impl TuiSelectable for FileModeSelection {
                 ),
             ],
             default: Some(0), // Default to Render
+            cancelable: true,
         }
     }
 }
+
+#[test]
+fn test_back_cancels_selection() {
+    let context = || FileModeSelectionContext {
+        tempo: 300,
+        reference_pitch: crate::A4,
+        path: None,
+        output_config: FileOutputConfig::default(),
+    };
+
+    let choose = |input: &str| {
+        super::with_test_io(input, |io| {
+            InteractiveTui::get_input::<FileModeSelection>(context(), io).unwrap()
+        })
+        .0
+    };
+
+    // Going back cancels the menu, so the file mode returns to the mode selection loop
+    for input in ["b\n", "Back\n"] {
+        assert!(choose(input).is_none());
+    }
+
+    // Other input still chooses options as usual
+    assert!(matches!(choose("exit\n"), Some(FileModeSelection::Exit)));
+
+    // The mode selection can't be canceled, so "b" is treated as an invalid selection there
+    let (mode, output) = super::with_test_io("b\ndisplay\n", |io| {
+        InteractiveTui::get_input::<super::Mode>((), io).unwrap()
+    });
+    assert!(matches!(mode, Some(super::Mode::Display)));
+    assert!(output.ends_with("Invalid selection, please try again.\n"));
+}
+
+#[test]
+fn test_preview_renders_the_requested_seconds() {
+    use crate::prelude::*;
+
+    let path = std::env::temp_dir().join("symphoxy_preview_test.wav");
+    let path = path.to_str().unwrap();
+    // 12.8 seconds long at the default 300 BPM
+    let piece = piano(whole(C4) * 4) * bass(double_whole(C4.octave(-1)) * 2);
+
+    for seconds in [1u32, 5, 12] {
+        let input = format!("write\nchange output path\n{path}\nwrite preview\n{seconds}\nexit\n");
+        let mut output = Vec::new();
+        InteractiveTui::start_with_io(piece.clone(), input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains(&format!("Rendering the first {seconds} seconds")),
+            "{output}"
+        );
+
+        let reader = hound::WavReader::open(path).unwrap();
+        assert_eq!(reader.duration(), seconds * reader.spec().sample_rate);
+    }
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,105 @@
+use std::{io::Write, sync::atomic::Ordering};
+
+use crate::play::STOP_LIVE_PLAYBACK;
+
+/// While held, pressing Ctrl-C stops live playback instead of exiting the program.
+///
+/// Dropping the guard restores whichever Ctrl-C handler was installed before it, such as the
+/// default behaviour or the host application's own, and clears any stop request, so cleanup
+/// happens whether playback finished normally or was interrupted.
+/// Interrupts are only caught on Unix platforms.
+pub(super) struct InterruptGuard {
+    /// The handler that was installed when the guard was created
+    #[cfg(unix)]
+    previous: libc::sighandler_t,
+    _private: (),
+}
+
+impl InterruptGuard {
+    pub(super) fn new() -> Self {
+        STOP_LIVE_PLAYBACK.store(false, Ordering::SeqCst);
+
+        #[cfg(unix)]
+        // SAFETY: The handler only stores to an atomic, which is async-signal-safe
+        let previous = unsafe {
+            libc::signal(
+                libc::SIGINT,
+                handle_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            )
+        };
+
+        InterruptGuard {
+            // If the handler couldn't be installed, there's no way to know what was there before
+            #[cfg(unix)]
+            previous: if previous == libc::SIG_ERR {
+                libc::SIG_DFL
+            } else {
+                previous
+            },
+            _private: (),
+        }
+    }
+
+    /// Whether playback was interrupted while this guard was held
+    pub(super) fn was_interrupted(&self) -> bool {
+        STOP_LIVE_PLAYBACK.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: The previous handler was installed before, so it's as sound to install as it was then
+        unsafe {
+            libc::signal(libc::SIGINT, self.previous);
+        }
+
+        STOP_LIVE_PLAYBACK.store(false, Ordering::SeqCst);
+        // Don't leave a half-written line behind, such as the "^C" echoed by the terminal
+        let _ = std::io::stdout().flush();
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn handle_interrupt(_signal: libc::c_int) {
+    STOP_LIVE_PLAYBACK.store(true, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_interrupt_guard_cleans_up_on_drop() {
+    let current_handler = || {
+        // SAFETY: Only reads the current handler, passing a valid pointer to write it to
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            libc::sigaction(libc::SIGINT, std::ptr::null(), &mut action);
+            action.sa_sigaction
+        }
+    };
+
+    extern "C" fn host_handler(_signal: libc::c_int) {}
+    let host_handler = host_handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: The handler does nothing, so it's async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, host_handler);
+    }
+
+    // Ctrl-C is caught while the guard is held. Raising a real interrupt would set the stop flag, which is
+    // shared with any playback running in parallel tests, so only check that the handler is installed.
+    let guard = InterruptGuard::new();
+    assert!(!guard.was_interrupted());
+    assert_eq!(
+        current_handler(),
+        handle_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t
+    );
+
+    // Dropping the guard puts back the handler the host application had installed
+    drop(guard);
+    assert!(!STOP_LIVE_PLAYBACK.load(Ordering::SeqCst));
+    assert_eq!(current_handler(), host_handler);
+
+    // SAFETY: Restoring the default handler is always sound
+    unsafe {
+        libc::signal(libc::SIGINT, libc::SIG_DFL);
+    }
+}
@@ -1,47 +1,109 @@
-use std::sync::Arc;
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    interactive::{InteractiveTui, PlayResult, SelectionInfo, Selections, TuiSelectable},
-    MusicPlayer, Piece,
+    interactive::{
+        interrupt::InterruptGuard, InteractiveTui, PlayResult, SelectionInfo, Selections, TuiIo, TuiSelectable,
+    },
+    MusicPlayer, NotePitch, Piece,
 };
 
+/// The slowest tempo that can be set in live mode, in BPM
+const MIN_TEMPO: u32 = 10;
+
+/// The fastest tempo that can be set in live mode, in BPM
+const MAX_TEMPO: u32 = 1000;
+
 impl InteractiveTui {
-    pub(super) fn handle_live_mode(piece: &Piece) -> PlayResult {
+    pub(super) fn handle_live_mode(piece: &Piece, io: &mut TuiIo) -> io::Result<PlayResult> {
         let Ok((_output_stream, output_handle)) = rodio::OutputStream::try_default() else {
-            println!("Failed to get default output stream. Please ensure your audio output is configured correctly.");
-            return PlayResult::Continue;
+            say!(
+                io,
+                "Failed to get default output stream. Please ensure your audio output is configured correctly."
+            );
+            return Ok(PlayResult::Continue);
         };
         let output_handle = Arc::new(output_handle);
 
         let mut player = MusicPlayer::new_live(300, output_handle);
         let mut show_score = false;
         loop {
-            let choice = InteractiveTui::get_input::<LiveModeSelection>(LiveModeSelectionContext {
-                show_score,
-                tempo: player.tempo_bpm as u64,
-            });
+            let Some(choice) = InteractiveTui::get_input::<LiveModeSelection>(
+                LiveModeSelectionContext {
+                    show_score,
+                    tempo: player.tempo_bpm as u64,
+                    reference_pitch: player.reference_pitch,
+                },
+                io,
+            )?
+            else {
+                return Ok(PlayResult::Continue);
+            };
 
             match choice {
                 LiveModeSelection::ChangeTempo => {
-                    let new_tempo = InteractiveTui::get_range_input::<10, 1000>("Enter tempo in BPM");
+                    let new_tempo = InteractiveTui::get_range_input(
+                        "Enter tempo in BPM",
+                        MIN_TEMPO,
+                        MAX_TEMPO,
+                        Some(player.tempo_bpm),
+                        io,
+                    )?;
 
                     player.tempo_bpm = new_tempo;
-                    println!("Tempo changed to {new_tempo} BPM.");
+                    say!(io, "Tempo changed to {new_tempo} BPM.");
+                }
+                LiveModeSelection::TapTempo => {
+                    say!(
+                        io,
+                        "Press Enter on every quarter note beat. Type anything else and press Enter when done:"
+                    );
+                    let mut taps = Vec::new();
+                    while io.read_line()?.is_empty() {
+                        taps.push(Instant::now());
+                    }
+
+                    if let Some(new_tempo) = tapped_tempo(&taps) {
+                        player.tempo_bpm = new_tempo;
+                        say!(io, "Tempo changed to {new_tempo} BPM.");
+                    } else {
+                        say!(
+                            io,
+                            "Tap at least twice to set the tempo. It's still {} BPM.",
+                            player.tempo_bpm
+                        );
+                    }
+                }
+                LiveModeSelection::ChangeTuning => {
+                    let new_reference = InteractiveTui::get_pitch_input("Enter A4 reference pitch in Hz", io)?;
+
+                    player = player.with_reference_pitch(NotePitch::new(new_reference));
+                    say!(io, "A4 tuned to {} Hz.", player.reference_pitch.0);
                 }
                 LiveModeSelection::Play => {
                     if show_score {
-                        println!("Playing piece with score display:");
-                        println!("{piece}");
+                        say!(io, "Playing piece with score display:");
+                        say!(io, "{piece}");
                     } else {
-                        println!("Playing piece without score display.");
+                        say!(io, "Playing piece without score display.");
                     }
+                    say!(io, "Press Ctrl-C to stop playback.");
+                    io.writer.flush()?;
+                    let guard = InterruptGuard::new();
                     player.play(piece.clone()).join().expect("Failed to play piece");
+                    if guard.was_interrupted() {
+                        say!(io);
+                        say!(io, "Playback stopped.");
+                    }
                 }
                 LiveModeSelection::ToggleScore => {
                     show_score = !show_score;
                 }
-                LiveModeSelection::Exit => return PlayResult::Exit,
-                LiveModeSelection::Continue => return PlayResult::Continue,
+                LiveModeSelection::Exit => return Ok(PlayResult::Exit),
+                LiveModeSelection::Continue => return Ok(PlayResult::Continue),
             }
         }
     }
@@ -50,6 +112,8 @@ impl InteractiveTui {
 #[derive(Clone, Copy)]
 enum LiveModeSelection {
     ChangeTempo,
+    TapTempo,
+    ChangeTuning,
     ToggleScore,
     Play,
     Exit,
@@ -59,6 +123,7 @@ enum LiveModeSelection {
 struct LiveModeSelectionContext {
     show_score: bool,
     tempo: u64,
+    reference_pitch: NotePitch,
 }
 
 impl TuiSelectable for LiveModeSelection {
@@ -82,6 +147,20 @@ impl TuiSelectable for LiveModeSelection {
                     },
                     Self::ChangeTempo,
                 ),
+                (
+                    SelectionInfo {
+                        name: "Tap Tempo".to_string(),
+                        description: "Set the tempo by tapping Enter on the beat".to_string(),
+                    },
+                    Self::TapTempo,
+                ),
+                (
+                    SelectionInfo {
+                        name: "Change Tuning".to_string(),
+                        description: format!("Current: A4 = {} Hz", context.reference_pitch.0),
+                    },
+                    Self::ChangeTuning,
+                ),
                 (
                     SelectionInfo {
                         name: if context.show_score { "Hide Score" } else { "Show Score" }.to_string(),
@@ -105,6 +184,51 @@ impl TuiSelectable for LiveModeSelection {
                 ),
             ],
             default: Some(0),
+            cancelable: true,
         }
     }
 }
+
+/// Works out the tempo, in BPM, of a series of taps on every quarter note, from the average time between them.
+///
+/// Each quarter note is four of the player's sixteenth-note beats. The tempo is clamped to the range live mode
+/// allows, and there's no tempo without at least two taps some time apart.
+fn tapped_tempo(taps: &[Instant]) -> Option<u32> {
+    let (first, last) = (taps.first()?, taps.last()?);
+    let intervals = u128::try_from(taps.len().checked_sub(1)?).ok()?;
+    let elapsed = last.saturating_duration_since(*first).as_micros();
+
+    // Four sixteenths per tap, over a minute
+    let tempo = Duration::from_secs(4 * 60)
+        .as_micros()
+        .checked_mul(intervals)?
+        .checked_add(elapsed / 2)?
+        .checked_div(elapsed)?;
+    Some(u32::try_from(tempo).unwrap_or(u32::MAX).clamp(MIN_TEMPO, MAX_TEMPO))
+}
+
+#[test]
+fn test_tapped_tempo_averages_intervals() {
+    let start = Instant::now();
+    let taps = |millis: &[u64]| -> Vec<Instant> {
+        millis
+            .iter()
+            .map(|&ms| start.checked_add(Duration::from_millis(ms)).unwrap())
+            .collect()
+    };
+
+    // Steady taps every half second are 120 quarter notes, or 480 sixteenths, a minute
+    assert_eq!(tapped_tempo(&taps(&[0, 500, 1000, 1500])), Some(480));
+
+    // Uneven taps are averaged: 1500ms over three intervals is still 500ms each
+    assert_eq!(tapped_tempo(&taps(&[0, 400, 1100, 1500])), Some(480));
+
+    // Very slow and very fast tapping are clamped to the allowed range
+    assert_eq!(tapped_tempo(&taps(&[0, 60_000])), Some(MIN_TEMPO));
+    assert_eq!(tapped_tempo(&taps(&[0, 50, 100])), Some(MAX_TEMPO));
+
+    // A single tap, or none, has no tempo
+    assert_eq!(tapped_tempo(&taps(&[0])), None);
+    assert_eq!(tapped_tempo(&[]), None);
+    assert_eq!(tapped_tempo(&taps(&[0, 0])), None);
+}
@@ -1,11 +1,26 @@
+use std::io::{self, BufRead, Write};
+
 use crate::Piece;
 
+/// Writes a line to the TUI's output, like `println!`, returning early if writing fails.
+macro_rules! say {
+    ($io:expr) => {
+        writeln!($io.writer)?
+    };
+    ($io:expr, $($arg:tt)*) => {
+        writeln!($io.writer, $($arg)*)?
+    };
+}
+
 #[cfg(feature = "wav-output")]
 mod file_mode;
 
 #[cfg(feature = "live-output")]
 mod live_mode;
 
+#[cfg(feature = "live-output")]
+mod interrupt;
+
 /// Interactive TUI for playing music pieces in a terminal interface.
 /// Allows users to select modes and configure playback options interactively.
 ///
@@ -23,6 +38,8 @@ impl InteractiveTui {
     /// Starts the interactive TUI for playing a music piece.
     /// Allows users to select playback modes and configure options interactively.
     ///
+    /// The TUI reads from stdin and writes to stdout. See [`InteractiveTui::start_with_io`] to use other streams.
+    ///
     /// # Arguments
     /// * `piece` - The music piece to be played interactively.
     ///
@@ -34,121 +51,199 @@ impl InteractiveTui {
     /// let piece = Piece::from(piano(quarter(C4) + quarter(A4)));
     /// InteractiveTui::start(piece);
     /// ```
+    ///
+    /// # Panics
+    /// Panics if reading from stdin or writing to stdout fails.
     pub fn start(piece: Piece) {
+        InteractiveTui::start_with_io(piece, io::stdin().lock(), io::stdout()).expect("Failed to use the terminal");
+    }
+
+    /// Starts the interactive TUI, reading the user's input from `reader` and writing to `writer`.
+    ///
+    /// This lets the TUI be driven by a script, or shown inside another interface.
+    /// The TUI finishes when the user exits, or when there's no input left.
+    ///
+    /// Live playback still plays through the default audio device, and files are still written to disk.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::InteractiveTui;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + quarter(A4)));
+    /// let mut output = Vec::new();
+    /// InteractiveTui::start_with_io(piece, "display\n".as_bytes(), &mut output).unwrap();
+    ///
+    /// let output = String::from_utf8(output).unwrap();
+    /// assert!(output.starts_with("Select an option:"));
+    /// assert!(output.ends_with("Exiting interactive mode.\n"));
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if reading from `reader` or writing to `writer` fails.
+    pub fn start_with_io(piece: Piece, mut reader: impl BufRead, mut writer: impl Write) -> io::Result<()> {
+        let mut io = TuiIo {
+            reader: &mut reader,
+            writer: &mut writer,
+        };
+        match InteractiveTui::run(&piece, &mut io) {
+            // Running out of input is treated the same as choosing to exit
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {}
+            result => result?,
+        }
+        say!(io, "Exiting interactive mode.");
+        io.writer.flush()
+    }
+
+    fn run(piece: &Piece, io: &mut TuiIo) -> io::Result<()> {
+        for lint in piece.lint() {
+            say!(io, "Warning: {lint}");
+        }
+
         loop {
-            let mode = InteractiveTui::get_input::<Mode>(());
+            let Some(mode) = InteractiveTui::get_input::<Mode>((), io)? else {
+                continue;
+            };
 
             let result = match mode {
                 #[cfg(feature = "live-output")]
-                Mode::Live => InteractiveTui::handle_live_mode(&piece),
+                Mode::Live => InteractiveTui::handle_live_mode(piece, io)?,
                 #[cfg(feature = "wav-output")]
-                Mode::File => InteractiveTui::handle_file_mode(&piece),
+                Mode::File => InteractiveTui::handle_file_mode(piece, io)?,
                 Mode::Display => {
-                    println!("{piece}");
+                    say!(io, "{piece}");
                     PlayResult::Continue
                 }
             };
 
             match result {
-                PlayResult::Exit => break,
+                PlayResult::Exit => return Ok(()),
                 PlayResult::Continue => continue,
             }
         }
-
-        println!("Exiting interactive mode.");
     }
 
+    /// Asks the user to choose one of the selections.
+    ///
+    /// Returns `None` if the selections are cancelable and the user chose to go back.
     #[expect(clippy::arithmetic_side_effects, reason = "No selection will have usize::MAX options")]
-    fn get_input<T: TuiSelectable>(context: T::Context) -> T {
+    fn get_input<T: TuiSelectable>(context: T::Context, io: &mut TuiIo) -> io::Result<Option<T>> {
         let selections = T::get_selections(context);
         let options = selections.options;
-        println!("{}:", selections.description);
+        say!(io, "{}:", selections.description);
         for (index, (key, _)) in options.iter().enumerate() {
-            println!("    {}. {} ({})", index + 1, key.name, key.description);
+            say!(io, "    {}. {} ({})", index + 1, key.name, key.description);
+        }
+        if selections.cancelable {
+            say!(io, "    b. Back (Return to the previous menu)");
         }
         if let Some(default) = selections.default {
-            println!("Default: {}", options[default].0.name);
+            say!(io, "Default: {}", options[default].0.name);
         }
 
         loop {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).expect("Failed to read line");
-            let input = input.trim().to_lowercase();
+            let input = io.read_line()?.to_lowercase();
 
             if input.is_empty() {
                 if let Some(default) = selections.default {
-                    return options[default].1;
+                    return Ok(Some(options[default].1));
                 } else {
-                    println!("Input cannot be empty, please try again.");
+                    say!(io, "Input cannot be empty, please try again.");
                     continue;
                 }
             }
 
+            if selections.cancelable && (input == "b" || input == "back") {
+                return Ok(None);
+            }
+
             if let Some((_, (_, value))) = options.iter().enumerate().find(|(idx, (selection, _))| {
                 (idx + 1).to_string() == input
                     || selection.name.to_lowercase().starts_with(&input)
                     || selection.description.to_lowercase().starts_with(&input)
             }) {
-                return *value;
+                return Ok(Some(*value));
             } else {
-                println!("Invalid selection, please try again.");
+                say!(io, "Invalid selection, please try again.");
             }
         }
     }
 
-    fn get_range_input<const MIN: u32, const MAX: u32>(ask: &str) -> u32 {
-        println!("{ask} (Between {MIN} and {MAX}):");
+    /// Asks the user for a whole number between `min` and `max`, inclusive.
+    ///
+    /// If there's a default, entering nothing chooses it.
+    fn get_range_input(ask: &str, min: u32, max: u32, default: Option<u32>, io: &mut TuiIo) -> io::Result<u32> {
+        say!(io, "{ask} (Between {min} and {max}):");
+        if let Some(default) = default {
+            say!(io, "Default: {default}");
+        }
         loop {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).expect("Failed to read line");
+            let input = io.read_line()?;
 
-            if let Ok(value) = input.trim().parse() {
-                if !(MIN..=MAX).contains(&value) {
-                    println!("Please enter a value between {MIN} and {MAX}.");
+            if input.is_empty() {
+                if let Some(default) = default {
+                    return Ok(default);
+                }
+            }
+
+            if let Ok(value) = input.parse() {
+                if !(min..=max).contains(&value) {
+                    say!(io, "Please enter a value between {min} and {max}.");
                     continue;
                 }
-                return value;
+                return Ok(value);
             } else {
-                println!("Invalid input. Please enter a valid BPM.");
+                say!(io, "Invalid input. Please enter a valid whole number.");
                 continue;
             }
         }
     }
 
-    #[cfg(feature = "wav-output")]
-    fn get_positive_float_input(ask: &str) -> f32 {
-        println!("{ask} (Between 0.0 and infinity):");
+    fn get_positive_float_input(ask: &str, io: &mut TuiIo) -> io::Result<f32> {
+        say!(io, "{ask} (Between 0.0 and infinity):");
         loop {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).expect("Failed to read line");
-
-            if let Ok(value) = input.trim().parse() {
+            if let Ok(value) = io.read_line()?.parse() {
                 if value < 0.0 {
-                    println!("Please enter a positive value.");
+                    say!(io, "Please enter a positive value.");
                     continue;
                 }
-                return value;
+                return Ok(value);
             } else {
-                println!("Invalid input. Please enter a valid BPM.");
+                say!(io, "Invalid input. Please enter a valid number.");
+                continue;
+            }
+        }
+    }
+
+    /// Asks for a frequency in Hz, such as a reference pitch, until a finite one above zero is entered
+    fn get_pitch_input(ask: &str, io: &mut TuiIo) -> io::Result<f32> {
+        say!(io, "{ask} (Above 0.0):");
+        loop {
+            if let Ok(value) = io.read_line()?.parse::<f32>() {
+                if !(value.is_finite() && value > 0.0) {
+                    say!(io, "Please enter a finite pitch above 0.0 Hz.");
+                    continue;
+                }
+                return Ok(value);
+            } else {
+                say!(io, "Invalid input. Please enter a valid number.");
                 continue;
             }
         }
     }
 
     #[cfg(feature = "wav-output")]
-    fn get_path_input(ask: &str) -> String {
-        println!("{ask}:");
+    fn get_path_input(ask: &str, io: &mut TuiIo) -> io::Result<String> {
+        say!(io, "{ask}:");
         loop {
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input).expect("Failed to read line");
-            let input = input.trim().to_string();
+            let input = io.read_line()?;
 
             match Self::get_absolute_path(input.as_str()) {
                 Ok(absolute_path) => {
-                    return absolute_path;
+                    return Ok(absolute_path);
                 }
                 Err(err) => {
-                    println!("{err}");
+                    say!(io, "{err}");
                     continue;
                 }
             }
@@ -187,6 +282,27 @@ enum PlayResult {
     Exit,
 }
 
+/// Where a running TUI reads its input from and writes its output to
+struct TuiIo<'a> {
+    reader: &'a mut dyn BufRead,
+    writer: &'a mut dyn Write,
+}
+
+impl TuiIo<'_> {
+    /// Reads a line of input, without its surrounding whitespace.
+    ///
+    /// Returns an [`io::ErrorKind::UnexpectedEof`] error if there's no input left.
+    fn read_line(&mut self) -> io::Result<String> {
+        // Make sure the question has been shown before waiting for the answer
+        self.writer.flush()?;
+        let mut input = String::new();
+        if self.reader.read_line(&mut input)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "there is no input left"));
+        }
+        Ok(input.trim().to_string())
+    }
+}
+
 trait TuiSelectable: Sized + Copy {
     type Context;
 
@@ -197,6 +313,8 @@ struct Selections<T> {
     pub description: String,
     pub default: Option<usize>,
     pub options: Vec<(SelectionInfo, T)>,
+    /// Whether the user can go back to the previous menu instead of choosing an option
+    pub cancelable: bool,
 }
 
 struct SelectionInfo {
@@ -220,6 +338,7 @@ impl TuiSelectable for Mode {
         Selections {
             description: "Select an option".to_string(),
             default: None,
+            cancelable: false,
             options: vec![
                 #[cfg(feature = "live-output")]
                 (
@@ -248,3 +367,84 @@ impl TuiSelectable for Mode {
         }
     }
 }
+
+/// Runs `f` with a TUI that reads `input` and writes to a buffer, and returns what it wrote
+#[cfg(test)]
+fn with_test_io<T>(input: &str, f: impl FnOnce(&mut TuiIo) -> T) -> (T, String) {
+    let mut output = Vec::new();
+    let result = f(&mut TuiIo {
+        reader: &mut input.as_bytes(),
+        writer: &mut output,
+    });
+    (result, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_range_input_bounds() {
+    let range_input = |input, default| {
+        with_test_io(input, |io| {
+            InteractiveTui::get_range_input("Tempo", 10, 1000, default, io).unwrap()
+        })
+        .0
+    };
+
+    // Values outside the bounds and invalid numbers are asked for again
+    assert_eq!(range_input("5\n1001\nfast\n\n120\n", None), 120);
+
+    // The bounds are inclusive
+    assert_eq!(range_input("10\n", None), 10);
+    assert_eq!(range_input("1000\n", None), 1000);
+
+    // Entering nothing chooses the default, if there is one
+    assert_eq!(range_input("\n", Some(300)), 300);
+    assert_eq!(range_input("9\n480\n", Some(300)), 480);
+}
+
+#[test]
+fn test_pitch_input_is_finite_and_positive() {
+    let (pitch, output) = with_test_io("inf\nNaN\n-inf\n0\n-440\nhigh\n442.5\n", |io| {
+        InteractiveTui::get_pitch_input("Pitch", io).unwrap()
+    });
+
+    // Infinite, NaN, and non-positive pitches are asked for again, like invalid numbers
+    assert_eq!(pitch, 442.5);
+    assert_eq!(output.matches("Please enter a finite pitch above 0.0 Hz.").count(), 5);
+    assert_eq!(output.matches("Invalid input. Please enter a valid number.").count(), 1);
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_scripted_session() {
+    use crate::prelude::*;
+
+    let piece = Piece::from(piano(quarter(C4) + quarter(A4)));
+    let script = "display\nwrite\nchange tempo\n120\nnonsense\nback\nwrite\nexit\ndisplay\n";
+    let mut output = Vec::new();
+    InteractiveTui::start_with_io(piece.clone(), script.as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    // The mode selection is shown first, and then the piece
+    assert!(output.starts_with("Select an option:\n"));
+    let display = output.find(&piece.to_string()).unwrap();
+    assert!(display > output.find("    3. Display (Display music information)").unwrap());
+
+    // Changing the tempo is shown the next time the file mode menu is printed
+    let tempo = output
+        .find("Enter tempo in BPM (Between 10 and 1000):\nDefault: 300\n")
+        .unwrap();
+    assert!(output[..tempo].contains("Current: 300 BPM"));
+    assert!(output[tempo..].contains("Current: 120 BPM"));
+    assert!(output[tempo..].contains("Invalid selection, please try again.\n"));
+
+    // Going back returns to the mode selection, and exiting ends the session before the last line is read
+    assert_eq!(output.matches("Select an option:\n").count(), 3);
+    assert!(output.ends_with("Default: Write\nExiting interactive mode.\n"));
+
+    // Running out of input also ends the session
+    let mut output = Vec::new();
+    InteractiveTui::start_with_io(piece, "write\n".as_bytes(), &mut output).unwrap();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .ends_with("Exiting interactive mode.\n"));
+}
+
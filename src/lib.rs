@@ -100,7 +100,7 @@
 //! 2. **Collections**: `Line` (sequential), `Piece` (simultaneous), `Chord` (harmonic)
 //! 3. **Traits**: `LengthFluid`, `TimbreFluid`, `ChordFluid` for flexibility
 //! 4. **Scales**: Support for different musical scales and tuning systems
-//! 5. **Instruments**: Guitar fret/tuning support and other instrument-specific tools
+//! 5. **Instruments**: Guitar fret/tuning support, preset synthesized instruments, and other instrument-specific tools
 //!
 //! ## Features
 //!
@@ -108,6 +108,9 @@
 //! - `wav-output`: Export compositions to WAV audio files  
 //! - `live-output`: Real-time audio playback
 //!
+//! To find out at runtime which features were enabled, such as to only offer the output options that
+//! are available, use [`features`].
+//!
 //! ## Philosophy
 //!
 //! Rather than relying heavily on traditional music notation, Symphoxy embraces a
@@ -175,16 +178,91 @@ pub mod scales;
 
 pub use instrument_tools::strings::{Frets, GuitarFrets, GuitarTuning, StringTuning};
 pub use note::chord::Chord;
-pub use note::{bass, drums, electric_guitar, piano, sine};
-pub use note::{dotted, double_whole, eighth, half, quarter, sixteenth, tie, whole};
+pub use note::PitchRounding;
+pub use note::{bandpass_noise, bass, drums, electric_guitar, instrument, noise, piano, sine};
+pub use note::{beats, Glide, Note, NoteKind, NoteLength, NoteLengthError, NotePitch, NoteValue, Timbre, REST};
+pub use note::{dotted, double_whole, eighth, half, note, quarter, sixteenth, tie, whole};
+pub use note::{double_whole_chord, eighth_chord, half_chord, quarter_chord, sixteenth_chord, whole_chord};
+pub use note::{Instrument, Lfo, ModTarget, Partial};
 pub use note::{LengthFluid, TimbreFluid};
-pub use note::{Note, NoteKind, NoteLength, NotePitch, Timbre, REST};
-pub use piece::line::Line;
-pub use piece::Piece;
+pub use piece::bars::{MeterError, TimeSignature};
+pub use piece::canon::canon;
+pub use piece::chance::maybe;
+pub use piece::groove::nudge;
+pub use piece::label::{label, Label};
+pub use piece::line::{FadeCurve, Line, ShortNotePolicy};
+pub use piece::lint::Lint;
+pub use piece::metadata::Metadata;
+pub use piece::midi::MidiOptions;
+pub use piece::modulation::lfo;
+pub use piece::ornament::{grace, grace_with, portamento, trill, GraceTiming};
+pub use piece::phrase::PhraseBuilder;
+pub use piece::summary::PieceSummary;
+pub use piece::tempo::{accelerando, fermata, ritardando};
+pub use piece::timeline::NoteTimeline;
+pub use piece::{Piece, TimedNote};
 pub use scales::interval::ChordShape;
-pub use scales::tet12::{get_note_name, get_note_name_with_octave, Tet12, A4, C4};
+pub use scales::key::{Key, KeyMode};
+pub use scales::pitch_class::PitchClassSet;
+pub use scales::tet12::{get_note_name, get_note_name_with_naming, get_note_name_with_octave, Tet12, A4, C4};
+pub use scales::tet12::{octave_down, octave_up};
+pub use scales::tet12::{PitchNaming, PitchParseError};
 pub use scales::Scale;
 
+/// Which optional parts of symphoxy were compiled in, from [`features`].
+///
+/// Only the Cargo features are listed. Everything else, such as exporting MIDI files with
+/// [`Piece::to_midi`], is always available. More fields may be added as features are added.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Features {
+    /// Whether pieces can be played through the speakers, with the `live-output` feature
+    pub live_output: bool,
+    /// Whether pieces can be rendered to WAV files and audio buffers, with the `wav-output` feature
+    pub wav_output: bool,
+    /// Whether the interactive terminal interface is available, with the `interactive-tui` feature
+    pub interactive_tui: bool,
+}
+
+/// Gets which optional parts of symphoxy were compiled in.
+///
+/// Cargo features are chosen when symphoxy is built, so this is for apps that are built with
+/// different features, and want to show only the options that are available.
+///
+/// # Example
+/// ```
+/// let features = symphoxy::features();
+/// if features.wav_output {
+///     println!("Export to WAV");
+/// }
+/// if features.live_output {
+///     println!("Play through the speakers");
+/// }
+/// ```
+pub const fn features() -> Features {
+    Features {
+        live_output: cfg!(feature = "live-output"),
+        wav_output: cfg!(feature = "wav-output"),
+        // The terminal interface also needs one of the output features to be usable
+        interactive_tui: cfg!(all(
+            feature = "interactive-tui",
+            any(feature = "wav-output", feature = "live-output")
+        )),
+    }
+}
+
+#[test]
+fn test_features_match_compiled_cfgs() {
+    let features = features();
+    assert_eq!(features.live_output, cfg!(feature = "live-output"));
+    assert_eq!(features.wav_output, cfg!(feature = "wav-output"));
+    assert_eq!(features.interactive_tui, cfg!(feature = "interactive-tui"));
+
+    // Every feature that's reported is actually usable
+    #[cfg(feature = "wav-output")]
+    let _ = MusicPlayer::new_file(300, 1.0, 8000).render_channels(Piece::default());
+}
+
 /// Commonly used types and functions for music composition.
 ///
 /// Import this module to get access to all the essential types and functions
@@ -197,19 +275,110 @@ pub use scales::Scale;
 /// let melody = piano(quarter(C4)) + piano(quarter(A4));
 /// let piece = melody * bass(half(C4));
 /// ```
+///
+/// The prelude contains everything needed to write music, but not to play it:
+///
+/// - **Lengths**: `sixteenth` to `double_whole`, `dotted`, `tie`, `note`, `beats`, `NoteLength`, and `PhraseBuilder`
+/// - **Pitches**: `NotePitch`, `C4`, `A4`, transposing with `Tet12`, and naming with `get_note_name` and `PitchNaming`
+/// - **Timbres**: `piano`, `bass`, `sine`, `electric_guitar`, `drums`, `noise`, `bandpass_noise`, `instrument`, and `Timbre`
+/// - **Rests and chords**: `REST`, `Chord`, `quarter_chord` and friends, `Interval`, and `ChordShape`
+/// - **Scales and keys**: `Scale`, `MajorScale` and the other scales, `Key`, and `PitchClassSet`
+/// - **Combinators**: `grace`, `trill`, `portamento`, `label`, `maybe`, `nudge`, `ritardando`, `accelerando`, and `Reversable`
+/// - **Guitars**: `Frets`, `StringTuning`, and `GuitarTuning::DEFAULT_GUITAR_TUNING`
+///
+/// Sequencing, layering, and repeating are done with operators rather than functions:
+/// `a + b` plays `a` then `b`, `a * b` plays them together, and `a * 3` plays `a` three times.
+///
+/// Playback and export types such as `MusicPlayer` are not in the prelude, and are imported from the crate root.
 pub mod prelude {
     pub use crate::instrument_tools::strings::*;
     pub use crate::note::chord::*;
     pub use crate::note::*;
+    pub use crate::piece::canon::*;
+    pub use crate::piece::chance::*;
+    pub use crate::piece::groove::*;
+    pub use crate::piece::label::*;
+    pub use crate::piece::modulation::*;
+    pub use crate::piece::ornament::*;
+    pub use crate::piece::phrase::*;
+    pub use crate::piece::tempo::*;
+    pub use crate::piece::Reversable;
+    pub use crate::scales::interval::*;
+    pub use crate::scales::key::*;
+    pub use crate::scales::pitch_class::*;
+    pub use crate::scales::tet12::*;
     pub use crate::scales::*;
-    pub use crate::{Line, Piece};
+    pub use crate::{FadeCurve, Line, Piece, ShortNotePolicy, TimedNote};
     pub use crate::{Note, NoteKind, NotePitch, REST};
-    pub use crate::{Scale, Tet12};
-    pub use crate::{A4, C4};
+}
+
+#[test]
+fn test_prelude_exports() {
+    // Refers to every item in the prelude, so removing one by accident fails to compile
+    use prelude::*;
+    #[expect(unused_imports, reason = "Only checks that the prelude still has these items")]
+    use prelude::{
+        AeolianScale, ChromaticScale, DorianScale, IonianScale, LocrianScale, LydianScale, MajorScale, MinorScale,
+        MixolydianScale, PhrygianScale, TwelveToneScale, WholeToneScale,
+    };
+    #[expect(unused_imports, reason = "Only checks that the prelude still has these items")]
+    use prelude::{ChordFluid, HasNoteLength, LengthFluid, Reversable, Scale, Tet12, TimbreFluid};
+    #[expect(unused_imports, reason = "Only checks that the prelude still has these items")]
+    use prelude::{Frets, GuitarFrets, GuitarTuning, StringTuning};
+
+    let lengths = [sixteenth, eighth, quarter, half, whole, double_whole];
+    let line: Line = lengths
+        .iter()
+        .map(|length| piano(length(C4)))
+        .collect::<Vec<Note>>()
+        .into();
+    let line = line + bass(dotted(quarter)(A4)) + sine(tie(quarter, eighth)(C4)) + electric_guitar(quarter(REST));
+    let line = line + drums(note(C4, NoteLength::QUARTER)) + noise(note(C4, beats(0.5).unwrap()));
+    let line = line + PhraseBuilder::new(NoteLength::EIGHTH).note(C4).notes([A4]).build();
+    let line = line + instrument(crate::instrument_tools::presets::organ(), quarter(C4));
+    let line = line + bandpass_noise(8, quarter(C4)) + label("end", piano(trill(C4, A4, NoteLength(4), NoteLength(1))));
+    let line = nudge(maybe(ritardando(accelerando(line, 300, 600), 600, 300), 0.5), -5.0).reverse();
+    let line = portamento(line, std::time::Duration::ZERO);
+    let ornament = grace_with(
+        piano(sixteenth(C4)),
+        piano(quarter(A4)),
+        GraceTiming::StealFromPrincipal,
+    );
+    let line = grace(ornament, line.clone()) + line;
+
+    let chords = quarter_chord(Chord::new([C4, A4])) + sixteenth_chord([C4]) + eighth_chord([C4]);
+    let chords = chords + half_chord([C4]) + whole_chord([C4]) + double_whole_chord([C4]);
+    let shape = ChordShape::from_intervals([Interval::UNISON, Interval::MAJOR_THIRD]);
+    let piece: Piece = chords * (line * 2) * piano(whole(C4.with_chord_shape(&shape)));
+
+    let _: [NotePitch; 6] = GuitarTuning::DEFAULT_GUITAR_TUNING.0;
+    let _: Option<(
+        TimedNote,
+        Label,
+        NoteKind,
+        Timbre,
+        NoteLengthError,
+        NotePitch,
+        NoteValue,
+    )> = None;
+    let _: Option<(Instrument, Partial, Glide)> = None;
+    let _: String = get_note_name(C4, A4) + &get_note_name_with_octave(C4, A4);
+    let _: String = get_note_name_with_naming(C4, A4, PitchNaming::Helmholtz);
+    let key: Key = piece.detect_key();
+    assert!(matches!(key.mode, KeyMode::Major | KeyMode::Minor));
 }
 
 #[cfg(all(feature = "interactive-tui", any(feature = "wav-output", feature = "live-output")))]
 pub use crate::interactive::InteractiveTui;
 
 #[cfg(any(feature = "wav-output", feature = "live-output"))]
-pub use crate::play::MusicPlayer;
+pub use crate::play::{downmix, downmix_with, CurveShape, MusicPlayer, OscillatorPhase, PanLaw};
+
+#[cfg(feature = "live-output")]
+pub use crate::play::{AudioOutput, AudioStream};
+
+#[cfg(feature = "wav-output")]
+pub use crate::play::{
+    BitDepth, ChannelLayout, ClickTrack, ClipMode, DitherMode, Effect, EffectChain, Interpolation, LoopRegion,
+    MixPrecision, PiecePlayer, RenderCancelled, RenderStats, RoundMode, SpectrogramOptions,
+};
@@ -1,7 +1,4 @@
-use std::ops::Add;
-
-use crate::scales::interval::ChordShape;
-use crate::{Line, Note, NoteKind, NotePitch, Piece, Scale, Tet12, C4};
+use crate::{Line, NotePitch, Piece, Scale};
 
 /// Represents a musical chord - a collection of pitches played simultaneously.
 ///
@@ -25,7 +22,9 @@ use crate::{Line, Note, NoteKind, NotePitch, Piece, Scale, Tet12, C4};
 /// let c_major = Chord::from_degrees(&scale, &[1, 3, 5]);
 ///
 /// // Create common chord shapes
-/// let major_shape = Chord::shape_from_semitone_offsets([4, 7]); // +4 and +7 semitones from root
+/// use symphoxy::{scales::interval::Interval, ChordShape};
+///
+/// let major_shape = ChordShape::from_intervals([Interval::UNISON, Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH]);
 /// let a_major = major_shape.transpose_to(A4);
 /// ```
 #[derive(Clone, Debug, PartialEq, Default)]
@@ -84,7 +83,7 @@ impl Chord {
     /// });
     /// ```
     pub fn strike(&self, striker: fn(NotePitch) -> Line) -> Piece {
-        Piece(self.0.iter().map(|&pitch| striker(pitch)).collect())
+        Piece::from(self.0.iter().map(|&pitch| striker(pitch)).collect::<Vec<_>>())
     }
 
     /// Transposes the chord to a new target pitch.
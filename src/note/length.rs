@@ -50,6 +50,47 @@ use crate::{
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct NoteLength(pub u16);
 
+/// A standard note value from sheet music, such as a quarter or half note. See [`NoteLength::to_notated`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoteValue {
+    /// A sixteenth note (1 time unit)
+    Sixteenth,
+    /// An eighth note (2 time units)
+    Eighth,
+    /// A quarter note (4 time units)
+    Quarter,
+    /// A half note (8 time units)
+    Half,
+    /// A whole note (16 time units)
+    Whole,
+    /// A double whole note, or breve (32 time units)
+    DoubleWhole,
+}
+
+impl NoteValue {
+    /// Every note value, from longest to shortest
+    pub const ALL: [NoteValue; 6] = [
+        NoteValue::DoubleWhole,
+        NoteValue::Whole,
+        NoteValue::Half,
+        NoteValue::Quarter,
+        NoteValue::Eighth,
+        NoteValue::Sixteenth,
+    ];
+
+    /// Gets the length of this note value without any dots.
+    pub fn length(self) -> NoteLength {
+        match self {
+            NoteValue::Sixteenth => NoteLength::SIXTEENTH,
+            NoteValue::Eighth => NoteLength::EIGHTH,
+            NoteValue::Quarter => NoteLength::QUARTER,
+            NoteValue::Half => NoteLength::HALF,
+            NoteValue::Whole => NoteLength::WHOLE,
+            NoteValue::DoubleWhole => NoteLength::DOUBLE_WHOLE,
+        }
+    }
+}
+
 /// A trait for types that can have their note length/duration modified.
 ///
 /// This trait enables a fluent API for setting note durations using functions
@@ -83,12 +124,52 @@ pub trait HasNoteLength {
     fn length(&self) -> NoteLength;
 }
 
+/// Creates a note (or chord, or line) of any length from the given musical element.
+///
+/// This is the general form of the named length functions: `quarter(C4)` is the same as
+/// `note(C4, NoteLength::QUARTER)`. Use it with [`beats`] or computed lengths for rhythms
+/// that aren't known ahead of time.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// assert_eq!(note(C4, NoteLength::QUARTER), quarter(C4));
+///
+/// // A rhythm that speeds up
+/// let line: Line = (1..=4).rev().map(|length| piano(note(C4, NoteLength(length)))).sum();
+/// assert_eq!(line.length(), 10);
+/// ```
+pub fn note<N: LengthFluid>(kind: N, length: NoteLength) -> N::Output {
+    kind.with_length(length)
+}
+
 macro_rules! note_length_fn {
     ($($name:ident, $value:expr, $doc:expr);*) => {
         $(
             #[doc = $doc]
             pub fn $name<N: LengthFluid>(kind: N) -> N::Output {
-                kind.with_length(NoteLength($value))
+                note(kind, NoteLength($value))
+            }
+        )*
+    }
+}
+
+macro_rules! chord_length_fn {
+    ($($name:ident, $value:expr, $doc:expr);*) => {
+        $(
+            #[doc = $doc]
+            ///
+            /// # Examples
+            /// ```
+            /// use symphoxy::prelude::*;
+            ///
+            #[doc = concat!("let piece = ", stringify!($name), "([C4, C4.semitone(4), C4.semitone(7)]);")]
+            /// assert_eq!(piece.lines.len(), 3);
+            #[doc = concat!("assert!(piece.lines.iter().all(|line| line.length() == ", stringify!($value), "));")]
+            /// ```
+            pub fn $name(pitches: impl Into<Chord>) -> Piece {
+                pitches.into().with_length(NoteLength($value))
             }
         )*
     }
@@ -120,7 +201,7 @@ impl LengthFluid for Chord {
     type Output = Piece;
 
     fn with_length(self, length: NoteLength) -> Self::Output {
-        Piece(
+        Piece::from(
             self.0
                 .into_iter()
                 .map(|note| note.with_length(length))
@@ -128,8 +209,9 @@ impl LengthFluid for Chord {
                     notes: vec![note],
                     pickup: vec![],
                     hold_pickup: false,
+                    labels: vec![],
                 })
-                .collect(),
+                .collect::<Vec<_>>(),
         )
     }
 }
@@ -154,6 +236,11 @@ impl LengthFluid for NotePitch {
                 pitch: self,
                 timbre: Timbre::Sine,
                 volume: 1.0,
+                pan: None,
+                probability: 1.0,
+                nudge_ms: 0.0,
+                glide: None,
+                lfo: None,
             },
         )
     }
@@ -168,6 +255,79 @@ note_length_fn!(
     double_whole, 32, "Creates a double whole note (32 time units) from the given musical element."
 );
 
+chord_length_fn!(
+    sixteenth_chord, 1, "Plays the given pitches together as a sixteenth note chord (1 time unit).";
+    eighth_chord, 2, "Plays the given pitches together as an eighth note chord (2 time units).";
+    quarter_chord, 4, "Plays the given pitches together as a quarter note chord (4 time units).";
+    half_chord, 8, "Plays the given pitches together as a half note chord (8 time units).";
+    whole_chord, 16, "Plays the given pitches together as a whole note chord (16 time units).";
+    double_whole_chord, 32, "Plays the given pitches together as a double whole note chord (32 time units)."
+);
+
+/// An error from creating a [`NoteLength`] out of an invalid value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NoteLengthError {
+    /// The length was negative
+    Negative,
+    /// The length was NaN or infinite
+    NotFinite,
+    /// The length was too long to be represented (more than `u16::MAX` sixteenth notes)
+    TooLong,
+}
+
+impl std::fmt::Display for NoteLengthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NoteLengthError::Negative => write!(f, "note length cannot be negative"),
+            NoteLengthError::NotFinite => write!(f, "note length must be a finite number"),
+            NoteLengthError::TooLong => write!(f, "note length is too long"),
+        }
+    }
+}
+
+impl std::error::Error for NoteLengthError {}
+
+/// Creates a note length from a number of beats, where one beat is a quarter note.
+///
+/// This follows the usual musical convention (as in 4/4 time), so `beats(1.0)` is a quarter
+/// note, `beats(0.5)` is an eighth note, and `beats(4.0)` is a whole note. Note that this
+/// differs from `MusicPlayer`'s tempo, which counts sixteenth notes.
+///
+/// Lengths are rounded to the nearest sixteenth note, the smallest length that can be represented.
+///
+/// # Errors
+/// Returns an error if `fraction` is negative, NaN or infinite, or too long to be represented.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// assert_eq!(beats(1.0), Ok(NoteLength(4)));
+/// assert_eq!(quarter(C4), C4.with_length(beats(1.0).unwrap()));
+///
+/// // Five sixteenth notes
+/// assert_eq!(beats(1.25), Ok(NoteLength(5)));
+///
+/// assert_eq!(beats(-1.0), Err(NoteLengthError::Negative));
+/// assert_eq!(beats(f64::NAN), Err(NoteLengthError::NotFinite));
+/// ```
+pub fn beats(fraction: f64) -> Result<NoteLength, NoteLengthError> {
+    if !fraction.is_finite() {
+        return Err(NoteLengthError::NotFinite);
+    }
+    if fraction < 0.0 {
+        return Err(NoteLengthError::Negative);
+    }
+
+    let sixteenths = (fraction * 4.0).round();
+    if sixteenths > f64::from(u16::MAX) {
+        return Err(NoteLengthError::TooLong);
+    }
+
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Checked to be in range")]
+    Ok(NoteLength(sixteenths as u16))
+}
+
 /// Creates a dotted note with 1.5x the duration of the base note.
 ///
 /// In music notation, a dot after a note increases its duration by half.
@@ -11,9 +11,13 @@ pub use timbre::*;
 use std::{
     fmt::Debug,
     ops::{Add, Mul},
+    time::Duration,
 };
 
-use crate::{Line, Piece, A4};
+use crate::{
+    scales::tet12::{get_note_name_with_naming, DisplayWith, PitchNaming},
+    Line, Piece, A4,
+};
 
 /// Represents a musical note with duration, pitch/rest, and timbre
 ///
@@ -28,6 +32,90 @@ use crate::{Line, Piece, A4};
 pub struct Note(pub NoteLength, pub NoteKind);
 
 impl Note {
+    /// Creates a new note from a length and a kind (pitched or rest).
+    ///
+    /// This is the same as constructing `Note(length, kind)` directly.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let rest = Note::new(NoteLength(4), REST);
+    /// assert_eq!(rest, quarter(REST));
+    /// ```
+    pub fn new(length: NoteLength, kind: NoteKind) -> Note {
+        Note(length, kind)
+    }
+
+    /// Creates a new pitched note with every property specified up front.
+    ///
+    /// This is an alternative to building notes with helpers like `piano(quarter(C4))`,
+    /// for when the properties are computed rather than written out by hand.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let note = Note::pitched(C4, NoteLength(4), Timbre::Piano, 0.5);
+    /// assert_eq!(note, piano(quarter(C4)).volume(0.5));
+    ///
+    /// // Notes built this way can be sequenced like any other note
+    /// let line = note + Note::pitched(A4, NoteLength(8), Timbre::Piano, 1.0);
+    /// assert_eq!(line, piano(quarter(C4)).volume(0.5) + piano(half(A4)));
+    /// ```
+    pub fn pitched(pitch: NotePitch, length: NoteLength, timbre: Timbre, volume: f32) -> Note {
+        Note(
+            length,
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan: None,
+                probability: 1.0,
+                nudge_ms: 0.0,
+                glide: None,
+                lfo: None,
+            },
+        )
+    }
+
+    /// Returns the pitch of this note, or `None` if it is a rest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(piano(quarter(C4)).pitch(), Some(C4));
+    /// assert_eq!(quarter(REST).pitch(), None);
+    /// ```
+    pub fn pitch(&self) -> Option<NotePitch> {
+        match self.1 {
+            NoteKind::Pitched { pitch, .. } => Some(pitch),
+            NoteKind::Rest => None,
+        }
+    }
+
+    /// Returns the timbre of this note, or `None` if it is a rest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(piano(quarter(C4)).timbre(), Some(Timbre::Piano));
+    /// assert_eq!(quarter(REST).timbre(), None);
+    /// ```
+    pub fn timbre(&self) -> Option<Timbre> {
+        match self.1 {
+            NoteKind::Pitched { timbre, .. } => Some(timbre),
+            NoteKind::Rest => None,
+        }
+    }
+
+    /// Returns `true` if this note is a rest.
+    pub fn is_rest(&self) -> bool {
+        matches!(self.1, NoteKind::Rest)
+    }
+
     /// Creates a new note with the specified volume level.
     ///
     /// For pitched notes, this sets the volume parameter. For rests,
@@ -62,12 +150,207 @@ impl Note {
     /// ```
     pub fn volume(&self, volume: f32) -> Note {
         let new_note_kind = match self.1 {
-            NoteKind::Pitched { pitch, timbre, .. } => NoteKind::Pitched { pitch, timbre, volume },
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+                ..
+            } => NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+            },
+            NoteKind::Rest => NoteKind::Rest,
+        };
+
+        Note(self.0, new_note_kind)
+    }
+
+    /// Creates a new note positioned at the specified point in the output channels.
+    ///
+    /// Pan ranges from `-1.0` (the first channel, e.g. left) to `1.0` (the last channel,
+    /// e.g. right). With more than two output channels, the note is placed between the
+    /// two nearest channels. Rests are unaffected.
+    ///
+    /// Notes without a pan are spread evenly across every output channel.
+    /// Pan only affects file output, and only for mono sources (all built-in synthesized timbres).
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let left = piano(quarter(C4)).pan(-1.0);
+    /// let right = piano(quarter(C4)).pan(1.0);
+    ///
+    /// assert!(matches!(left.1, NoteKind::Pitched { pan: Some(-1.0), .. }));
+    /// ```
+    pub fn pan(&self, pan: f32) -> Note {
+        let new_note_kind = match self.1 {
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+                ..
+            } => NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan: Some(pan.clamp(-1.0, 1.0)),
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+            },
             NoteKind::Rest => NoteKind::Rest,
         };
 
         Note(self.0, new_note_kind)
     }
+
+    /// Creates a new note that's played the given number of milliseconds later than its place in time,
+    /// or earlier if the offset is negative. Rests are unaffected.
+    ///
+    /// The note's length and the timing of the notes around it don't change, so this is useful for
+    /// small deliberate pushes and drags that give music its groove. See [`nudge`](crate::nudge) for
+    /// nudging every note in a line.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let late = piano(quarter(C4)).nudge(15.0);
+    ///
+    /// assert!(matches!(late.1, NoteKind::Pitched { nudge_ms: 15.0, .. }));
+    /// ```
+    pub fn nudge(&self, offset_ms: f32) -> Note {
+        let new_note_kind = match self.1 {
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                glide,
+                lfo,
+                ..
+            } => NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms: offset_ms,
+                glide,
+                lfo,
+            },
+            NoteKind::Rest => NoteKind::Rest,
+        };
+
+        Note(self.0, new_note_kind)
+    }
+
+    /// Creates a new note that slides into its pitch at its start, or starts on its pitch with `None`.
+    /// Rests are unaffected.
+    ///
+    /// See [`portamento`](crate::portamento) for sliding between every note in a line.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scoop = sine(quarter(A4)).glide(Some(Glide {
+    ///     from: A4.semitone(-2),
+    ///     duration: Duration::from_millis(60),
+    /// }));
+    ///
+    /// assert!(matches!(scoop.1, NoteKind::Pitched { glide: Some(_), .. }));
+    /// ```
+    pub fn glide(&self, glide: Option<Glide>) -> Note {
+        match self.1 {
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                lfo,
+                ..
+            } => Note(
+                self.0,
+                NoteKind::Pitched {
+                    pitch,
+                    timbre,
+                    volume,
+                    pan,
+                    probability,
+                    nudge_ms,
+                    glide,
+                    lfo,
+                },
+            ),
+            NoteKind::Rest => *self,
+        }
+    }
+
+    /// Creates a new note whose level, pitch, or filter cutoff is wobbled by an oscillator, or not with `None`.
+    /// Rests are unaffected.
+    ///
+    /// See [`lfo`](crate::lfo) for applying an oscillator to every note in a line.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let tremolo = sine(whole(A4)).lfo(Some(Lfo {
+    ///     target: ModTarget::Amplitude,
+    ///     rate_beats: 0.5,
+    ///     depth: 0.3,
+    /// }));
+    ///
+    /// assert!(matches!(tremolo.1, NoteKind::Pitched { lfo: Some(_), .. }));
+    /// ```
+    pub fn lfo(&self, lfo: Option<Lfo>) -> Note {
+        match self.1 {
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                ..
+            } => Note(
+                self.0,
+                NoteKind::Pitched {
+                    pitch,
+                    timbre,
+                    volume,
+                    pan,
+                    probability,
+                    nudge_ms,
+                    glide,
+                    lfo,
+                },
+            ),
+            NoteKind::Rest => *self,
+        }
+    }
 }
 
 impl Add<Note> for Note {
@@ -106,7 +389,7 @@ impl Mul<Note> for Note {
         let self_line: Line = self.into();
         let rhs_line: Line = rhs.into();
 
-        Piece(vec![self_line, rhs_line])
+        Piece::from(vec![self_line, rhs_line])
     }
 }
 
@@ -121,7 +404,12 @@ impl Mul<Note> for Note {
 /// let a4_note = NoteKind::Pitched {
 ///     pitch: NotePitch(440.0),
 ///     timbre: Timbre::Piano,
-///     volume: 1.0
+///     volume: 1.0,
+///     pan: None,
+///     probability: 1.0,
+///     nudge_ms: 0.0,
+///     glide: None,
+///     lfo: None,
 /// };
 ///
 /// // Create a rest
@@ -143,6 +431,62 @@ pub enum NoteKind {
         timbre: Timbre,
         /// Volume level (0.0 = silent, 1.0 = full volume, can exceed 1.0)
         volume: f32,
+        /// Position across the output channels (-1.0 = first, 1.0 = last).
+        /// `None` spreads the note evenly across every channel.
+        pan: Option<f32>,
+        /// The chance that the note is played, from 0.0 (never) to 1.0 (always). See [`maybe`](crate::maybe)
+        probability: f32,
+        /// How far the note is moved from its place in time, in milliseconds. Negative values play it earlier.
+        /// See [`nudge`](crate::nudge)
+        nudge_ms: f32,
+        /// A slide into the note's pitch from another one at its start, or `None` to start on its pitch.
+        /// See [`portamento`](crate::portamento)
+        glide: Option<Glide>,
+        /// A wobble in the note's level, pitch, or filter cutoff, in time with the piece, or `None` for none.
+        /// See [`lfo`](crate::lfo)
+        lfo: Option<Lfo>,
+    },
+}
+
+/// A slide into a note's pitch from another pitch, over the start of the note.
+///
+/// The pitch moves evenly in semitones, so a slide up an octave passes the halfway point (a tritone up)
+/// halfway through.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glide {
+    /// The pitch the note starts at
+    pub from: NotePitch,
+    /// How long the slide takes to reach the note's own pitch
+    pub duration: Duration,
+}
+
+/// A low-frequency oscillator: a slow, steady wobble in a note's level, pitch, or filter cutoff, from [`lfo`](crate::lfo).
+///
+/// The wobble is a sine wave whose cycles are measured in beats, so it stays in time with the piece at any tempo.
+/// Its phase is measured from the start of the piece, so every note it's applied to follows the same wave.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lfo {
+    /// What the oscillator changes
+    pub target: ModTarget,
+    /// How many beats (quarter notes) each cycle lasts. Cycles that aren't positive don't change anything
+    pub rate_beats: f32,
+    /// How far the target swings either way. What this is measured in depends on the [`ModTarget`]
+    pub depth: f32,
+}
+
+/// What an [`Lfo`] changes, and what its depth is measured in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModTarget {
+    /// The note's level, which swings between `1.0 - depth` and `1.0 + depth` times its volume, like a tremolo.
+    /// The depth is clamped to between 0.0 and 1.0
+    Amplitude,
+    /// The note's pitch, which swings up and down by `depth` semitones, like a vibrato
+    Pitch,
+    /// The cutoff of a low-pass filter on the note, which swings up and down by `depth` octaves around `hz`,
+    /// for a wah-like sweep
+    FilterCutoff {
+        /// The filter's cutoff at the middle of its swing, in Hz
+        hz: f32,
     },
 }
 
@@ -152,6 +496,11 @@ impl From<NotePitch> for NoteKind {
             pitch: value,
             timbre: Timbre::default(),
             volume: 1.0,
+            pan: None,
+            probability: 1.0,
+            nudge_ms: 0.0,
+            glide: None,
+            lfo: None,
         }
     }
 }
@@ -218,6 +567,148 @@ impl NotePitch {
     pub fn frequency(&self) -> f32 {
         self.0
     }
+
+    /// The lowest pitch MIDI can represent: note number 0, C-1 (about 8.18 Hz).
+    pub const MIDI_LOWEST: NotePitch = NotePitch(8.175_799);
+
+    /// The highest pitch MIDI can represent: note number 127, G9 (about 12543.85 Hz).
+    pub const MIDI_HIGHEST: NotePitch = NotePitch(12_543.854);
+
+    /// Creates the pitch of a MIDI note number, where 60 is C4 and 69 is A4 (440 Hz).
+    ///
+    /// Every note number from 0 ([`NotePitch::MIDI_LOWEST`]) to 127 ([`NotePitch::MIDI_HIGHEST`])
+    /// is valid. Higher numbers aren't used by MIDI, but still give a pitch in 12-tone equal temperament.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(NotePitch::from_midi(69), A4);
+    /// assert!((NotePitch::from_midi(60).0 - C4.0).abs() < 0.01);
+    /// assert_eq!(NotePitch::from_midi(81), A4.octave(1));
+    /// ```
+    pub fn from_midi(note_number: u8) -> NotePitch {
+        NotePitch(A4.0 * 2.0f32.powf((f32::from(note_number) - 69.0) / 12.0))
+    }
+
+    /// Gets the MIDI note number nearest to this pitch, where 60 is C4 and 69 is A4 (440 Hz).
+    ///
+    /// Pitches outside of MIDI's range are clamped to it, so anything below
+    /// [`NotePitch::MIDI_LOWEST`] becomes 0 and anything above [`NotePitch::MIDI_HIGHEST`] becomes 127.
+    /// A pitch that isn't a positive frequency (such as zero or NaN) also becomes 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(C4.to_midi(), 60);
+    /// assert_eq!(A4.semitone(1).to_midi(), 70);
+    ///
+    /// // Out of range pitches are clamped
+    /// assert_eq!(NotePitch(1.0).to_midi(), 0);
+    /// assert_eq!(C4.octave(10).to_midi(), 127);
+    /// ```
+    pub fn to_midi(self) -> u8 {
+        let note_number = 69.0 + 12.0 * (self.0 / A4.0).log2();
+        if note_number.is_nan() {
+            return 0;
+        }
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Clamped to the range of a u8")]
+        let note_number = note_number.round().clamp(0.0, 127.0) as u8;
+        note_number
+    }
+
+    /// Snaps a frequency, in Hz, to the nearest semitone in 12-tone equal temperament, with A4 tuned to `a4`.
+    ///
+    /// This is useful for turning the output of pitch detection into notes. See [`NotePitch::from_frequency_with`]
+    /// to choose which way frequencies between two semitones are snapped, and
+    /// [`NotePitch::from_frequency_with_cents`] to find how far out of tune they were.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Slightly flat of A4, and slightly sharp of the C above it
+    /// assert_eq!(NotePitch::from_frequency(437.0, A4), A4);
+    /// assert_eq!(NotePitch::from_frequency(525.0, A4), A4.semitone(3));
+    /// ```
+    pub fn from_frequency(hz: f32, a4: NotePitch) -> NotePitch {
+        NotePitch::from_frequency_with(hz, a4, PitchRounding::Nearest)
+    }
+
+    /// Snaps a frequency, in Hz, to a semitone in 12-tone equal temperament, with A4 tuned to `a4`, rounding it as
+    /// `rounding` says.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A little sharp of A4
+    /// assert_eq!(NotePitch::from_frequency_with(445.0, A4, PitchRounding::Nearest), A4);
+    /// assert_eq!(NotePitch::from_frequency_with(445.0, A4, PitchRounding::Down), A4);
+    /// assert_eq!(NotePitch::from_frequency_with(445.0, A4, PitchRounding::Up), A4.semitone(1));
+    /// ```
+    pub fn from_frequency_with(hz: f32, a4: NotePitch, rounding: PitchRounding) -> NotePitch {
+        NotePitch::from_frequency_with_cents(hz, a4, rounding).0
+    }
+
+    /// Snaps a frequency, in Hz, to a semitone like [`NotePitch::from_frequency_with`], and also gets how far the
+    /// frequency is from that semitone, in cents.
+    ///
+    /// The cents are positive if the frequency is sharp of the pitch it snapped to, and negative if it's flat. There
+    /// are 100 cents in a semitone, so rounding to the nearest semitone is never more than 50 cents out. Frequencies
+    /// within a thousandth of a semitone of one count as exactly on it, so that rounding errors in a frequency
+    /// don't push it a whole semitone up or down. A frequency that isn't positive is returned as it is, 0 cents out.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // An A4 tuned to 442 Hz is about 8 cents sharp
+    /// let (pitch, cents) = NotePitch::from_frequency_with_cents(442.0, A4, PitchRounding::Nearest);
+    /// assert_eq!(pitch, A4);
+    /// assert!((cents - 7.85).abs() < 0.01);
+    ///
+    /// // But it's about 92 cents flat of the A# above
+    /// let (pitch, cents) = NotePitch::from_frequency_with_cents(442.0, A4, PitchRounding::Up);
+    /// assert_eq!(pitch, A4.semitone(1));
+    /// assert!((cents + 92.15).abs() < 0.01);
+    /// ```
+    pub fn from_frequency_with_cents(hz: f32, a4: NotePitch, rounding: PitchRounding) -> (NotePitch, f32) {
+        let semitones = 12.0 * (hz / a4.0).log2();
+        if !semitones.is_finite() {
+            return (NotePitch(hz), 0.0);
+        }
+
+        let nearest = semitones.round();
+        let semitones = if (semitones - nearest).abs() < 0.001 {
+            nearest
+        } else {
+            semitones
+        };
+        let snapped = match rounding {
+            // Halfway between two semitones always goes up, whichever side of A4 it's on
+            PitchRounding::Nearest => (semitones + 0.5).floor(),
+            PitchRounding::Down => semitones.floor(),
+            PitchRounding::Up => semitones.ceil(),
+        };
+        (
+            NotePitch(a4.0 * 2.0f32.powf(snapped / 12.0)),
+            (semitones - snapped) * 100.0,
+        )
+    }
+}
+
+/// Which way [`NotePitch::from_frequency_with`] snaps a frequency that's between two semitones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum PitchRounding {
+    /// Snaps to whichever semitone is closer, going up when it's exactly halfway
+    #[default]
+    Nearest,
+    /// Snaps to the semitone below, so the frequency is never flat of the pitch
+    Down,
+    /// Snaps to the semitone above, so the frequency is never sharp of the pitch
+    Up,
 }
 
 impl From<NoteLength> for u16 {
@@ -237,12 +728,29 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 
 impl Display for NotePitch {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        write!(
-            f,
-            "{}: {:.2}Hz",
-            crate::scales::tet12::get_note_name_with_octave(*self, A4),
-            self.0
-        )
+        write!(f, "{}", self.display_with(PitchNaming::Scientific))
+    }
+}
+
+impl NotePitch {
+    /// Formats the pitch like its `Display` output, with its name written in `naming`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(C4.display_with(PitchNaming::Scientific).to_string(), format!("{C4}"));
+    /// assert_eq!(C4.display_with(PitchNaming::Solfege).to_string(), "do: 261.63Hz");
+    /// ```
+    pub fn display_with(&self, naming: PitchNaming) -> impl Display + '_ {
+        DisplayWith(self, naming)
+    }
+}
+
+impl Display for DisplayWith<'_, NotePitch> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let DisplayWith(pitch, naming) = *self;
+        write!(f, "{}: {:.2}Hz", get_note_name_with_naming(*pitch, A4, naming), pitch.0)
     }
 }
 
@@ -253,6 +761,19 @@ impl Display for NoteLength {
 }
 
 impl NoteLength {
+    /// The length of a sixteenth note (1 time unit)
+    pub const SIXTEENTH: NoteLength = NoteLength(1);
+    /// The length of an eighth note (2 time units)
+    pub const EIGHTH: NoteLength = NoteLength(2);
+    /// The length of a quarter note (4 time units)
+    pub const QUARTER: NoteLength = NoteLength(4);
+    /// The length of a half note (8 time units)
+    pub const HALF: NoteLength = NoteLength(8);
+    /// The length of a whole note (16 time units)
+    pub const WHOLE: NoteLength = NoteLength(16);
+    /// The length of a double whole note (32 time units)
+    pub const DOUBLE_WHOLE: NoteLength = NoteLength(32);
+
     /// Creates a new `NoteLength` from a duration value.
     ///
     /// # Examples
@@ -274,6 +795,39 @@ impl NoteLength {
     pub fn duration(&self) -> u16 {
         self.0
     }
+
+    /// Gets the standard note value and number of dots that this length would be written as in sheet music.
+    ///
+    /// The result is the longest dotted note value that fits in the length, along with the remainder if
+    /// it doesn't fit exactly, which should be written as a tied note after it. The remainder can be notated
+    /// in turn, to find every note in the tie. Zero length notes can't be written, so they return `None`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert_eq!(NoteLength::QUARTER.to_notated(), Some((NoteValue::Quarter, 0, NoteLength(0))));
+    /// assert_eq!(NoteLength(6).to_notated(), Some((NoteValue::Quarter, 1, NoteLength(0))));
+    /// assert_eq!(NoteLength(14).to_notated(), Some((NoteValue::Half, 2, NoteLength(0))));
+    ///
+    /// // Five sixteenths are a quarter note tied to a sixteenth note
+    /// assert_eq!(NoteLength(5).to_notated(), Some((NoteValue::Quarter, 0, NoteLength(1))));
+    /// assert_eq!(NoteLength(0).to_notated(), None);
+    /// ```
+    pub fn to_notated(&self) -> Option<(NoteValue, u8, NoteLength)> {
+        let value = NoteValue::ALL.into_iter().find(|value| value.length() <= *self)?;
+
+        let mut written = value.length().0;
+        let mut dot = written;
+        let mut dots: u8 = 0;
+        // Each dot adds half of the last, and a dot shorter than a sixteenth note can't be represented
+        while dot % 2 == 0 && written.saturating_add(dot / 2) <= self.0 {
+            dot /= 2;
+            written = written.saturating_add(dot);
+            dots = dots.saturating_add(1);
+        }
+        Some((value, dots, NoteLength(self.0.saturating_sub(written))))
+    }
 }
 
 /// A constant representing a musical rest (silence).
@@ -18,6 +18,11 @@ use crate::{Line, Note, NoteKind, Piece};
 ///     pitch: NotePitch::new(440.0),
 ///     timbre: Timbre::Sine,
 ///     volume: 1.0,
+///     pan: None,
+///     probability: 1.0,
+///     nudge_ms: 0.0,
+///     glide: None,
+///     lfo: None,
 /// });
 /// ```
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
@@ -35,6 +40,20 @@ pub enum Timbre {
     /// Electric guitar sound - bright, sustained tones with distortion
     ElectricGuitar,
 
+    /// White noise - a hiss with no pitch, useful for percussion and effects.
+    ///
+    /// The note's pitch is ignored. The noise is random, but reproducible: see `MusicPlayer::with_seed`.
+    Noise,
+
+    /// Noise filtered around the note's pitch, for tuned percussion such as toms and woodblocks.
+    ///
+    /// `q` is how narrow the filter is: around 2 gives a breathy, barely pitched hit, while 20 or more
+    /// sounds close to a whistle. A `q` of 0 is treated as 1. See [`bandpass_noise`].
+    BandpassNoise {
+        /// The quality factor of the filter, the pitch divided by the width of the band it lets through
+        q: u8,
+    },
+
     /// Built-in drum kit sounds.
     ///
     /// The drum kit uses specific pitches to trigger different drum sounds:
@@ -69,6 +88,11 @@ pub enum Timbre {
     ///     pitch: A4, // Pitch ignored for unpitched sources
     ///     timbre: custom,
     ///     volume: 1.0,
+    ///     pan: None,
+    ///     probability: 1.0,
+    ///     nudge_ms: 0.0,
+    ///     glide: None,
+    ///     lfo: None,
     /// });
     /// ```
     CustomSourceUnpitched(&'static str),
@@ -88,9 +112,170 @@ pub enum Timbre {
     ///     pitch: NotePitch::new(440.0), // Will pitch-shift from C4 to A4
     ///     timbre: custom,
     ///     volume: 1.0,
+    ///     pan: None,
+    ///     probability: 1.0,
+    ///     nudge_ms: 0.0,
+    ///     glide: None,
+    ///     lfo: None,
     /// });
     /// ```
     CustomSourcePitched(&'static str),
+
+    /// Several timbres played together, as one richer sound.
+    ///
+    /// Every note plays through each of the timbres at once, at the note's pitch, and their outputs
+    /// are summed. Unlike stacking lines with `*`, this applies to every note uniformly, so a layered
+    /// timbre can be used anywhere a single one can.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A piano with a pure sine tone underneath
+    /// let layered = Timbre::Layered(&[Timbre::Piano, Timbre::Sine]);
+    /// let line = piano(quarter(C4) + quarter(A4)).with_timbre(layered);
+    /// ```
+    Layered(&'static [Timbre]),
+
+    /// A synthesized instrument, built from sine partials, an envelope, and a filter.
+    ///
+    /// See [`Instrument`], and the ready-made ones in [`presets`](crate::instrument_tools::presets).
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::instrument_tools::presets;
+    ///
+    /// let pad = quarter(C4).with_timbre(Timbre::Instrument(presets::strings()));
+    /// ```
+    Instrument(Instrument),
+}
+
+/// A synthesized instrument: a set of sine partials shaped by an envelope, and optionally filtered.
+///
+/// Every note is the sum of the partials, each at its own pitch relative to the note's. The envelope
+/// then rises to full level over the attack, falls to the sustain level over the decay, and fades
+/// out over the release at the end of the note. The player's envelope curves shape the attack and
+/// release, like they do for the built-in timbres.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A hollow, clarinet-like tone from odd harmonics only
+/// const PARTIALS: &[Partial] = &[
+///     Partial { cents: 0, level: 100 },
+///     Partial { cents: 1902, level: 40 },
+///     Partial { cents: 2786, level: 20 },
+/// ];
+/// let reed = Instrument {
+///     name: "reed",
+///     partials: PARTIALS,
+///     attack_ms: 30,
+///     decay_ms: 0,
+///     sustain: 100,
+///     release_ms: 60,
+///     low_pass_hz: Some(3000),
+///     midi_program: 71,
+///     transpose: 0,
+/// };
+/// let melody = instrument(reed, quarter(C4) + quarter(A4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instrument {
+    /// A name for the instrument, used when exporting notes and as the MIDI track name. Empty for no name
+    pub name: &'static str,
+    /// The sine waves that make up the sound
+    pub partials: &'static [Partial],
+    /// How long the sound takes to reach full level at the start of a note
+    pub attack_ms: u16,
+    /// How long the sound takes to fall from full level to the sustain level after the attack
+    pub decay_ms: u16,
+    /// The level the sound is held at after the decay, as a percentage of full level
+    pub sustain: u8,
+    /// How long the sound takes to fade out at the end of a note
+    pub release_ms: u16,
+    /// The cutoff of a low-pass filter applied to the sound, in Hz, or `None` for no filter
+    pub low_pass_hz: Option<u16>,
+    /// The General MIDI program notes with this instrument are exported as, zero indexed
+    pub midi_program: u8,
+    /// How many semitones above its written pitch the instrument sounds, or below if negative. See [`Instrument::with_transpose`]
+    pub transpose: i16,
+}
+
+impl Instrument {
+    /// Renames the instrument. The name is used for the track name when exporting MIDI, so it shows up in DAWs.
+    ///
+    /// An empty name leaves the instrument unnamed, and its MIDI track is given a default name such as "Instrument 1".
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::instrument_tools::presets;
+    ///
+    /// let lead = presets::organ().with_name("Lead organ");
+    /// assert_eq!(lead.name, "Lead organ");
+    /// ```
+    pub const fn with_name(self, name: &'static str) -> Self {
+        Instrument { name, ..self }
+    }
+
+    /// Makes the instrument a transposing instrument, which sounds `semitones` above the pitch its notes are written at.
+    ///
+    /// Like a real B♭ clarinet, which sounds a whole step below what's written, notes are written at the pitch the
+    /// player reads and rendered at concert pitch. Only the audio is transposed: exports such as [`Piece::to_midi`]
+    /// and [`Piece::to_event_json`] show the written pitch. Negative values sound below the written pitch.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::instrument_tools::presets;
+    ///
+    /// // Sounds as a B♭, a whole step below the written C
+    /// let clarinet = presets::organ().with_name("Clarinet in B♭").with_transpose(-2);
+    /// let melody = instrument(clarinet, quarter(C4) + quarter(C4.semitone(2)));
+    /// ```
+    ///
+    /// [`Piece::to_midi`]: crate::Piece::to_midi
+    /// [`Piece::to_event_json`]: crate::Piece::to_event_json
+    pub const fn with_transpose(self, semitones: i16) -> Self {
+        Instrument {
+            transpose: semitones,
+            ..self
+        }
+    }
+
+    /// Sets the General MIDI program the instrument's notes are exported as by [`Piece::to_midi`].
+    ///
+    /// Programs are zero indexed, so `0` is Acoustic Grand Piano and `80` is Lead 1 (square). Values above
+    /// 127 are clamped.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::instrument_tools::presets;
+    ///
+    /// let lead = presets::organ().with_name("Square lead").with_midi_program(80);
+    /// assert_eq!(lead.midi_program, 80);
+    /// assert_eq!(presets::organ().with_midi_program(200).midi_program, 127);
+    /// ```
+    ///
+    /// [`Piece::to_midi`]: crate::Piece::to_midi
+    pub const fn with_midi_program(self, program: u8) -> Self {
+        Instrument {
+            midi_program: if program > 127 { 127 } else { program },
+            ..self
+        }
+    }
+}
+
+/// One sine wave in an [`Instrument`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Partial {
+    /// The partial's pitch above the note's, in cents (hundredths of a semitone).
+    ///
+    /// 0 is the note's pitch, 1200 an octave above it, and 1902 the third harmonic. Negative values are below it.
+    pub cents: i16,
+    /// How loud the partial is, relative to the others
+    pub level: u8,
 }
 
 /// A trait for types that can have their timbre (sound characteristics) modified.
@@ -128,7 +313,25 @@ pub trait TimbreFluid {
 impl TimbreFluid for NoteKind {
     fn with_timbre(self, timbre: Timbre) -> Self {
         match self {
-            NoteKind::Pitched { pitch, volume, .. } => NoteKind::Pitched { pitch, timbre, volume },
+            NoteKind::Pitched {
+                pitch,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+                ..
+            } => NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+            },
             NoteKind::Rest => NoteKind::Rest,
         }
     }
@@ -146,13 +349,17 @@ impl TimbreFluid for Line {
             notes: self.notes.into_iter().map(|n| n.with_timbre(timbre)).collect(),
             pickup: self.pickup.into_iter().map(|n| n.with_timbre(timbre)).collect(),
             hold_pickup: self.hold_pickup,
+            labels: self.labels,
         }
     }
 }
 
 impl TimbreFluid for Piece {
     fn with_timbre(self, timbre: Timbre) -> Self {
-        Piece(self.0.into_iter().map(|line| line.with_timbre(timbre)).collect())
+        Piece {
+            lines: self.lines.into_iter().map(|line| line.with_timbre(timbre)).collect(),
+            metadata: self.metadata,
+        }
     }
 }
 
@@ -172,5 +379,34 @@ builtin_timbre_fns!(
     bass, Bass, "Applies a bass guitar timbre - deep, rich low-frequency tones.";
     piano, Piano, "Applies a piano timbre - complex harmonic structure with natural decay.";
     electric_guitar, ElectricGuitar, "Applies an electric guitar timbre - bright, sustained tones with distortion.";
-    drums, Drums, "Applies a drum kit timbre - use specific pitches to trigger different drum sounds."
+    drums, Drums, "Applies a drum kit timbre - use specific pitches to trigger different drum sounds.";
+    noise, Noise, "Applies a white noise timbre - an unpitched hiss, useful for percussion and effects."
 );
+
+/// Applies a noise timbre filtered around each note's pitch, giving pitched noise hits.
+///
+/// See [`Timbre::BandpassNoise`] for what `q` does.
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A pair of toms, a fourth apart
+/// let toms = bandpass_noise(8, eighth(C4) + eighth(C4.semitone(-5)));
+/// ```
+pub fn bandpass_noise<T: TimbreFluid>(q: u8, timbre_haver: T) -> T {
+    timbre_haver.with_timbre(Timbre::BandpassNoise { q })
+}
+
+/// Applies a synthesized instrument's timbre, such as one of the [`presets`](crate::instrument_tools::presets).
+///
+/// # Example
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::instrument_tools::presets;
+///
+/// let chimes = instrument(presets::bell(), quarter(C4.octave(1)) + quarter(A4.octave(1)));
+/// ```
+pub fn instrument<T: TimbreFluid>(instrument: Instrument, timbre_haver: T) -> T {
+    timbre_haver.with_timbre(Timbre::Instrument(instrument))
+}
@@ -0,0 +1,233 @@
+use std::ops::Range;
+
+use crate::{NoteLength, Piece};
+
+impl Piece {
+    /// Marks the start of the piece as a pickup (anacrusis): an incomplete bar before the first downbeat.
+    ///
+    /// The notes don't change. Instead, the pickup is recorded in the piece's
+    /// [`pickup_length`](crate::Metadata::pickup_length), so it's kept when the piece is transformed, and
+    /// [`Piece::bars`] starts counting full bars after it. Setting a new pickup replaces the old one, and a length of zero removes it.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let tune = Piece::from(piano(quarter(C4) + whole(A4)));
+    /// assert_eq!(tune.pickup_length(), 0);
+    ///
+    /// let tune = tune.with_pickup(NoteLength::QUARTER);
+    /// assert_eq!(tune.pickup_length(), 4);
+    /// ```
+    pub fn with_pickup(mut self, length: NoteLength) -> Piece {
+        self.metadata.pickup_length = usize::from(length.0);
+        self
+    }
+
+    /// Gets the length of the pickup set with [`Piece::with_pickup`], in time units, or zero if there isn't one.
+    pub fn pickup_length(&self) -> usize {
+        self.metadata.pickup_length
+    }
+
+    /// Splits the piece into bars of the given length, returning the time span of each bar.
+    ///
+    /// If the piece has a pickup, it's returned as a shorter first bar, and the first full bar starts
+    /// on the downbeat after it. The last bar is cut short if the piece ends partway through it.
+    /// A bar length of zero is treated as one time unit.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // A quarter note pickup, then two bars of 4/4
+    /// let tune = Piece::from(piano(quarter(C4) + whole(A4) + half(C4) + half(A4))).with_pickup(NoteLength::QUARTER);
+    ///
+    /// let bars: Vec<_> = tune.bars(NoteLength::WHOLE).collect();
+    /// assert_eq!(bars, [0..4, 4..20, 20..36]);
+    ///
+    /// // The first downbeat is after the pickup, at the start of the A4
+    /// let downbeat = bars[1].start;
+    /// assert_eq!(tune.get_notes_at_instant(downbeat).next().and_then(|note| note.pitch()), Some(A4));
+    ///
+    /// // Without a pickup, every bar is full length
+    /// let bars: Vec<_> = Piece::from(piano(whole(C4) * 2)).bars(NoteLength::WHOLE).collect();
+    /// assert_eq!(bars, [0..16, 16..32]);
+    /// ```
+    pub fn bars(&self, bar_length: NoteLength) -> impl Iterator<Item = Range<usize>> {
+        let bar_length = usize::from(bar_length.0.max(1));
+        let length = self.length();
+        let pickup = self.pickup_length().min(length);
+
+        let pickup_bar = (pickup > 0).then_some(0..pickup);
+        let full_bars = (pickup..length)
+            .step_by(bar_length)
+            .map(move |start| start..start.saturating_add(bar_length).min(length));
+        pickup_bar.into_iter().chain(full_bars)
+    }
+
+    /// Splits the piece into bars like [`Piece::bars`], with a time signature that changes partway through.
+    ///
+    /// The piece starts in `initial`, and each of `changes` switches to a new time signature from the
+    /// given time unit onwards. Every change has to be at the start of a bar, counting bars after the pickup
+    /// as usual, and a change at time 0 applies from the first downbeat. The changes don't have to be in order.
+    ///
+    /// # Errors
+    /// Returns an error if a change isn't at the start of a bar, or a time signature can't be counted in
+    /// sixteenth notes, such as 5/32.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MeterError, TimeSignature};
+    ///
+    /// // Two bars of 4/4, then two bars of 3/4
+    /// let tune = Piece::from(piano(whole(C4) * 2 + dotted(half)(A4) * 2));
+    /// let waltz = [(32, TimeSignature::new(3, 4))];
+    ///
+    /// let bars = tune.bars_with_meter(TimeSignature::COMMON, &waltz).unwrap();
+    /// assert_eq!(bars, [0..16, 16..32, 32..44, 44..56]);
+    ///
+    /// // Changing halfway through a bar isn't allowed
+    /// let early = [(24, TimeSignature::new(3, 4))];
+    /// assert_eq!(
+    ///     tune.bars_with_meter(TimeSignature::COMMON, &early),
+    ///     Err(MeterError::Unaligned { time: 24 })
+    /// );
+    /// ```
+    pub fn bars_with_meter(
+        &self,
+        initial: TimeSignature,
+        changes: &[(usize, TimeSignature)],
+    ) -> Result<Vec<Range<usize>>, MeterError> {
+        if let Some(&invalid) = std::iter::once(&initial)
+            .chain(changes.iter().map(|(_, signature)| signature))
+            .find(|signature| signature.bar_length().is_none())
+        {
+            return Err(MeterError::InvalidSignature(invalid));
+        }
+
+        let length = self.length();
+        let pickup = self.pickup_length().min(length);
+        let mut bars: Vec<_> = (pickup > 0).then_some(0..pickup).into_iter().collect();
+
+        let mut changes = changes.to_vec();
+        changes.sort_by_key(|&(time, _)| time);
+        let mut changes = changes.into_iter().peekable();
+
+        let mut signature = initial;
+        while let Some((_, change)) = changes.next_if(|&(time, _)| time == 0) {
+            signature = change;
+        }
+
+        // Keep counting bars past the end, so that changes after it are still checked
+        let mut start = pickup;
+        loop {
+            while let Some((time, change)) = changes.next_if(|&(time, _)| time <= start) {
+                if time < start {
+                    return Err(MeterError::Unaligned { time });
+                }
+                signature = change;
+            }
+            if start >= length && changes.peek().is_none() {
+                return Ok(bars);
+            }
+
+            let end = start.saturating_add(usize::from(signature.bar_length().map_or(1, |length| length.0)));
+            if start < length {
+                bars.push(start..end.min(length));
+            }
+            start = end;
+        }
+    }
+}
+
+/// A time signature, such as 4/4 or 6/8, for splitting a piece into bars. See [`Piece::bars_with_meter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimeSignature {
+    /// How many beats are in each bar, the top number
+    pub beats: u16,
+    /// Which note value gets one beat, the bottom number: 4 for quarter notes, 8 for eighth notes, and so on
+    pub beat_value: u16,
+}
+
+impl TimeSignature {
+    /// Common time, 4/4
+    pub const COMMON: TimeSignature = TimeSignature::new(4, 4);
+
+    /// Creates a time signature of `beats` notes of `beat_value` per bar.
+    pub const fn new(beats: u16, beat_value: u16) -> Self {
+        TimeSignature { beats, beat_value }
+    }
+
+    /// Gets the length of one bar, or `None` if it can't be counted in sixteenth notes or is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::TimeSignature;
+    ///
+    /// assert_eq!(TimeSignature::COMMON.bar_length(), Some(NoteLength::WHOLE));
+    /// assert_eq!(TimeSignature::new(6, 8).bar_length(), Some(NoteLength(12)));
+    /// assert_eq!(TimeSignature::new(3, 32).bar_length(), None);
+    /// ```
+    pub fn bar_length(self) -> Option<NoteLength> {
+        let beat = 16u16.checked_div(self.beat_value)?;
+        if beat.checked_mul(self.beat_value) != Some(16) || self.beats == 0 {
+            return None;
+        }
+        beat.checked_mul(self.beats).map(NoteLength)
+    }
+}
+
+/// An error from splitting a piece into bars with [`Piece::bars_with_meter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MeterError {
+    /// A time signature change wasn't at the start of a bar
+    Unaligned {
+        /// The time unit the change was at
+        time: usize,
+    },
+    /// A time signature has no beats, or its beats can't be counted in sixteenth notes
+    InvalidSignature(TimeSignature),
+}
+
+impl std::fmt::Display for MeterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MeterError::Unaligned { time } => {
+                write!(
+                    f,
+                    "time signature change at time unit {time} is not at the start of a bar"
+                )
+            }
+            MeterError::InvalidSignature(signature) => {
+                write!(
+                    f,
+                    "time signature {}/{} is not supported",
+                    signature.beats, signature.beat_value
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeterError {}
+
+#[test]
+fn test_pickup_follows_the_piece_through_transformations() {
+    use crate::prelude::*;
+
+    let tune = piano(quarter(C4) + whole(A4)) * bass(whole(C4.octave(-1)) + quarter(REST));
+    let tune = tune.with_pickup(NoteLength::QUARTER);
+
+    assert_eq!(tune.volume(0.5).pickup_length(), 4);
+    assert_eq!(tune.scale_time(2.0).pickup_length(), 8);
+    assert_eq!(tune.slice(0..2).pickup_length(), 2);
+    assert_eq!(tune.slice(4..20).pickup_length(), 0);
+    assert_eq!(tune.clone().reverse().pickup_length(), 0);
+
+    // The pickup is stored once for the piece, so adding a line doesn't change it
+    let with_drums = tune * Line::from(sine(whole(C4.octave(-2))));
+    assert_eq!(with_drums.pickup_length(), 4);
+    assert_eq!(with_drums.bars(NoteLength::WHOLE).next(), Some(0..4));
+}
@@ -0,0 +1,439 @@
+use std::{io, time::Duration};
+
+use crate::{
+    note::{Glide, Instrument, Lfo, ModTarget, NoteKind, Partial, Timbre},
+    Label, Line, Metadata, Note, NoteLength, NotePitch, Piece,
+};
+
+/// The bytes every saved piece starts with
+const MAGIC: &[u8; 4] = b"SYPX";
+
+/// The version of the format written by [`Piece::save`]. Increase this whenever the format changes.
+const FORMAT_VERSION: u16 = 1;
+
+impl Piece {
+    /// Saves the piece to a file in a compact binary format, which can be loaded with [`Piece::load`].
+    ///
+    /// The file starts with a version number, so files saved by later versions of symphoxy can be told apart.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(A4)) * bass(half(C4.octave(-1)));
+    /// let path = std::env::temp_dir().join("symphoxy_save_example.sypx");
+    ///
+    /// piece.save(&path).unwrap();
+    /// assert_eq!(Piece::load(&path).unwrap(), piece);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_bytes())
+    }
+
+    /// Loads a piece saved with [`Piece::save`].
+    ///
+    /// Custom source paths, layered timbres, and instruments are stored as `&'static` values, so loading
+    /// a piece that uses them leaks a small amount of memory for each one.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read, isn't a saved piece, or was saved with a newer
+    /// version of the format than this version of symphoxy supports.
+    pub fn load(path: impl AsRef<std::path::Path>) -> io::Result<Piece> {
+        Piece::from_bytes(&std::fs::read(path)?)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        write_len(&mut bytes, self.lines.len());
+        for line in &self.lines {
+            write_notes(&mut bytes, &line.pickup);
+            bytes.push(u8::from(line.hold_pickup));
+            write_notes(&mut bytes, &line.notes);
+            write_len(&mut bytes, line.labels.len());
+            for label in &line.labels {
+                write_str(&mut bytes, &label.name);
+                bytes.extend_from_slice(&(label.start as u64).to_le_bytes());
+                bytes.extend_from_slice(&(label.end as u64).to_le_bytes());
+            }
+        }
+        for field in [&self.metadata().title, &self.metadata().composer] {
+            match field {
+                None => bytes.push(0),
+                Some(text) => {
+                    bytes.push(1);
+                    write_str(&mut bytes, text);
+                }
+            }
+        }
+        write_len(&mut bytes, self.metadata().comments.len());
+        for comment in &self.metadata().comments {
+            write_str(&mut bytes, comment);
+        }
+        bytes.extend_from_slice(&(self.metadata().pickup_length as u64).to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> io::Result<Piece> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(invalid_data("not a saved symphoxy piece".to_string()));
+        }
+        let version = reader.u16()?;
+        if version > FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "piece was saved with format version {version}, but this version of symphoxy only supports up to version {FORMAT_VERSION}"
+            )));
+        }
+
+        let lines = (0..reader.u32()?)
+            .map(|_| {
+                let pickup = reader.notes()?;
+                let hold_pickup = reader.u8()? != 0;
+                let notes = reader.notes()?;
+                let labels = (0..reader.u32()?)
+                    .map(|_| {
+                        Ok(Label {
+                            name: reader.string()?,
+                            start: reader.usize()?,
+                            end: reader.usize()?,
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+                Ok(Line {
+                    notes,
+                    pickup,
+                    hold_pickup,
+                    labels,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+
+        let metadata = Metadata {
+            title: reader.optional_string()?,
+            composer: reader.optional_string()?,
+            comments: (0..reader.u32()?).map(|_| reader.string()).collect::<io::Result<_>>()?,
+            pickup_length: reader.usize()?,
+        };
+
+        if !reader.bytes.is_empty() {
+            return Err(invalid_data("unexpected data after the end of the piece".to_string()));
+        }
+        Ok(Piece { lines, metadata })
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_len(bytes: &mut Vec<u8>, len: usize) {
+    bytes.extend_from_slice(&u32::try_from(len).unwrap_or(u32::MAX).to_le_bytes());
+}
+
+fn write_str(bytes: &mut Vec<u8>, value: &str) {
+    write_len(bytes, value.len());
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+fn write_notes(bytes: &mut Vec<u8>, notes: &[Note]) {
+    write_len(bytes, notes.len());
+    for note in notes {
+        bytes.extend_from_slice(&note.0 .0.to_le_bytes());
+        match note.1 {
+            NoteKind::Rest => bytes.push(0),
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+            } => {
+                bytes.push(1);
+                bytes.extend_from_slice(&pitch.0.to_le_bytes());
+                write_timbre(bytes, timbre);
+                bytes.extend_from_slice(&volume.to_le_bytes());
+                match pan {
+                    None => bytes.push(0),
+                    Some(pan) => {
+                        bytes.push(1);
+                        bytes.extend_from_slice(&pan.to_le_bytes());
+                    }
+                }
+                bytes.extend_from_slice(&probability.to_le_bytes());
+                bytes.extend_from_slice(&nudge_ms.to_le_bytes());
+                match glide {
+                    None => bytes.push(0),
+                    Some(glide) => {
+                        bytes.push(1);
+                        bytes.extend_from_slice(&glide.from.0.to_le_bytes());
+                        bytes.extend_from_slice(&glide.duration.as_secs_f32().to_le_bytes());
+                    }
+                }
+                match lfo {
+                    None => bytes.push(0),
+                    Some(lfo) => {
+                        match lfo.target {
+                            ModTarget::Amplitude => bytes.push(1),
+                            ModTarget::Pitch => bytes.push(2),
+                            ModTarget::FilterCutoff { hz } => {
+                                bytes.push(3);
+                                bytes.extend_from_slice(&hz.to_le_bytes());
+                            }
+                        }
+                        bytes.extend_from_slice(&lfo.rate_beats.to_le_bytes());
+                        bytes.extend_from_slice(&lfo.depth.to_le_bytes());
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn write_timbre(bytes: &mut Vec<u8>, timbre: Timbre) {
+    match timbre {
+        Timbre::Sine => bytes.push(0),
+        Timbre::Bass => bytes.push(1),
+        Timbre::Piano => bytes.push(2),
+        Timbre::ElectricGuitar => bytes.push(3),
+        Timbre::Noise => bytes.push(4),
+        Timbre::Drums => bytes.push(5),
+        Timbre::CustomSourceUnpitched(path) => {
+            bytes.push(6);
+            write_str(bytes, path);
+        }
+        Timbre::CustomSourcePitched(path) => {
+            bytes.push(7);
+            write_str(bytes, path);
+        }
+        Timbre::Layered(timbres) => {
+            bytes.push(8);
+            write_len(bytes, timbres.len());
+            for &timbre in timbres {
+                write_timbre(bytes, timbre);
+            }
+        }
+        Timbre::BandpassNoise { q } => {
+            bytes.extend_from_slice(&[9, q]);
+        }
+        Timbre::Instrument(instrument) => {
+            bytes.push(10);
+            write_str(bytes, instrument.name);
+            write_len(bytes, instrument.partials.len());
+            for partial in instrument.partials {
+                bytes.extend_from_slice(&partial.cents.to_le_bytes());
+                bytes.push(partial.level);
+            }
+            bytes.extend_from_slice(&instrument.attack_ms.to_le_bytes());
+            bytes.extend_from_slice(&instrument.decay_ms.to_le_bytes());
+            bytes.push(instrument.sustain);
+            bytes.extend_from_slice(&instrument.release_ms.to_le_bytes());
+            match instrument.low_pass_hz {
+                Some(cutoff) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(&cutoff.to_le_bytes());
+                }
+                None => bytes.push(0),
+            }
+            bytes.push(instrument.midi_program);
+            bytes.extend_from_slice(&instrument.transpose.to_le_bytes());
+        }
+    }
+}
+
+/// Reads values from the front of a byte slice
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "saved piece ended early"));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn usize(&mut self) -> io::Result<usize> {
+        usize::try_from(u64::from_le_bytes(self.array()?)).map_err(|_| invalid_data("time is too large".to_string()))
+    }
+
+    fn f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_le_bytes(self.array()?))
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|_| invalid_data("text is not valid UTF-8".to_string()))
+    }
+
+    fn optional_string(&mut self) -> io::Result<Option<String>> {
+        match self.u8()? {
+            0 => Ok(None),
+            _ => self.string().map(Some),
+        }
+    }
+
+    fn notes(&mut self) -> io::Result<Vec<Note>> {
+        (0..self.u32()?)
+            .map(|_| {
+                let length = NoteLength(self.u16()?);
+                let kind = match self.u8()? {
+                    0 => NoteKind::Rest,
+                    1 => NoteKind::Pitched {
+                        pitch: NotePitch(self.f32()?),
+                        timbre: self.timbre()?,
+                        volume: self.f32()?,
+                        pan: match self.u8()? {
+                            0 => None,
+                            _ => Some(self.f32()?),
+                        },
+                        probability: self.f32()?,
+                        nudge_ms: self.f32()?,
+                        glide: match self.u8()? {
+                            0 => None,
+                            _ => Some(Glide {
+                                from: NotePitch(self.f32()?),
+                                duration: Duration::try_from_secs_f32(self.f32()?)
+                                    .map_err(|_| invalid_data("glide duration is invalid".to_string()))?,
+                            }),
+                        },
+                        lfo: match self.u8()? {
+                            0 => None,
+                            target => {
+                                let target = match target {
+                                    1 => ModTarget::Amplitude,
+                                    2 => ModTarget::Pitch,
+                                    3 => ModTarget::FilterCutoff { hz: self.f32()? },
+                                    target => return Err(invalid_data(format!("unknown LFO target {target}"))),
+                                };
+                                Some(Lfo {
+                                    target,
+                                    rate_beats: self.f32()?,
+                                    depth: self.f32()?,
+                                })
+                            }
+                        },
+                    },
+                    kind => return Err(invalid_data(format!("unknown note kind {kind}"))),
+                };
+                Ok(Note(length, kind))
+            })
+            .collect()
+    }
+
+    fn timbre(&mut self) -> io::Result<Timbre> {
+        Ok(match self.u8()? {
+            0 => Timbre::Sine,
+            1 => Timbre::Bass,
+            2 => Timbre::Piano,
+            3 => Timbre::ElectricGuitar,
+            4 => Timbre::Noise,
+            5 => Timbre::Drums,
+            6 => Timbre::CustomSourceUnpitched(self.string()?.leak()),
+            7 => Timbre::CustomSourcePitched(self.string()?.leak()),
+            8 => {
+                let timbres = (0..self.u32()?).map(|_| self.timbre()).collect::<io::Result<Vec<_>>>()?;
+                Timbre::Layered(timbres.leak())
+            }
+            9 => Timbre::BandpassNoise { q: self.u8()? },
+            10 => {
+                let name = self.string()?.leak();
+                let partials = (0..self.u32()?)
+                    .map(|_| {
+                        Ok(Partial {
+                            cents: i16::from_le_bytes(self.array()?),
+                            level: self.u8()?,
+                        })
+                    })
+                    .collect::<io::Result<Vec<_>>>()?;
+                Timbre::Instrument(Instrument {
+                    name,
+                    partials: partials.leak(),
+                    attack_ms: self.u16()?,
+                    decay_ms: self.u16()?,
+                    sustain: self.u8()?,
+                    release_ms: self.u16()?,
+                    low_pass_hz: match self.u8()? {
+                        0 => None,
+                        _ => Some(self.u16()?),
+                    },
+                    midi_program: self.u8()?,
+                    transpose: i16::from_le_bytes(self.array()?),
+                })
+            }
+            timbre => return Err(invalid_data(format!("unknown timbre {timbre}"))),
+        })
+    }
+}
+
+#[test]
+fn test_load_rejects_newer_versions() {
+    let piece = Piece::from(crate::prelude::piano(crate::prelude::quarter(crate::C4)));
+    let mut bytes = piece.to_bytes();
+    assert_eq!(Piece::from_bytes(&bytes).unwrap(), piece);
+
+    bytes[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    let error = Piece::from_bytes(&bytes).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("format version 2"));
+}
+
+#[test]
+fn test_metadata_round_trips() {
+    let piece = Piece::from(crate::prelude::piano(crate::prelude::quarter(crate::C4))).with_metadata(Metadata {
+        title: Some("Prelude".to_string()),
+        composer: None,
+        comments: vec!["Play softly".to_string(), String::new()],
+        pickup_length: 0,
+    });
+    assert_eq!(Piece::from_bytes(&piece.to_bytes()).unwrap(), piece);
+}
+
+#[test]
+fn test_pickup_round_trips() {
+    use crate::prelude::*;
+
+    let piece = (piano(quarter(C4) + whole(A4)) * bass(whole(C4.octave(-1)) * 2)).with_pickup(NoteLength::QUARTER);
+    let loaded = Piece::from_bytes(&piece.to_bytes()).unwrap();
+    assert_eq!(loaded, piece);
+    assert_eq!(loaded.pickup_length(), 4);
+}
+
+#[test]
+fn test_lfos_round_trip() {
+    use crate::prelude::*;
+
+    let piece = lfo(sine(half(C4)), ModTarget::FilterCutoff { hz: 900.0 }, 2.0, 1.5)
+        * lfo(piano(half(A4)), ModTarget::Pitch, 0.25, 0.5)
+        * lfo(bass(half(C4.octave(-1))), ModTarget::Amplitude, 1.0, 0.3);
+    assert_eq!(Piece::from_bytes(&piece.to_bytes()).unwrap(), piece);
+}
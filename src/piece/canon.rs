@@ -0,0 +1,60 @@
+use crate::{Line, Note, NoteKind, NoteLength, Piece, Tet12};
+
+/// Makes a canon, or round, from a single line: `voices` copies of it in parallel, each entering `offset` after the one before.
+///
+/// The first voice is the line itself. Each later voice is also transposed `interval` semitones further
+/// than the voice before it, so an interval of -12 has every voice enter an octave below the last, and 0
+/// gives a round where every voice sings the same pitches. Each voice is its own line of the piece, and
+/// the piece lasts until the last voice finishes.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // "Frère Jacques", with the second voice a bar later and a fifth higher
+/// let tune = piano(quarter(C4) + quarter(C4.semitone(2)) + quarter(C4.semitone(4)) + quarter(C4));
+/// let round = canon(tune.clone(), 3, NoteLength(16), 7);
+///
+/// assert_eq!(round.lines.len(), 3);
+/// assert_eq!(round.lines[1], whole(REST) + tune.semitone(7));
+/// assert_eq!(round.length(), 48);
+/// ```
+pub fn canon(line: impl Into<Line>, voices: u32, offset: NoteLength, interval: i16) -> Piece {
+    let line: Line = line.into();
+    let mut transposition = 0i16;
+    let lines = (0..voices)
+        .map(|voice| {
+            // One rest for each voice that entered before, so long delays can't overflow a note's length
+            let entrance: Line = (0..voice).map(|_| Note(offset, NoteKind::Rest)).sum();
+            #[expect(clippy::arithmetic_side_effects, reason = "Line concatenation")]
+            let copy = entrance + line.semitone(transposition);
+            transposition = transposition.saturating_add(interval);
+            copy
+        })
+        .collect::<Vec<Line>>();
+    Piece::from(lines)
+}
+
+#[test]
+fn test_two_voice_canon_adds_a_delayed_transposed_copy() {
+    use crate::prelude::*;
+
+    let subject = piano(quarter(C4) + eighth(A4) + eighth(REST) + half(C4.semitone(5)));
+    let duet = canon(subject.clone(), 2, NoteLength(8), -12);
+
+    assert_eq!(duet.lines.len(), 2);
+    assert_eq!(duet.lines[0], subject);
+    assert_eq!(duet.lines[1], half(REST) + octave_down(subject.clone()));
+    assert_eq!(duet.length(), subject.length() + 8);
+
+    // The second voice enters with the leader's last note, and they play together
+    let entering: Vec<_> = duet
+        .notes()
+        .filter(|timed| timed.start == 8)
+        .map(|timed| timed.note)
+        .collect();
+    assert_eq!(entering, [piano(half(C4.semitone(5))), piano(quarter(C4.octave(-1)))]);
+    assert_eq!(duet.notes_in_range(8, 16).len(), 3);
+
+    assert!(canon(subject, 0, NoteLength(8), 7).lines.is_empty());
+}
@@ -0,0 +1,55 @@
+use crate::{Line, Note, NoteKind};
+
+/// Makes every note in a line play only some of the time, for generative or aleatoric music.
+///
+/// Each time the line is rendered, every note is played with the given probability, from 0.0
+/// (never) to 1.0 (always). Which notes play is decided by the player's seed, so rendering with
+/// the same seed always gives the same result (see `MusicPlayer::with_seed`). A note that is
+/// skipped is silent for its length, so the timing of the rest of the line is unchanged.
+///
+/// Applying `maybe` to a line that already has probabilities multiplies them together.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A hi-hat pattern where the offbeats only play half of the time
+/// let hat = drums(eighth(C4.octave(-1)));
+/// let pattern = (hat + maybe(hat, 0.5)) * 4;
+///
+/// let twice = maybe(maybe(piano(quarter(C4)), 0.5), 0.5);
+/// assert!(matches!(twice.notes[0].1, NoteKind::Pitched { probability: 0.25, .. }));
+/// ```
+pub fn maybe(line: impl Into<Line>, probability: f32) -> Line {
+    let line: Line = line.into();
+    let with_chance = |note: &Note| match note.1 {
+        NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            pan,
+            probability: existing,
+            nudge_ms,
+            glide,
+            lfo,
+        } => Note(
+            note.0,
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability: (existing * probability).clamp(0.0, 1.0),
+                nudge_ms,
+                glide,
+                lfo,
+            },
+        ),
+        NoteKind::Rest => *note,
+    };
+    Line {
+        notes: line.notes.iter().map(with_chance).collect(),
+        pickup: line.pickup.iter().map(with_chance).collect(),
+        ..line
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::Write;
+
+use crate::{
+    note::{NoteKind, Timbre},
+    scales::tet12::A4,
+    Piece,
+};
+
+/// One row of the event export.
+struct Event {
+    start_beat: usize,
+    duration_beats: u16,
+    pitch_midi: i32,
+    frequency: f32,
+    velocity: f32,
+    instrument: String,
+}
+
+impl Piece {
+    /// Exports every note in the piece as a JSON array of event objects.
+    ///
+    /// Each object has the fields `start_beat`, `duration_beats`, `pitch_midi`,
+    /// `frequency`, `velocity`, and `instrument`:
+    /// - Times are in the piece's time units (sixteenth notes), the same units as [`NoteLength`](crate::NoteLength).
+    /// - `pitch_midi` is the nearest MIDI note number, where A4 (440 Hz) is 69.
+    /// - `velocity` is the note's volume multiplier.
+    /// - `instrument` is the name of the timbre, such as `"piano"`, or the file path for custom sources.
+    ///
+    /// There is one event per note returned by [`Piece::notes`].
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + half(A4)));
+    /// let json = piece.to_event_json();
+    ///
+    /// assert!(json.starts_with('['));
+    /// assert!(json.contains(r#"{"start_beat":4,"duration_beats":8,"pitch_midi":69,"frequency":440,"velocity":1,"instrument":"piano"}"#));
+    /// ```
+    pub fn to_event_json(&self) -> String {
+        let objects: Vec<String> = self
+            .events()
+            .map(|event| {
+                format!(
+                    r#"{{"start_beat":{},"duration_beats":{},"pitch_midi":{},"frequency":{},"velocity":{},"instrument":"{}"}}"#,
+                    event.start_beat,
+                    event.duration_beats,
+                    event.pitch_midi,
+                    event.frequency,
+                    event.velocity,
+                    escape_json(&event.instrument),
+                )
+            })
+            .collect();
+
+        format!("[{}]", objects.join(","))
+    }
+
+    /// Exports every note in the piece as CSV, with a header row and one row per note.
+    ///
+    /// The columns are `start_beat`, `duration_beats`, `pitch_midi`, `frequency`, `velocity`,
+    /// and `instrument`. See [`Piece::to_event_json`] for what each column means.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + half(A4)) * bass(whole(C4.octave(-1)));
+    /// let csv = piece.to_event_csv();
+    /// let rows: Vec<&str> = csv.lines().collect();
+    ///
+    /// assert_eq!(rows[0], "start_beat,duration_beats,pitch_midi,frequency,velocity,instrument");
+    /// assert_eq!(rows.len() - 1, piece.notes().count());
+    /// assert_eq!(rows[2], "4,8,69,440,1,piano");
+    /// ```
+    pub fn to_event_csv(&self) -> String {
+        let mut csv = String::from("start_beat,duration_beats,pitch_midi,frequency,velocity,instrument\n");
+
+        for event in self.events() {
+            // Writing to a string can't fail
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                event.start_beat,
+                event.duration_beats,
+                event.pitch_midi,
+                event.frequency,
+                event.velocity,
+                escape_csv(&event.instrument),
+            );
+        }
+
+        csv
+    }
+
+    fn events(&self) -> impl Iterator<Item = Event> + '_ {
+        self.notes().filter_map(|timed| match timed.note.1 {
+            NoteKind::Pitched {
+                pitch, timbre, volume, ..
+            } => Some(Event {
+                start_beat: timed.start,
+                duration_beats: timed.note.0 .0,
+                pitch_midi: midi_note_number(pitch.0),
+                frequency: pitch.0,
+                velocity: volume,
+                instrument: timbre_name(timbre),
+            }),
+            NoteKind::Rest => None,
+        })
+    }
+}
+
+#[expect(clippy::cast_possible_truncation, reason = "Rounded, and audible pitches are well within range")]
+pub(super) fn midi_note_number(frequency: f32) -> i32 {
+    (69.0 + 12.0 * (frequency / A4.0).log2()).round() as i32
+}
+
+fn timbre_name(timbre: Timbre) -> String {
+    match timbre {
+        Timbre::Sine => "sine".to_string(),
+        Timbre::Bass => "bass".to_string(),
+        Timbre::Piano => "piano".to_string(),
+        Timbre::ElectricGuitar => "electric_guitar".to_string(),
+        Timbre::Drums => "drums".to_string(),
+        Timbre::Noise => "noise".to_string(),
+        Timbre::BandpassNoise { q } => format!("bandpass_noise_q{q}"),
+        Timbre::CustomSourceUnpitched(path) | Timbre::CustomSourcePitched(path) => path.to_string(),
+        Timbre::Instrument(instrument) if instrument.name.is_empty() => "instrument".to_string(),
+        Timbre::Instrument(instrument) => instrument.name.to_string(),
+        Timbre::Layered(timbres) => timbres.iter().map(|&timbre| timbre_name(timbre)).collect::<Vec<_>>().join("+"),
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{Line, Note, NoteKind};
+
+/// Moves every note in a line the given number of milliseconds later, or earlier if the offset is negative.
+///
+/// Unlike changing note lengths, nudging doesn't affect the timing of anything else: each note keeps its
+/// length, and the notes around it stay where they are. This is useful for deterministic microtiming,
+/// such as a snare that's played slightly behind the beat. Notes can't be moved before the start of
+/// a piece, so notes nudged earlier than that start at the beginning instead.
+///
+/// The offset is in milliseconds rather than time units, so it's the same at any tempo. Live playback
+/// can only delay notes, so notes nudged earlier are played on time when playing live.
+/// Applying `nudge` to a line that's already nudged adds the offsets together.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A backbeat that drags slightly behind the kick drum
+/// let kick = drums(quarter(C4.octave(-2)));
+/// let snare = drums(quarter(C4));
+/// let groove = kick + nudge(snare, 12.0) + kick + nudge(snare, 12.0);
+///
+/// let pushed = nudge(nudge(piano(quarter(C4)), 10.0), -15.0);
+/// assert!(matches!(pushed.notes[0].1, NoteKind::Pitched { nudge_ms: -5.0, .. }));
+/// ```
+pub fn nudge(line: impl Into<Line>, offset_ms: f32) -> Line {
+    let line: Line = line.into();
+    let nudged = |note: &Note| match note.1 {
+        NoteKind::Pitched { nudge_ms, .. } => note.nudge(nudge_ms + offset_ms),
+        NoteKind::Rest => *note,
+    };
+    Line {
+        notes: line.notes.iter().map(nudged).collect(),
+        pickup: line.pickup.iter().map(nudged).collect(),
+        ..line
+    }
+}
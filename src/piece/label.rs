@@ -0,0 +1,72 @@
+use crate::{Line, Piece};
+
+/// A named section of a line, such as "Verse" or "Chorus".
+///
+/// See [`label`] and [`Piece::labels`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Label {
+    /// The name of the section
+    pub name: String,
+    /// The time the section starts, in time units from the start of the line
+    pub start: usize,
+    /// The time the section ends, in time units from the start of the line
+    pub end: usize,
+}
+
+/// Names a section of music, so it can be found later with [`Piece::labels`].
+///
+/// The label spans the whole line, not including its pickup. It moves along with the line
+/// when it is added to other lines, so sections can be labelled before putting them together.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let verse = label("Verse", piano(quarter(C4) * 4));
+/// let chorus = label("Chorus", piano(half(A4) * 2));
+/// let song = Piece::from(verse.clone() + chorus + verse);
+///
+/// let labels = song.labels();
+/// assert_eq!(labels[0], Label { name: "Verse".to_string(), start: 0, end: 16 });
+/// assert_eq!(labels[1], Label { name: "Chorus".to_string(), start: 16, end: 32 });
+/// assert_eq!(labels[2], Label { name: "Verse".to_string(), start: 32, end: 48 });
+/// ```
+pub fn label(name: &str, line: impl Into<Line>) -> Line {
+    let mut line: Line = line.into();
+    line.labels.push(Label {
+        name: name.to_string(),
+        start: 0,
+        end: line.length(),
+    });
+    line
+}
+
+impl Piece {
+    /// Returns every labelled section in the piece, ordered by when they start.
+    ///
+    /// Sections with the same name and span in several lines, such as a labelled chord, are only returned once.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let intro = label("Intro", bass(whole(C4)));
+    /// let piece = piano(whole(REST) + label("Melody", quarter(C4) + quarter(A4))) * intro;
+    ///
+    /// let labels = piece.labels();
+    /// assert_eq!(labels.len(), 2);
+    /// assert_eq!((labels[0].name.as_str(), labels[0].start, labels[0].end), ("Intro", 0, 16));
+    /// assert_eq!((labels[1].name.as_str(), labels[1].start, labels[1].end), ("Melody", 16, 24));
+    /// ```
+    pub fn labels(&self) -> Vec<Label> {
+        let mut labels: Vec<Label> = self.lines.iter().flat_map(|line| line.labels.iter().cloned()).collect();
+        labels.sort_by_key(|label| (label.start, label.end));
+        let mut unique: Vec<Label> = Vec::with_capacity(labels.len());
+        for label in labels {
+            if !unique.contains(&label) {
+                unique.push(label);
+            }
+        }
+        unique
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
-    iter::{Product, Sum},
+    iter::Sum,
     ops::{Add, Mul, Neg, Not},
+    time::Duration,
 };
 
 use crate::{
@@ -8,7 +9,7 @@ use crate::{
     Note,
 };
 
-use super::Piece;
+use super::{label::Label, Piece};
 
 /// Represents a sequence of musical notes played one after another (melody/rhythm).
 ///
@@ -48,7 +49,28 @@ use super::Piece;
 /// // The `-` operator makes the line a pickup line, and the `!` operator
 /// // indicates that the pickup should be held into the first note of the main sequence.
 /// let mut line_with_pickup = -!piano(eighth(b4) + eighth(g4)) + piano(quarter(g4)) + piano(quarter(a4));
+/// ```
+///
+/// ## Lines and Pieces
+/// A `Line` is one voice, where each note follows the last. A [`Piece`] is any number of lines
+/// playing at the same time. Adding notes and lines with `+` keeps a `Line`, as do combinators like
+/// [`label`](super::label::label) and [`maybe`](super::chance::maybe), so a voice stays a `Line` until
+/// it's layered with another using `*`, which gives a `Piece`. Chords are several voices at once, so
+/// giving one a length also gives a `Piece`.
+///
+/// Anything that plays music accepts either, so converting is only needed to call a `Piece` method
+/// on a single voice, or to store voices alongside pieces. [`Line::into_piece`] and `Piece::from`
+/// both turn a line into a piece of just that line.
 ///
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let melody: Line = piano(quarter(C4)) + piano(quarter(A4));
+/// let duet: Piece = melody.clone() * bass(half(C4.octave(-1)));
+///
+/// assert_eq!(melody.into_piece().lines.len(), 1);
+/// assert_eq!(duet.lines.len(), 2);
+/// ```
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Line {
     /// The main sequence of notes in the line
@@ -57,6 +79,8 @@ pub struct Line {
     pub pickup: Vec<Note>,
     /// Whether the pickup should be held into the first note of the main sequence
     pub hold_pickup: bool,
+    /// Named sections of the line, such as "Verse" or "Chorus". See [`label`](super::label::label)
+    pub labels: Vec<Label>,
 }
 
 impl Line {
@@ -72,6 +96,22 @@ impl Line {
     pub fn new() -> Line {
         Line::default()
     }
+
+    /// Turns the line into a piece with just this line in it.
+    ///
+    /// This is the same as `Piece::from(line)`, but reads better at the end of a chain of methods.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = label("Intro", piano(quarter(C4) + half(A4)));
+    /// assert_eq!(line.clone().into_piece(), Piece::from(line));
+    /// ```
+    pub fn into_piece(self) -> Piece {
+        Piece::from(self)
+    }
+
     /// Extends the line by adding a rest of the specified duration.
     ///
     /// This is mostly used internally for convenience, but can also be used
@@ -91,6 +131,32 @@ impl Line {
         #[expect(clippy::arithmetic_side_effects, reason = "User is expected to handle this error")]
         return self.clone() + Note(NoteLength(extend_by), NoteKind::Rest);
     }
+    /// Starts the line later, by putting a rest of the given length before it.
+    ///
+    /// This lines up voices that come in at different times when they're played together with `*`,
+    /// and reads more clearly than adding the rest by hand. A pickup still leads into the line's
+    /// first note, taking its time from the end of the rest, and labels move along with the notes.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4));
+    ///
+    /// // The second voice comes in a half note after the first
+    /// let round = melody.clone() * melody.clone().offset(NoteLength(8));
+    ///
+    /// assert_eq!(round.lines[1], half(REST) + melody.clone());
+    /// assert_eq!(round.length(), 16);
+    /// assert_eq!(melody.clone().offset(NoteLength(0)), melody);
+    /// ```
+    pub fn offset(self, by: NoteLength) -> Line {
+        if by.0 == 0 {
+            return self;
+        }
+        #[expect(clippy::arithmetic_side_effects, reason = "User is expected to handle this error")]
+        return Line::from(Note(by, NoteKind::Rest)) + self;
+    }
     /// Returns the total duration of the line in time units.
     ///
     /// This sums up the durations of all notes in the main sequence.
@@ -125,6 +191,223 @@ impl Line {
             notes: self.notes.iter().map(|note| note.volume(volume)).collect(),
             pickup: self.pickup.iter().map(|note| note.volume(volume)).collect(),
             hold_pickup: self.hold_pickup,
+            labels: self.labels.clone(),
+        }
+    }
+
+    /// Creates a new line with all notes positioned at the specified pan.
+    ///
+    /// See [`Note::pan`] for how pan values map onto output channels.
+    /// Rest notes are unaffected.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4));
+    /// let left_melody = melody.pan(-1.0);
+    /// ```
+    pub fn pan(&self, pan: f32) -> Line {
+        Line {
+            notes: self.notes.iter().map(|note| note.pan(pan)).collect(),
+            pickup: self.pickup.iter().map(|note| note.pan(pan)).collect(),
+            hold_pickup: self.hold_pickup,
+            labels: self.labels.clone(),
+        }
+    }
+
+    /// Stretches or compresses the line in time by the given factor, without changing pitch.
+    ///
+    /// A factor of `2.0` makes every note twice as long (half speed), and `0.5` makes every
+    /// note half as long (double speed). Pickup notes are scaled too.
+    ///
+    /// Since note lengths are whole time units, the start and end of each note are rounded
+    /// to the nearest unit. Rounding is based on the note's position in the line rather than its
+    /// length, so lines that were aligned before scaling remain aligned afterwards.
+    /// Notes may end up with a length of zero if the factor is small enough, and a note that
+    /// becomes longer than the longest possible [`NoteLength`] is split into several notes in a row,
+    /// like a tie, so the notes after it keep their timing.
+    ///
+    /// Negative, infinite, and NaN factors are treated as zero. Labels are scaled along with the notes.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = piano(quarter(C4) + eighth(A4) + eighth(C4));
+    ///
+    /// assert_eq!(line.scale_time(2.0), piano(half(C4) + quarter(A4) + quarter(C4)));
+    /// assert_eq!(line.scale_time(0.5).length(), 4);
+    /// ```
+    pub fn scale_time(&self, factor: f32) -> Line {
+        Line {
+            notes: scale_notes(&self.notes, factor),
+            pickup: scale_notes(&self.pickup, factor),
+            hold_pickup: self.hold_pickup,
+            labels: self
+                .labels
+                .iter()
+                .map(|label| Label {
+                    name: label.name.clone(),
+                    start: scale_time(label.start, factor),
+                    end: scale_time(label.end, factor),
+                })
+                .collect(),
+        }
+    }
+
+    /// Cuts out the part of the line from time unit `range.start` up to `range.end`, as a line of its own.
+    ///
+    /// Notes that cross either end of the range are shortened to fit it, and the slice starts at time 0,
+    /// so it's `range.len()` long unless the line ends sooner. Labels are cut and moved the same way, and
+    /// any labels entirely outside the range are dropped. The pickup only leads into the start of the
+    /// line, so it's kept, along with whether it's held, only if the range starts at 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let line = piano(quarter(C4) + half(A4) + quarter(C4));
+    ///
+    /// // The half note is cut in two by the end of the slice
+    /// assert_eq!(line.slice(2..8), piano(eighth(C4) + quarter(A4)));
+    /// assert_eq!(line.slice(12..100), piano(Line::from(quarter(C4))));
+    /// assert_eq!(line.slice(20..30), Line::new());
+    ///
+    /// // A slice from later on has no pickup to hold
+    /// let held = !(-piano(Line::from(eighth(C4))) + line);
+    /// assert!(held.slice(0..8).hold_pickup);
+    /// assert!(!held.slice(2..8).hold_pickup);
+    /// ```
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Line {
+        let mut time = 0usize;
+        let notes = self
+            .notes
+            .iter()
+            .filter_map(|note| {
+                let start = time.max(range.start);
+                time = time.saturating_add(note.0 .0 as usize);
+                let end = time.min(range.end);
+                // Clipped to the note, which fits in a u16
+                let length = u16::try_from(end.checked_sub(start)?).ok()?;
+                (length > 0).then_some(Note(NoteLength(length), note.1))
+            })
+            .collect();
+
+        let from_start = range.start == 0;
+        Line {
+            notes,
+            pickup: if from_start { self.pickup.clone() } else { vec![] },
+            hold_pickup: from_start && self.hold_pickup,
+            labels: self
+                .labels
+                .iter()
+                .filter(|label| label.start < range.end && label.end > range.start)
+                .map(|label| Label {
+                    name: label.name.clone(),
+                    start: label.start.max(range.start).saturating_sub(range.start),
+                    end: label.end.min(range.end).saturating_sub(range.start),
+                })
+                .collect(),
+        }
+    }
+
+    /// Cleans up notes that last less than `min` at `tempo_bpm`, which tend to click rather than sound.
+    ///
+    /// The tempo is in sixteenth notes per minute, as for [`MusicPlayer::new_file`](crate::MusicPlayer::new_file).
+    /// With [`ShortNotePolicy::Remove`], short notes are replaced with rests, and notes of no length at all are
+    /// dropped, so every other note plays when it did. With [`ShortNotePolicy::Extend`], short notes are held
+    /// until they last at least `min`, taking the time from the start of the notes after them. Those notes
+    /// start later and are shortened, or dropped if the extended note covers them entirely, but their ends
+    /// don't move. Either way, the line keeps its length.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::prelude::*;
+    ///
+    /// // At 300 BPM, a sixteenth note lasts 200 ms
+    /// let line = piano(quarter(C4) + sixteenth(A4) + quarter(C4));
+    /// let min = Duration::from_millis(300);
+    ///
+    /// assert_eq!(
+    ///     line.cull_short_notes(min, 300, ShortNotePolicy::Remove),
+    ///     piano(quarter(C4)) + sixteenth(REST) + piano(quarter(C4))
+    /// );
+    /// // Held for two sixteenths, so the note after it starts a sixteenth late and ends on time
+    /// assert_eq!(
+    ///     line.cull_short_notes(min, 300, ShortNotePolicy::Extend),
+    ///     piano(quarter(C4) + eighth(A4) + dotted(eighth)(C4))
+    /// );
+    /// ```
+    pub fn cull_short_notes(&self, min: Duration, tempo_bpm: u32, policy: ShortNotePolicy) -> Line {
+        Line {
+            notes: cull_short_notes(&self.notes, min, tempo_bpm, policy),
+            pickup: cull_short_notes(&self.pickup, min, tempo_bpm, policy),
+            hold_pickup: self.hold_pickup,
+            labels: self.labels.clone(),
+        }
+    }
+
+    /// Fades the line in from silence over its first `length`, along `curve`.
+    ///
+    /// Each note is faded to the volume at its middle, since a note can only have one volume. Pickup notes
+    /// play before the fade starts, so they're silenced. A fade longer than the line ends after it does.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let note = piano(quarter(C4));
+    /// assert_eq!(
+    ///     (note * 4).fade_in(NoteLength::HALF, FadeCurve::Linear),
+    ///     note.volume(0.25) + note.volume(0.75) + note + note
+    /// );
+    /// ```
+    pub fn fade_in(&self, length: NoteLength, curve: FadeCurve) -> Line {
+        self.fade_from(0, length, |progress| curve.fade_out_gain(1.0 - progress))
+    }
+
+    /// Fades the line out to silence over its last `length`, along `curve`.
+    ///
+    /// Each note is faded to the volume at its middle, since a note can only have one volume. A fade longer
+    /// than the line starts before it does, so the first notes are already quieter.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let note = piano(quarter(C4));
+    /// assert_eq!(
+    ///     (note * 4).fade_out(NoteLength::HALF, FadeCurve::Linear),
+    ///     note + note + note.volume(0.75) + note.volume(0.25)
+    /// );
+    /// ```
+    pub fn fade_out(&self, length: NoteLength, curve: FadeCurve) -> Line {
+        let start = self.length().saturating_sub(length.0 as usize);
+        self.fade_from(start, length, |progress| curve.fade_out_gain(progress))
+    }
+
+    /// Scales the volume of every note by `gain` at how far through a fade of `length` starting at `start` the
+    /// middle of the note is, from 0.0 to 1.0. Pickup notes are faded as if they were before the fade.
+    #[expect(clippy::cast_precision_loss, reason = "Only used for the fade's volume")]
+    pub(super) fn fade_from(&self, start: usize, length: NoteLength, gain: impl Fn(f32) -> f32) -> Line {
+        let progress = |middle: f32| ((middle - start as f32) / f32::from(length.0.max(1))).clamp(0.0, 1.0);
+        let notes = super::timed_notes(self)
+            .map(|timed| {
+                let middle = timed.start as f32 + f32::from(timed.note.0 .0) / 2.0;
+                super::scale_volume(timed.note, gain(progress(middle)))
+            })
+            .collect();
+        Line {
+            notes,
+            pickup: self
+                .pickup
+                .iter()
+                .map(|&note| super::scale_volume(note, gain(progress(f32::NEG_INFINITY))))
+                .collect(),
+            hold_pickup: self.hold_pickup,
+            labels: self.labels.clone(),
         }
     }
 
@@ -163,6 +446,125 @@ impl Line {
     }
 }
 
+/// Scales a time within a line, rounding it to the nearest time unit.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss,
+    reason = "Scaled times are clamped to be non-negative"
+)]
+pub(super) fn scale_time(time: usize, factor: f32) -> usize {
+    let factor = if factor.is_finite() { factor.max(0.0) } else { 0.0 };
+    (time as f32 * factor).round() as usize
+}
+
+fn scale_notes(notes: &[Note], factor: f32) -> Vec<Note> {
+    warp_notes(notes, |time| scale_time(time, factor))
+}
+
+/// Moves the start and end of each note to a new time, keeping the notes in order.
+///
+/// `warp` takes a time within the notes, and must never move a later time before an earlier one.
+/// Notes warped to more than `u16::MAX` time units are split into several notes of the same kind in a row.
+#[expect(clippy::cast_possible_truncation, reason = "Lengths are split to fit in a u16")]
+#[expect(clippy::arithmetic_side_effects, reason = "Warped ends are never before warped starts")]
+pub(super) fn warp_notes(notes: &[Note], warp: impl Fn(usize) -> usize) -> Vec<Note> {
+    let mut time = 0;
+    let mut warped = Vec::with_capacity(notes.len());
+    for note in notes {
+        let start = warp(time);
+        time += note.0 .0 as usize;
+        let mut length = warp(time) - start;
+        while length > u16::MAX as usize {
+            warped.push(Note(NoteLength(u16::MAX), note.1));
+            length -= u16::MAX as usize;
+        }
+        warped.push(Note(NoteLength(length as u16), note.1));
+    }
+    warped
+}
+
+/// What [`Line::cull_short_notes`] and [`Piece::cull_short_notes`] do with notes that are too short.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum ShortNotePolicy {
+    /// Replaces short notes with rests of the same length
+    #[default]
+    Remove,
+    /// Holds short notes until they're long enough, shortening the notes after them to make room
+    Extend,
+}
+
+/// The shape of a fade made with [`Line::fade_in`], [`Line::fade_out`], and the same methods on [`Piece`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum FadeCurve {
+    /// Changes volume at a constant rate
+    #[default]
+    Linear,
+    /// Fades out quickly at first, then trails off, like a natural decay. Fading in is the reverse, starting
+    /// slowly and rising fastest at the end
+    Exponential,
+    /// Starts and ends gently, changing fastest in the middle
+    SCurve,
+}
+
+impl FadeCurve {
+    /// The volume at `progress` through a fade out, from 1.0 at the start down to 0.0 at the end
+    pub(super) fn fade_out_gain(self, progress: f32) -> f32 {
+        /// How sharply the exponential curve bends
+        const STEEPNESS: f32 = 5.0;
+
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => 1.0 - progress,
+            FadeCurve::Exponential => ((-STEEPNESS * progress).exp() - (-STEEPNESS).exp()) / (1.0 - (-STEEPNESS).exp()),
+            FadeCurve::SCurve => 0.5 + 0.5 * (std::f32::consts::PI * progress).cos(),
+        }
+    }
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    reason = "The minimum length is rounded up, and saturates at the u16 limit"
+)]
+fn cull_short_notes(notes: &[Note], min: Duration, tempo_bpm: u32, policy: ShortNotePolicy) -> Vec<Note> {
+    let units_per_second = f64::from(tempo_bpm) / 60.0;
+    let min_length = (min.as_secs_f64() * units_per_second).ceil().min(f64::from(u16::MAX)) as u16;
+    let is_short = |note: &Note| !note.is_rest() && f64::from(note.0 .0) < min.as_secs_f64() * units_per_second;
+
+    let mut culled: Vec<Note> = Vec::with_capacity(notes.len());
+    // Time an extended note still has to take from the notes after it
+    let mut owed = 0u16;
+    for note in notes {
+        let taken = owed.min(note.0 .0);
+        owed = owed.saturating_sub(taken);
+        let note = Note(NoteLength(note.0 .0.saturating_sub(taken)), note.1);
+        if note.0 .0 == 0 && taken > 0 {
+            continue;
+        }
+
+        if !is_short(&note) {
+            culled.push(note);
+            continue;
+        }
+        match policy {
+            ShortNotePolicy::Remove if note.0 .0 > 0 => culled.push(Note(note.0, NoteKind::Rest)),
+            ShortNotePolicy::Remove => {}
+            ShortNotePolicy::Extend => {
+                owed = min_length.saturating_sub(note.0 .0);
+                culled.push(Note(NoteLength(min_length), note.1));
+            }
+        }
+    }
+
+    // The line ended before the extended note could take all its time, so it only lasts until the end
+    if let Some(last) = culled.last_mut().filter(|_| owed > 0) {
+        last.0 .0 = last.0 .0.saturating_sub(owed);
+    }
+    culled.retain(|note| !(note.0 .0 == 0 && is_short(note)));
+    culled
+}
+
 impl Neg for Line {
     type Output = Line;
 
@@ -171,6 +573,7 @@ impl Neg for Line {
             notes: vec![],
             pickup: self.notes,
             hold_pickup: self.hold_pickup,
+            labels: vec![],
         }
     }
 }
@@ -198,6 +601,7 @@ impl From<Vec<Note>> for Line {
             notes,
             pickup: vec![],
             hold_pickup: false,
+            labels: vec![],
         }
     }
 }
@@ -235,13 +639,13 @@ impl Add<Piece> for Line {
     #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
     #[expect(clippy::cast_possible_truncation, reason = "I don't want to deal with this right now")]
     fn add(self, rhs: Piece) -> Self::Output {
-        if !rhs.0.is_empty() {
+        if !rhs.lines.is_empty() {
             let mut piece = rhs.clone();
             let self_len = self.length();
 
-            piece.0[0] = self + piece.0[0].clone();
-            for line_no in 1..piece.0.len() {
-                piece.0[line_no] = Line::new().extend(self_len as u16) + piece.0[line_no].clone()
+            piece.lines[0] = self + piece.lines[0].clone();
+            for line_no in 1..piece.lines.len() {
+                piece.lines[line_no] = Line::new().extend(self_len as u16) + piece.lines[line_no].clone()
             }
 
             piece
@@ -310,6 +714,9 @@ impl Add<Line> for Line {
 
         notes.append(&mut pickup_line.notes);
 
+        // Where the main sequence of `rhs` starts
+        let offset = Line::from(notes.clone()).length();
+
         let mut rhs_notes = rhs.notes;
 
         if rhs.hold_pickup {
@@ -322,10 +729,17 @@ impl Add<Line> for Line {
             }
         }
 
+        let rhs_labels = rhs.labels.into_iter().map(|label| Label {
+            start: label.start + offset,
+            end: label.end + offset,
+            ..label
+        });
+
         Line {
             notes: [notes, rhs_notes].concat(),
             pickup: self.pickup,
             hold_pickup: self.hold_pickup,
+            labels: self.labels.into_iter().chain(rhs_labels).collect(),
         }
     }
 }
@@ -356,7 +770,7 @@ impl Mul<Line> for Line {
     type Output = Piece;
 
     fn mul(self, rhs: Line) -> Self::Output {
-        Piece(vec![self, rhs])
+        Piece::from(vec![self, rhs])
     }
 }
 
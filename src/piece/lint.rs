@@ -0,0 +1,191 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::{
+    note::{NoteKind, NotePitch},
+    scales::tet12::{DisplayWith, PitchNaming},
+    Note, Piece,
+};
+
+use super::{timed_notes, TimedNote};
+
+/// The lowest pitch that is considered playable by [`Piece::lint`], at the bottom of human hearing.
+pub const LOWEST_PLAYABLE_PITCH: NotePitch = NotePitch(20.0);
+
+/// The highest pitch that is considered playable by [`Piece::lint`], at the top of human hearing.
+pub const HIGHEST_PLAYABLE_PITCH: NotePitch = NotePitch(20_000.0);
+
+/// A likely mistake in a piece, as reported by [`Piece::lint`].
+///
+/// Lints don't stop a piece from being played or rendered. They point out things
+/// that are usually unintentional. Times are in time units from the start of the piece,
+/// and lines are referred to by their index in the piece.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lint {
+    /// Two lines play the same pitch with the same timbre at overlapping times.
+    ///
+    /// This just makes the note louder, and can cause clipping.
+    DuplicateNote {
+        /// The note in the first line
+        note: Note,
+        /// The time the note in the first line starts
+        start: usize,
+        /// The indices of the two lines
+        lines: (usize, usize),
+    },
+    /// A note's pitch is outside of the audible range
+    /// ([`LOWEST_PLAYABLE_PITCH`] to [`HIGHEST_PLAYABLE_PITCH`]).
+    OutOfRange {
+        /// The note that is out of range
+        note: Note,
+        /// The time the note starts
+        start: usize,
+        /// The index of the line the note is in
+        line: usize,
+    },
+    /// A note has a length of zero, so it will never be heard.
+    ZeroLength {
+        /// The zero-length note
+        note: Note,
+        /// The time the note would have started
+        start: usize,
+        /// The index of the line the note is in
+        line: usize,
+    },
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.display_with(PitchNaming::Scientific))
+    }
+}
+
+impl Lint {
+    /// Formats the lint's message like its `Display` output, with pitches named in `naming`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let lints = Piece::from(piano(Line::from(C4.with_length(NoteLength(0))))).lint();
+    /// assert_eq!(
+    ///     lints[0].display_with(PitchNaming::Solfege).to_string(),
+    ///     "Line 0 has a zero-length do: 261.63Hz at time 0"
+    /// );
+    /// ```
+    pub fn display_with(&self, naming: PitchNaming) -> impl Display + '_ {
+        DisplayWith(self, naming)
+    }
+}
+
+impl Display for DisplayWith<'_, Lint> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let DisplayWith(lint, naming) = *self;
+        let describe = |note: &Note| match note.pitch() {
+            Some(pitch) => pitch.display_with(naming).to_string(),
+            None => "rest".to_string(),
+        };
+        match lint {
+            Lint::DuplicateNote { note, start, lines } => write!(
+                f,
+                "Lines {} and {} both play {} at overlapping times, starting at time {start}",
+                lines.0,
+                lines.1,
+                describe(note)
+            ),
+            Lint::OutOfRange { note, start, line } => write!(
+                f,
+                "Line {line} plays {} at time {start}, which is outside the audible range",
+                describe(note)
+            ),
+            Lint::ZeroLength { note, start, line } => {
+                write!(f, "Line {line} has a zero-length {} at time {start}", describe(note))
+            }
+        }
+    }
+}
+
+impl Piece {
+    /// Checks the piece for likely mistakes.
+    ///
+    /// This reports:
+    /// - The same pitch and timbre playing in two lines at overlapping times
+    /// - Pitches outside of the audible range
+    /// - Notes with a length of zero
+    ///
+    /// These are warnings about composition quality, not errors; the piece can still be played.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::Lint;
+    ///
+    /// // The melody accidentally doubles the C4 held in the piano's left hand
+    /// let piece = piano(half(C4) + half(A4)) * piano(whole(C4));
+    ///
+    /// assert_eq!(
+    ///     piece.lint(),
+    ///     vec![Lint::DuplicateNote { note: piano(half(C4)), start: 0, lines: (0, 1) }]
+    /// );
+    /// ```
+    pub fn lint(&self) -> Vec<Lint> {
+        let lines: Vec<Vec<TimedNote>> = self.lines.iter().map(|line| timed_notes(line).collect()).collect();
+        let mut lints = Vec::new();
+
+        for (line, notes) in lines.iter().enumerate() {
+            for &TimedNote { start, note } in notes {
+                if note.0 .0 == 0 {
+                    lints.push(Lint::ZeroLength { note, start, line });
+                } else if note
+                    .pitch()
+                    .is_some_and(|pitch| !(LOWEST_PLAYABLE_PITCH.0..=HIGHEST_PLAYABLE_PITCH.0).contains(&pitch.0))
+                {
+                    lints.push(Lint::OutOfRange { note, start, line });
+                }
+            }
+        }
+
+        for (first, first_notes) in lines.iter().enumerate() {
+            for (second, second_notes) in lines.iter().enumerate().skip(first.saturating_add(1)) {
+                for &TimedNote { start, note } in first_notes {
+                    let duplicated = second_notes.iter().any(
+                        |&TimedNote {
+                             start: other_start,
+                             note: other,
+                         }| {
+                            same_sound(note, other) && overlaps(start, note, other_start, other)
+                        },
+                    );
+                    if duplicated {
+                        lints.push(Lint::DuplicateNote {
+                            note,
+                            start,
+                            lines: (first, second),
+                        });
+                    }
+                }
+            }
+        }
+
+        lints
+    }
+}
+
+fn same_sound(a: Note, b: Note) -> bool {
+    match (a.1, b.1) {
+        (
+            NoteKind::Pitched { pitch, timbre, .. },
+            NoteKind::Pitched {
+                pitch: other_pitch,
+                timbre: other_timbre,
+                ..
+            },
+        ) => pitch == other_pitch && timbre == other_timbre,
+        _ => false,
+    }
+}
+
+fn overlaps(start: usize, note: Note, other_start: usize, other: Note) -> bool {
+    let end = start.saturating_add(note.0 .0 as usize);
+    let other_end = other_start.saturating_add(other.0 .0 as usize);
+    start < other_end && other_start < end
+}
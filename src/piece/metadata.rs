@@ -0,0 +1,93 @@
+use crate::Piece;
+
+/// Information about a piece that isn't part of the music itself, such as its title and composer.
+///
+/// Metadata is saved along with the piece by [`Piece::save`], and its text is exported into MIDI files
+/// as text events by [`Piece::to_midi`], but it has no effect on how the piece sounds.
+///
+/// When pieces are combined with `+` or `*`, the result keeps the metadata of the first piece, or
+/// of the second if the first has none. Transforming a piece, such as with [`Piece::volume`], keeps its metadata.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::Metadata;
+///
+/// let piece = Piece::from(piano(quarter(C4) + quarter(A4))).with_metadata(Metadata {
+///     title: Some("Two Notes".to_string()),
+///     composer: Some("J. S. Bach".to_string()),
+///     ..Default::default()
+/// });
+///
+/// // Adding to the piece keeps its title
+/// let longer = piece + piano(half(C4));
+/// assert_eq!(longer.metadata().title.as_deref(), Some("Two Notes"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+    /// The title of the piece
+    pub title: Option<String>,
+    /// Who wrote the piece
+    pub composer: Option<String>,
+    /// Free-form notes about the piece, such as performance directions or a copyright notice
+    pub comments: Vec<String>,
+    /// The length of the incomplete bar before the first downbeat, in time units. See [`Piece::with_pickup`]
+    pub pickup_length: usize,
+}
+
+impl Metadata {
+    /// Gets whether there's no metadata at all.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::Metadata;
+    ///
+    /// assert!(Metadata::default().is_empty());
+    /// assert!(!Metadata { comments: vec!["Play softly".to_string()], ..Default::default() }.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.composer.is_none() && self.comments.is_empty() && self.pickup_length == 0
+    }
+
+    /// Picks the metadata kept when two pieces are combined: this one, unless it's empty
+    pub(crate) fn or(self, other: Metadata) -> Metadata {
+        if self.is_empty() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl Piece {
+    /// Gets the piece's metadata.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// assert!(Piece::from(piano(whole(C4))).metadata().is_empty());
+    /// ```
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Creates a new piece with the given metadata, replacing any it already had.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::Metadata;
+    ///
+    /// let piece = Piece::from(piano(whole(C4))).with_metadata(Metadata {
+    ///     title: Some("Drone".to_string()),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(piece.metadata().title.as_deref(), Some("Drone"));
+    /// ```
+    pub fn with_metadata(mut self, metadata: Metadata) -> Piece {
+        self.metadata = metadata;
+        self
+    }
+}
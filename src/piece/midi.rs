@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use crate::{
+    note::{NoteKind, Timbre},
+    Line, Metadata, Piece, Tet12, C4,
+};
+
+/// MIDI ticks per quarter note
+const TICKS_PER_QUARTER: u16 = 96;
+
+/// MIDI ticks per time unit (sixteenth note)
+const TICKS_PER_UNIT: u32 = 24;
+
+/// The General MIDI percussion channel (channel 10, zero indexed)
+const DRUM_CHANNEL: u8 = 9;
+
+/// The meta event type for free-form text
+const TEXT_EVENT: u8 = 0x01;
+
+/// The meta event type for a track's name, which names the whole sequence when it's on the first track
+const TRACK_NAME_EVENT: u8 = 0x03;
+
+/// Options for exporting a piece as a MIDI file with [`Piece::to_midi_with`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::MidiOptions;
+///
+/// // Export sine notes as a synth lead (Lead 1, program 80) instead of the default, Acoustic Grand Piano
+/// assert_eq!(MidiOptions::new().program(Timbre::Sine), 0);
+/// let options = MidiOptions::new().with_tempo(240).with_program(Timbre::Sine, 80);
+///
+/// assert_eq!(options.program(Timbre::Sine), 80);
+/// assert_eq!(options.program(Timbre::Piano), 0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MidiOptions {
+    tempo_bpm: u32,
+    programs: HashMap<Timbre, u8>,
+}
+
+impl Default for MidiOptions {
+    fn default() -> Self {
+        MidiOptions {
+            tempo_bpm: 300,
+            programs: HashMap::new(),
+        }
+    }
+}
+
+impl MidiOptions {
+    /// Creates the default options: a tempo of 300 BPM, and the default program for each timbre.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the tempo, in the same units as `MusicPlayer` (sixteenth notes per minute).
+    ///
+    /// Non-positive tempos are ignored.
+    pub fn with_tempo(mut self, tempo_bpm: u32) -> Self {
+        if tempo_bpm > 0 {
+            self.tempo_bpm = tempo_bpm;
+        }
+        self
+    }
+
+    /// Sets the General MIDI program (instrument) that notes with the given timbre are exported as.
+    ///
+    /// Programs are zero indexed, so `0` is Acoustic Grand Piano. Values above 127 are clamped.
+    /// This has no effect on [`Timbre::Drums`], which always uses the percussion channel.
+    pub fn with_program(mut self, timbre: Timbre, program: u8) -> Self {
+        self.programs.insert(timbre, program.min(127));
+        self
+    }
+
+    /// Gets the General MIDI program that notes with the given timbre are exported as.
+    ///
+    /// Unless set with [`MidiOptions::with_program`], the defaults are:
+    /// - `Piano`: 0 (Acoustic Grand Piano)
+    /// - `Bass`: 33 (Electric Bass, finger)
+    /// - `ElectricGuitar`: 29 (Overdriven Guitar)
+    /// - `Noise`: 121 (Breath Noise)
+    /// - `BandpassNoise`: 117 (Melodic Tom)
+    /// - `Layered`: the program of its first timbre
+    /// - `Instrument`: the instrument's own `midi_program`
+    /// - Anything else: 0
+    pub fn program(&self, timbre: Timbre) -> u8 {
+        self.programs.get(&timbre).copied().unwrap_or(match timbre {
+            Timbre::Bass => 33,
+            Timbre::ElectricGuitar => 29,
+            Timbre::Noise => 121,
+            Timbre::BandpassNoise { .. } => 117,
+            Timbre::Layered(timbres) => timbres.first().map_or(0, |&first| self.program(first)),
+            Timbre::Instrument(instrument) => instrument.midi_program.min(127),
+            _ => 0,
+        })
+    }
+}
+
+impl Piece {
+    /// Exports the piece as a Standard MIDI File, using the default [`MidiOptions`].
+    ///
+    /// See [`Piece::to_midi_with`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(A4)) * bass(half(C4.octave(-1)));
+    /// let midi = piece.to_midi();
+    ///
+    /// assert_eq!(&midi[0..4], b"MThd");
+    /// // A tempo track, followed by a track for each line
+    /// assert_eq!(midi.windows(4).filter(|chunk| chunk == b"MTrk").count(), 3);
+    /// ```
+    pub fn to_midi(&self) -> Vec<u8> {
+        self.to_midi_with(&MidiOptions::default())
+    }
+
+    /// Exports the piece as a Standard MIDI File, with the given options.
+    ///
+    /// The file has a tempo track followed by one track per line. Each line plays on its own
+    /// channel, and a program change is sent whenever the timbre changes. Each track is named after
+    /// the first named [`Instrument`](crate::Instrument) in its line, or "Instrument 1", "Instrument 2",
+    /// and so on if it doesn't have one. The piece's [`Metadata`] is written to the tempo track: the title
+    /// as its name, and the composer (as "Composer: ...") and each comment as text events. Drums are played on
+    /// the General MIDI percussion channel, as kick, snare, hi-hat, or crash depending on pitch.
+    /// Pitches are rounded to the nearest MIDI note, and volume is mapped so that `1.0` is velocity 100.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MidiOptions;
+    ///
+    /// let piece = Piece::from(sine(quarter(C4) + quarter(A4)));
+    /// let midi = piece.to_midi_with(&MidiOptions::new().with_program(Timbre::Sine, 80));
+    ///
+    /// // The line's track starts with a program change (0xC0 on the first channel) to program 80
+    /// assert!(midi.windows(3).any(|event| event == [0x00, 0xC0, 80]));
+    /// ```
+    pub fn to_midi_with(&self, options: &MidiOptions) -> Vec<u8> {
+        let mut tracks = vec![tempo_track(options.tempo_bpm, self.metadata())];
+        tracks.extend(
+            self.lines
+                .iter()
+                .enumerate()
+                .map(|(index, line)| line_track(line, index, options)),
+        );
+
+        let mut file = Vec::new();
+        file.extend_from_slice(b"MThd");
+        file.extend_from_slice(&6u32.to_be_bytes());
+        // Format 1: multiple tracks played simultaneously
+        file.extend_from_slice(&1u16.to_be_bytes());
+        file.extend_from_slice(&u16::try_from(tracks.len()).unwrap_or(u16::MAX).to_be_bytes());
+        file.extend_from_slice(&TICKS_PER_QUARTER.to_be_bytes());
+
+        for track in tracks {
+            file.extend_from_slice(b"MTrk");
+            file.extend_from_slice(&u32::try_from(track.len()).unwrap_or(u32::MAX).to_be_bytes());
+            file.extend(track);
+        }
+
+        file
+    }
+}
+
+fn tempo_track(tempo_bpm: u32, metadata: &Metadata) -> Vec<u8> {
+    // Tempo is in sixteenth notes per minute, but MIDI wants microseconds per quarter note
+    let micros_per_quarter = 240_000_000u32.checked_div(tempo_bpm).unwrap_or(500_000);
+
+    let mut track = Vec::new();
+    if let Some(title) = &metadata.title {
+        write_text_event(&mut track, TRACK_NAME_EVENT, title);
+    }
+    if let Some(composer) = &metadata.composer {
+        write_text_event(&mut track, TEXT_EVENT, &format!("Composer: {composer}"));
+    }
+    for comment in &metadata.comments {
+        write_text_event(&mut track, TEXT_EVENT, comment);
+    }
+
+    track.extend_from_slice(&[0x00, 0xFF, 0x51, 0x03]);
+    track.extend_from_slice(&micros_per_quarter.to_be_bytes()[1..]);
+    track.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+    track
+}
+
+#[expect(clippy::cast_possible_truncation, reason = "Channels, keys and velocities are clamped")]
+#[expect(clippy::cast_sign_loss, reason = "Keys and velocities are clamped")]
+fn line_track(line: &Line, index: usize, options: &MidiOptions) -> Vec<u8> {
+    // Use the 15 melodic channels in turn, skipping the percussion channel
+    let melodic_channel = (index % 15) as u8;
+    let melodic_channel = if melodic_channel >= DRUM_CHANNEL {
+        melodic_channel.saturating_add(1)
+    } else {
+        melodic_channel
+    };
+
+    let mut track = Vec::new();
+    write_text_event(&mut track, TRACK_NAME_EVENT, &track_name(line, index));
+
+    let mut current_program = None;
+    let mut delta = 0u32;
+
+    for note in &line.notes {
+        let length = u32::from(note.0 .0).saturating_mul(TICKS_PER_UNIT);
+
+        match note.1 {
+            NoteKind::Pitched {
+                pitch, timbre, volume, ..
+            } => {
+                let (channel, key) = if timbre == Timbre::Drums {
+                    (DRUM_CHANNEL, drum_key(pitch.0))
+                } else {
+                    (melodic_channel, pitch.to_midi())
+                };
+
+                if timbre != Timbre::Drums && current_program != Some(options.program(timbre)) {
+                    let program = options.program(timbre);
+                    write_event(&mut track, delta, &[0xC0 | channel, program]);
+                    current_program = Some(program);
+                    delta = 0;
+                }
+
+                let velocity = (volume * 100.0).round().clamp(1.0, 127.0) as u8;
+                write_event(&mut track, delta, &[0x90 | channel, key, velocity]);
+                write_event(&mut track, length, &[0x80 | channel, key, 0]);
+                delta = 0;
+            }
+            NoteKind::Rest => delta = delta.saturating_add(length),
+        }
+    }
+
+    write_event(&mut track, delta, &[0xFF, 0x2F, 0x00]);
+    track
+}
+
+/// Names a line's track after the first named instrument in its notes, or by its position if it doesn't have one.
+/// Only the notes are exported, so an instrument that only plays in the pickup doesn't name the track.
+fn track_name(line: &Line, index: usize) -> String {
+    line.notes
+        .iter()
+        .find_map(|note| match note.1 {
+            NoteKind::Pitched {
+                timbre: Timbre::Instrument(instrument),
+                ..
+            } if !instrument.name.is_empty() => Some(instrument.name.to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| format!("Instrument {}", index.saturating_add(1)))
+}
+
+/// Picks the General MIDI percussion key for a drum note, matching the sample chosen during playback
+fn drum_key(frequency: f32) -> u8 {
+    if frequency > C4.octave(1).semitone(6).0 {
+        49 // Crash Cymbal 1
+    } else if frequency > C4.semitone(6).0 {
+        42 // Closed Hi-Hat
+    } else if frequency < C4.semitone(-6).0 {
+        36 // Bass Drum 1
+    } else {
+        38 // Acoustic Snare
+    }
+}
+
+fn write_event(track: &mut Vec<u8>, delta: u32, event: &[u8]) {
+    write_variable_length(track, delta);
+    track.extend_from_slice(event);
+}
+
+/// Writes a text meta event of the given type at the start of a track
+fn write_text_event(track: &mut Vec<u8>, kind: u8, text: &str) {
+    write_variable_length(track, 0);
+    track.extend_from_slice(&[0xFF, kind]);
+    write_variable_length(track, u32::try_from(text.len()).unwrap_or(u32::MAX));
+    track.extend_from_slice(text.as_bytes());
+}
+
+/// Writes a MIDI variable-length quantity: 7 bits per byte, most significant first,
+/// with the top bit set on every byte except the last
+fn write_variable_length(track: &mut Vec<u8>, value: u32) {
+    let mut bytes = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        bytes.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    track.extend(bytes.into_iter().rev());
+}
+
+#[test]
+fn test_tracks_are_named_after_their_instruments() {
+    use crate::{instrument, instrument_tools::presets, note::quarter, sine, A4};
+
+    let named = instrument(presets::strings().with_name("Violins"), quarter(C4));
+    let unnamed = instrument(presets::strings().with_name(""), quarter(C4));
+    // The pickup isn't exported, so the violas don't name their track
+    let named_in_pickup = -Line::from(instrument(presets::strings().with_name("Violas"), quarter(C4)));
+    let piece = Piece::from(named) * sine(quarter(A4)) * unnamed * (named_in_pickup + sine(quarter(A4)));
+    let midi = piece.to_midi();
+
+    // A track name meta event at the start of each line's track
+    let has_name = |name: &str| {
+        let mut event = vec![0x00, 0xFF, 0x03, u8::try_from(name.len()).unwrap()];
+        event.extend_from_slice(name.as_bytes());
+        midi.windows(event.len()).any(|window| window == event)
+    };
+    assert!(has_name("Violins"));
+    assert!(has_name("Instrument 2"));
+    assert!(has_name("Instrument 3"));
+    assert!(has_name("Instrument 4"));
+    assert!(!has_name("Violas"));
+    assert!(!has_name("Instrument 1"));
+}
+
+#[test]
+fn test_instrument_programs_are_exported() {
+    use crate::{instrument, instrument_tools::presets, note::quarter};
+
+    let lead = presets::organ().with_midi_program(80);
+    let midi = Piece::from(instrument(lead, quarter(C4))).to_midi();
+
+    // A program change on the line's channel, at the start of its track
+    let has_program = |program: u8| midi.windows(3).any(|event| event == [0x00, 0xC0, program]);
+    assert!(has_program(80));
+    assert!(!has_program(presets::organ().midi_program));
+}
+
+#[test]
+fn test_metadata_is_exported_as_text_events() {
+    use crate::{note::quarter, piano};
+
+    let piece = Piece::from(piano(quarter(C4))).with_metadata(Metadata {
+        title: Some("Prelude".to_string()),
+        composer: Some("J. S. Bach".to_string()),
+        comments: vec!["Play softly".to_string()],
+        ..Default::default()
+    });
+    let midi = piece.to_midi();
+
+    let has_event = |kind: u8, text: &str| {
+        let mut event = vec![0x00, 0xFF, kind, u8::try_from(text.len()).unwrap()];
+        event.extend_from_slice(text.as_bytes());
+        midi.windows(event.len()).any(|window| window == event)
+    };
+    assert!(has_event(TRACK_NAME_EVENT, "Prelude"));
+    assert!(has_event(TEXT_EVENT, "Composer: J. S. Bach"));
+    assert!(has_event(TEXT_EVENT, "Play softly"));
+
+    // Without metadata, the tempo track starts straight away with the tempo
+    let plain = Piece::from(piano(quarter(C4))).to_midi();
+    assert_eq!(&plain[22..26], [0x00, 0xFF, 0x51, 0x03]);
+}
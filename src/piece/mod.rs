@@ -5,11 +5,13 @@ use std::{
 };
 
 use itertools::{EitherOrBoth, Itertools};
+use label::Label;
 use line::Line;
+use metadata::Metadata;
 
 use crate::{
-    note::{NoteKind, NotePitch, Timbre},
-    scales::tet12::{self, A4, C4},
+    note::{NoteKind, NoteLength, NotePitch, Timbre},
+    scales::tet12::{self, DisplayWith, PitchNaming, A4, C4},
     Note, Tet12,
 };
 
@@ -18,12 +20,87 @@ use crate::{
 /// Contains the `Line` type for representing sequential note sequences.
 pub mod line;
 
+/// Musical ornaments such as grace notes and trills.
+///
+/// Contains combinators for decorating lines with quick, expressive notes.
+pub mod ornament;
+
+/// Checks for likely mistakes in pieces.
+///
+/// Contains the `Lint` type reported by `Piece::lint`.
+pub mod lint;
+
+/// Naming sections of music.
+///
+/// Contains the `label` combinator and the `Label` type reported by `Piece::labels`.
+pub mod label;
+
+/// Notes that only play some of the time.
+///
+/// Contains the `maybe` combinator.
+pub mod chance;
+
+/// Gradual tempo changes.
+///
+/// Contains the `ritardando`, `accelerando`, and `fermata` combinators.
+pub mod tempo;
+
+/// Small, deliberate changes to when notes are played.
+///
+/// Contains the `nudge` combinator.
+pub mod groove;
+
+/// Tempo-synced wobbles in notes' level, pitch, or filter cutoff.
+///
+/// Contains the `lfo` combinator.
+pub mod modulation;
+
+/// Bars and pickups.
+///
+/// Contains `Piece::bars` for splitting a piece into bars, and `Piece::with_pickup` for starting with an incomplete bar.
+pub mod bars;
+
+/// Writing lines without repeating note lengths.
+///
+/// Contains the `PhraseBuilder` type, which gives bare pitches a default length.
+pub mod phrase;
+
+/// Titles, composers, and other information about pieces.
+///
+/// Contains the `Metadata` type stored in every `Piece`.
+pub mod metadata;
+
+/// Overviews of pieces, for checking what a render will produce before doing it.
+///
+/// Contains the `PieceSummary` type reported by `Piece::summary`.
+pub mod summary;
+
+/// Finding the notes in a window of time.
+///
+/// Contains the `NoteTimeline` type made by `Piece::timeline`.
+pub mod timeline;
+
+/// Canons and rounds made from a single line.
+///
+/// Contains the `canon` combinator.
+pub mod canon;
+
+/// Exporting pieces as MIDI files.
+///
+/// Contains the `MidiOptions` type used by `Piece::to_midi_with`.
+pub mod midi;
+
+mod binary;
+mod events;
+
 /// Represents a complete musical composition with multiple simultaneous parts.
 ///
 /// A `Piece` contains multiple `Line`s that play simultaneously, creating
 /// harmony and polyphony. This is the top-level structure for complete
 /// musical compositions.
 ///
+/// A piece also has [`Metadata`], such as its title, which doesn't affect how it sounds. See [`Piece::metadata`].
+///
 /// # Examples
 /// ```
 /// use symphoxy::prelude::*;
@@ -36,7 +113,7 @@ pub mod line;
 /// let chords = electric_guitar(whole(Chord::new([c4, e4, g4]))); // This is actually a piece
 ///
 /// // Combine into a piece
-/// let piece = Piece(vec![
+/// let piece = Piece::from(vec![
 ///     melody.clone(),
 ///     bass.clone()
 /// ]) * chords.clone();
@@ -45,7 +122,22 @@ pub mod line;
 /// let piece2 = melody * bass * chords;
 /// ```
 #[derive(Clone, Default, Debug, PartialEq)]
-pub struct Piece(pub Vec<Line>);
+pub struct Piece {
+    /// The lines of the piece, which all play at the same time
+    pub lines: Vec<Line>,
+    pub(crate) metadata: Metadata,
+}
+
+/// A note along with the time it starts playing within a piece.
+///
+/// See [`Piece::notes`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimedNote {
+    /// The time the note starts, in time units from the start of the piece
+    pub start: usize,
+    /// The note itself
+    pub note: Note,
+}
 
 impl Piece {
     /// Creates a new empty piece.
@@ -55,7 +147,7 @@ impl Piece {
     /// use symphoxy::prelude::*;
     ///
     /// let empty_piece = Piece::new();
-    /// assert_eq!(empty_piece.0.len(), 0);
+    /// assert_eq!(empty_piece.lines.len(), 0);
     /// ```
     pub fn new() -> Self {
         Default::default()
@@ -75,32 +167,285 @@ impl Piece {
     /// let loud_piece = piece.volume(1.5);  // 150% volume
     /// ```
     pub fn volume(&self, volume: f32) -> Self {
-        Piece(self.0.iter().map(|line| line.volume(volume)).collect())
+        Piece {
+            lines: self.lines.iter().map(|line| line.volume(volume)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
+    /// Stretches or compresses the piece in time by the given factor, without changing pitch.
+    ///
+    /// A factor of `2.0` plays the piece at half speed, and `0.5` at double speed.
+    /// Unlike changing the playback tempo, this changes the piece itself.
+    /// See [`Line::scale_time`] for how rounding and notes that become too long are handled.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + half(A4)) * bass(whole(C4));
+    ///
+    /// assert_eq!(piece.scale_time(2.0).length(), 2 * piece.length());
+    /// assert_eq!(piece.scale_time(0.5).length(), piece.length() / 2);
+    /// ```
+    pub fn scale_time(&self, factor: f32) -> Self {
+        Piece {
+            lines: self.lines.iter().map(|line| line.scale_time(factor)).collect(),
+            metadata: Metadata {
+                pickup_length: line::scale_time(self.pickup_length(), factor),
+                ..self.metadata().clone()
+            },
+        }
+    }
+
+    /// Shifts the pitch of the piece by `n` semitones, without changing its timing.
+    ///
+    /// For the note model this is a transposition, the same as [`Tet12::semitone`], so every pitched note
+    /// changes and the audio is rendered from the new pitches. Pitched custom sources follow the new
+    /// pitches, but unpitched ones, such as drum samples, don't change. To shift all of the rendered audio,
+    /// use `MusicPlayer::with_pitch_shift` instead. Shifts beyond the range of an `i16` are clamped.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + half(A4)) * bass(whole(C4));
+    /// let shifted = piece.clone().pitch_shift_semitones(-12);
+    ///
+    /// assert_eq!(shifted, piece.octave(-1));
+    /// assert_eq!(shifted.length(), piece.length());
+    /// ```
+    pub fn pitch_shift_semitones(self, n: i32) -> Piece {
+        let n = i16::try_from(n).unwrap_or(if n < 0 { i16::MIN } else { i16::MAX });
+        self.semitone(n)
+    }
+
+    /// Creates a new piece with all notes positioned at the specified pan.
+    ///
+    /// See [`Note::pan`] for how pan values map onto output channels.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4)) * bass(quarter(C4));
+    /// let right_piece = piece.pan(1.0);
+    /// ```
+    pub fn pan(&self, pan: f32) -> Self {
+        Piece {
+            lines: self.lines.iter().map(|line| line.pan(pan)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
+    /// Plays `a` then `b`, fading `a` out while `b` fades in over the last `overlap` of `a`.
+    ///
+    /// The fades are linear, and always add up to the original volume. Since a note can only have
+    /// one volume, each note in the overlap is faded according to the time at its middle.
+    /// The overlap is clamped to the length of the shorter piece. Like `*`, it keeps the metadata of
+    /// `a`, or of `b` if `a` has none.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let a = Piece::from(piano(quarter(C4) * 8));
+    /// let b = Piece::from(bass(quarter(C4) * 8));
+    /// let medley = Piece::crossfade(a.clone(), b.clone(), NoteLength::HALF);
+    ///
+    /// assert_eq!(medley.length(), a.length() + b.length() - 8);
+    ///
+    /// // Every note of `a` comes first, then every note of `b`
+    /// let volumes: Vec<_> = medley
+    ///     .notes()
+    ///     .map(|timed| match timed.note.1 {
+    ///         NoteKind::Pitched { volume, .. } => (timed.start, volume),
+    ///         NoteKind::Rest => unreachable!(),
+    ///     })
+    ///     .collect();
+    ///
+    /// // The two quarter notes of each piece in the overlap fade in opposite directions
+    /// assert_eq!(volumes[6..8], [(24, 0.75), (28, 0.25)]);
+    /// assert_eq!(volumes[8..10], [(24, 0.25), (28, 0.75)]);
+    ///
+    /// // So the volume never jumps at the seam
+    /// for line in [&volumes[..8], &volumes[8..]] {
+    ///     assert!(line.windows(2).all(|pair| (pair[0].1 - pair[1].1).abs() <= 0.5));
+    /// }
+    ///
+    /// // Overlapping by more than a whole piece plays them together
+    /// assert_eq!(Piece::crossfade(a, b, NoteLength(1000)).length(), 32);
+    /// ```
+    #[expect(clippy::arithmetic_side_effects, reason = "Offsets are within the length of the crossfaded piece")]
+    pub fn crossfade(a: Piece, b: Piece, overlap: NoteLength) -> Piece {
+        let a_length = a.length();
+        let overlap = overlap.min(NoteLength(u16::try_from(a_length.min(b.length())).unwrap_or(u16::MAX)));
+        let fade_start = a_length.saturating_sub(overlap.0 as usize);
+
+        // `a` fades out and `b` fades in linearly over the overlap, so their volumes always add up
+        let faded_a = Piece {
+            lines: a
+                .lines
+                .iter()
+                .map(|line| {
+                    line.fade_from(fade_start, overlap, |progress| {
+                        line::FadeCurve::Linear.fade_out_gain(progress)
+                    })
+                })
+                .collect(),
+            metadata: a.metadata,
+        };
+        let faded_b = b.fade_in(overlap, line::FadeCurve::Linear);
+
+        // Start every line of `b` at the beginning of the overlap
+        let mut rests = Vec::new();
+        push_rests_until(&mut rests, &mut 0, fade_start);
+        let offset = Line::from(rests);
+
+        let shifted_b = Piece {
+            lines: faded_b.lines.into_iter().map(|line| offset.clone() + line).collect(),
+            metadata: faded_b.metadata,
+        };
+
+        faded_a * shifted_b
+    }
+
+    /// Fades the piece in from silence over its first `length`, along `curve`.
+    ///
+    /// Every line fades together. See [`Line::fade_in`] for how each note is faded.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Line::from(piano(whole(C4))) * bass(half(REST) + half(C4.octave(-1)));
+    /// let faded = piece.fade_in(NoteLength::WHOLE, FadeCurve::Linear);
+    ///
+    /// assert_eq!(faded.lines[0], Line::from(piano(whole(C4)).volume(0.5)));
+    /// assert_eq!(faded.lines[1], half(REST) + bass(half(C4.octave(-1))).volume(0.75));
+    /// ```
+    pub fn fade_in(&self, length: NoteLength, curve: line::FadeCurve) -> Piece {
+        Piece {
+            lines: self.lines.iter().map(|line| line.fade_in(length, curve)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
+    /// Fades the piece out to silence over its last `length`, along `curve`.
+    ///
+    /// Every line fades together, ending with the longest one, so shorter lines may end partway through the
+    /// fade. See [`Line::fade_out`] for how each note is faded.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(half(C4) + half(A4)) * bass(half(C4.octave(-1)));
+    /// let faded = piece.fade_out(NoteLength::WHOLE, FadeCurve::Linear);
+    ///
+    /// assert_eq!(faded.lines[0], piano(half(C4)).volume(0.75) + piano(half(A4)).volume(0.25));
+    /// assert_eq!(faded.lines[1], Line::from(bass(half(C4.octave(-1))).volume(0.75)));
+    /// ```
+    pub fn fade_out(&self, length: NoteLength, curve: line::FadeCurve) -> Piece {
+        let start = self.length().saturating_sub(length.0 as usize);
+        Piece {
+            lines: self
+                .lines
+                .iter()
+                .map(|line| line.fade_from(start, length, |progress| curve.fade_out_gain(progress)))
+                .collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+}
+
+/// Multiplies the volume of a note, leaving rests unchanged.
+fn scale_volume(note: Note, factor: f32) -> Note {
+    match note.1 {
+        NoteKind::Pitched { volume, .. } => note.volume(volume * factor),
+        NoteKind::Rest => note,
+    }
+}
+
+/// Gets every note in a line, including rests, along with when it starts.
+pub(crate) fn timed_notes(line: &Line) -> impl Iterator<Item = TimedNote> + '_ {
+    line.notes.iter().scan(0usize, |time, &note| {
+        let start = *time;
+        *time = time.saturating_add(note.0 .0 as usize);
+        Some(TimedNote { start, note })
+    })
+}
+
+/// Rebuilds a line from timed notes, filling gaps with rests and shortening overlapping notes.
+///
+/// The new line is at least as long as the original, and keeps its pickup.
+#[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+#[expect(clippy::cast_possible_truncation, reason = "Notes are shortened, so they still fit in a u16")]
+fn rebuild_line(original: &Line, mut notes: Vec<TimedNote>) -> Line {
+    notes.sort_by_key(|timed| timed.start);
+
+    let mut rebuilt: Vec<Note> = Vec::with_capacity(notes.len());
+    let mut time = 0usize;
+
+    for (index, timed) in notes.iter().enumerate() {
+        push_rests_until(&mut rebuilt, &mut time, timed.start);
+
+        // Don't let this note run into the next one
+        let mut length = timed.note.0 .0 as usize;
+        if let Some(next) = notes.get(index + 1) {
+            length = length.min(next.start.saturating_sub(time));
+        }
+
+        rebuilt.push(Note(NoteLength(length as u16), timed.note.1));
+        time += length;
+    }
+
+    push_rests_until(&mut rebuilt, &mut time, original.length());
+
+    Line {
+        notes: rebuilt,
+        pickup: original.pickup.clone(),
+        hold_pickup: original.hold_pickup,
+        labels: original.labels.clone(),
+    }
+}
+
+/// Pushes rests until `time` reaches `until`, splitting them if they are too long for one note.
+#[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+#[expect(clippy::cast_possible_truncation, reason = "Rests are split into chunks that fit in a u16")]
+fn push_rests_until(notes: &mut Vec<Note>, time: &mut usize, until: usize) {
+    while *time < until {
+        let length = (until - *time).min(u16::MAX as usize);
+        notes.push(Note(NoteLength(length as u16), NoteKind::Rest));
+        *time += length;
     }
 }
 
 impl From<Line> for Piece {
     fn from(value: Line) -> Self {
-        Piece(vec![value])
+        Piece::from(vec![value])
     }
 }
 
 impl From<Note> for Piece {
     fn from(value: Note) -> Self {
-        Piece(vec![Line::from(value)])
+        Piece::from(vec![Line::from(value)])
     }
 }
 
 // Additional From implementations for Piece ergonomics
 impl From<Vec<Line>> for Piece {
     fn from(lines: Vec<Line>) -> Self {
-        Piece(lines)
+        Piece {
+            lines,
+            metadata: Metadata::default(),
+        }
     }
 }
 
 impl<const N: usize> From<[Line; N]> for Piece {
     fn from(lines: [Line; N]) -> Self {
-        Piece(lines.to_vec())
+        Piece::from(lines.to_vec())
     }
 }
 
@@ -119,17 +464,215 @@ impl Piece {
     /// assert_eq!(notes_at_start.len(), 2); // Piano C4 and bass A4
     /// ```
     pub fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.0
+        self.lines
             .clone()
             .into_iter()
             .flat_map(move |l| l.get_notes_at_instant(instant).collect::<Vec<_>>())
     }
 
+    /// Returns every pitched note in the piece, along with when it starts.
+    ///
+    /// Notes are ordered line by line, then by start time within each line. Rests are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(REST) + half(A4)) * bass(whole(C4));
+    /// let notes: Vec<_> = piece.notes().collect();
+    ///
+    /// assert_eq!(notes.len(), 3);
+    /// assert_eq!(notes[1].start, 8);
+    /// assert_eq!(notes[1].note, piano(half(A4)));
+    /// ```
+    pub fn notes(&self) -> impl Iterator<Item = TimedNote> + '_ {
+        self.lines
+            .iter()
+            .flat_map(|line| timed_notes(line).filter(|timed| !timed.note.is_rest()))
+    }
+
+    /// Returns the most pitched notes that sound at the same time anywhere in the piece.
+    ///
+    /// This is useful for finding accidentally dense sections, or for estimating how much work
+    /// rendering the piece will take. A note that ends exactly when another starts doesn't overlap it,
+    /// and zero-length notes never sound. Notes that only play with some probability are counted as
+    /// if they always play.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4) + half(C4));
+    /// let chord = piano(half_chord([C4, C4.semitone(4), C4.semitone(7)]));
+    /// let bass_line = bass(whole(C4.octave(-1)));
+    ///
+    /// // The chord and the melody overlap for the first half note, and the bass plays throughout
+    /// let piece = chord * melody * bass_line;
+    /// assert_eq!(piece.max_polyphony(), 5);
+    /// assert_eq!(Piece::new().max_polyphony(), 0);
+    /// ```
+    pub fn max_polyphony(&self) -> usize {
+        // Each note starts and ends a voice. Sorting ends before starts at the same time means
+        // that notes which only touch aren't counted as overlapping.
+        let mut events: Vec<(usize, bool)> = self
+            .notes()
+            .filter(|timed| timed.note.0 .0 > 0)
+            .flat_map(|timed| {
+                [
+                    (timed.start, true),
+                    (timed.start.saturating_add(timed.note.0 .0 as usize), false),
+                ]
+            })
+            .collect();
+        events.sort_unstable();
+
+        let mut voices: usize = 0;
+        let mut most_voices = 0;
+        for (_, starts) in events {
+            if starts {
+                voices = voices.saturating_add(1);
+                most_voices = most_voices.max(voices);
+            } else {
+                voices = voices.saturating_sub(1);
+            }
+        }
+        most_voices
+    }
+
+    /// Checks whether two pieces play the same notes at the same times, however they were built.
+    ///
+    /// Derived equality compares how the piece is put together, so `(a + b) + c` and `a + (b + c)` can differ
+    /// even though they sound the same. This instead compares every pitched note and when it starts, ignoring
+    /// which line each note is in and the order of notes that start together. Rests, zero-length notes, leftover
+    /// pickups, and metadata don't affect how a piece sounds, so they're ignored too.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4));
+    /// let bass_line = bass(Line::from(half(C4.octave(-1))));
+    ///
+    /// // Stacking lines in either order gives a different structure, but the same music
+    /// assert_ne!(melody.clone() * bass_line.clone(), bass_line.clone() * melody.clone());
+    /// assert!((melody.clone() * bass_line.clone()).musically_eq(&(bass_line * melody.clone())));
+    ///
+    /// assert!(!Piece::from(melody.clone()).musically_eq(&Piece::from(melody.volume(0.5))));
+    /// ```
+    pub fn musically_eq(&self, other: &Piece) -> bool {
+        let sounding = |piece: &Piece| {
+            let mut notes: Vec<TimedNote> = piece.notes().filter(|timed| timed.note.0 .0 > 0).collect();
+            notes.sort_by_key(|timed| (timed.start, timed.note.0 .0));
+            notes
+        };
+        let (ours, theirs) = (sounding(self), sounding(other));
+        let key = |timed: &TimedNote| (timed.start, timed.note.0 .0);
+        if ours.len() != theirs.len() || !ours.iter().map(key).eq(theirs.iter().map(key)) {
+            return false;
+        }
+
+        // Notes have no ordering of their own, so those with the same start and length are matched up by equality
+        let mut group_start = 0;
+        while let Some(first) = ours.get(group_start) {
+            let group_end = ours[group_start..]
+                .iter()
+                .position(|timed| key(timed) != key(first))
+                .map_or(ours.len(), |len| group_start.saturating_add(len));
+            let mut unmatched = theirs[group_start..group_end].to_vec();
+            for timed in &ours[group_start..group_end] {
+                match unmatched.iter().position(|other| other == timed) {
+                    Some(index) => {
+                        unmatched.swap_remove(index);
+                    }
+                    None => return false,
+                }
+            }
+            group_start = group_end;
+        }
+        true
+    }
+
+    /// Creates a new piece by transforming every pitched note with the given function.
+    ///
+    /// Each line is transformed separately, so the parallel structure of the piece is kept.
+    /// The function may change a note's start time or length; the line is rebuilt with rests
+    /// filling any gaps, and a note that would overlap the next one is shortened to fit.
+    /// Pickups are left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) * 4));
+    ///
+    /// // Accent the first beat of every half note
+    /// let accented = piece.map_notes(|mut timed| {
+    ///     if timed.start % 8 == 0 {
+    ///         timed.note = timed.note.volume(1.5);
+    ///     }
+    ///     timed
+    /// });
+    ///
+    /// assert_eq!(accented.lines[0].notes[0], piano(quarter(C4)).volume(1.5));
+    /// assert_eq!(accented.lines[0].notes[1], piano(quarter(C4)));
+    /// assert_eq!(accented.length(), piece.length());
+    /// ```
+    pub fn map_notes(&self, mut f: impl FnMut(TimedNote) -> TimedNote) -> Piece {
+        Piece {
+            lines: self
+                .lines
+                .iter()
+                .map(|line| {
+                    let notes = timed_notes(line)
+                        .map(|timed| if timed.note.is_rest() { timed } else { f(timed) })
+                        .collect();
+                    rebuild_line(line, notes)
+                })
+                .collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
+    /// Creates a new piece keeping only the pitched notes that match the predicate.
+    ///
+    /// Removed notes are replaced by rests, so the timing of every other note is unchanged.
+    /// Pickups are left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let c3 = C4.octave(-1);
+    /// let piece = piano(quarter(C4) + quarter(c3.semitone(-1)) + quarter(A4)) * bass(whole(c3.octave(-1)));
+    ///
+    /// // Drop everything below C3
+    /// let high = piece.filter_notes(|timed| timed.note.pitch().is_some_and(|pitch| pitch.0 >= c3.0));
+    ///
+    /// assert_eq!(high.lines[0].notes[1], quarter(REST));
+    /// assert_eq!(high.notes().count(), 2);
+    /// assert_eq!(high.length(), piece.length());
+    /// ```
+    pub fn filter_notes(&self, mut predicate: impl FnMut(&TimedNote) -> bool) -> Piece {
+        Piece {
+            lines: self
+                .lines
+                .iter()
+                .map(|line| {
+                    let notes = timed_notes(line)
+                        .filter(|timed| !timed.note.is_rest() && predicate(timed))
+                        .collect();
+                    rebuild_line(line, notes)
+                })
+                .collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
     /// As opposed to `get_notes_at_instant`, this gets any note which would
     /// be playing during a given instant, rather than the notes which start at a given instant.
     #[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking, almost always safe")]
     pub fn get_notes_during_instant(&self, instant: usize) -> impl Iterator<Item = Note> {
-        self.0.clone().into_iter().filter_map(move |l| {
+        self.lines.clone().into_iter().filter_map(move |l| {
             // get note at time
             let mut time_acc = 0;
             for note in l.notes.clone() {
@@ -143,6 +686,68 @@ impl Piece {
         })
     }
 
+    /// Cuts out the part of the piece from time unit `range.start` up to `range.end`, as a piece of its own.
+    ///
+    /// Every line is cut the same way as by [`Line::slice`], so notes crossing either end of the range are
+    /// shortened to fit it, and the slice starts at time 0. The metadata is kept, but the pickup only if the
+    /// range starts at 0. This is useful for rendering
+    /// just part of a long piece, such as its first few seconds with
+    /// [`MusicPlayer::time_unit_at`](crate::MusicPlayer::time_unit_at).
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) * 8) * bass(double_whole(C4.octave(-1)));
+    ///
+    /// // The second bar, with the second half of the bass note
+    /// let bar = piece.slice(16..32);
+    /// assert_eq!(bar.length(), 16);
+    /// assert_eq!(bar.lines[0], piano(quarter(C4) * 4));
+    /// assert_eq!(bar.lines[1], bass(Line::from(whole(C4.octave(-1)))));
+    /// ```
+    pub fn slice(&self, range: std::ops::Range<usize>) -> Piece {
+        Piece {
+            lines: self.lines.iter().map(|line| line.slice(range.clone())).collect(),
+            metadata: Metadata {
+                pickup_length: if range.start == 0 {
+                    self.pickup_length().min(range.end)
+                } else {
+                    0
+                },
+                ..self.metadata().clone()
+            },
+        }
+    }
+
+    /// Cleans up notes that last less than `min` at `tempo_bpm` in every line, such as the near-zero notes left
+    /// by quantizing or importing.
+    ///
+    /// See [`Line::cull_short_notes`] for what each [`ShortNotePolicy`](line::ShortNotePolicy) does. Every
+    /// line keeps its length.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + Line::from(C4.with_length(NoteLength(0))) + quarter(A4)) * bass(half(C4));
+    /// let cleaned = piece.cull_short_notes(Duration::from_millis(50), 300, ShortNotePolicy::Remove);
+    ///
+    /// assert_eq!(cleaned.lines[0], piano(quarter(C4) + quarter(A4)));
+    /// assert_eq!(cleaned.lines[1], piece.lines[1]);
+    /// ```
+    pub fn cull_short_notes(&self, min: std::time::Duration, tempo_bpm: u32, policy: line::ShortNotePolicy) -> Piece {
+        Piece {
+            lines: self
+                .lines
+                .iter()
+                .map(|line| line.cull_short_notes(min, tempo_bpm, policy))
+                .collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
     /// Returns the total duration of the piece in time units.
     ///
     /// This is the length of the longest line in the piece, since all lines
@@ -159,7 +764,38 @@ impl Piece {
     /// assert_eq!(piece.length(), 16); // Length of the longest line
     /// ```
     pub fn length(&self) -> usize {
-        self.0.iter().map(|line| line.length()).max().unwrap_or_default()
+        self.lines.iter().map(|line| line.length()).max().unwrap_or_default()
+    }
+
+    /// Finds the tempo that makes the piece last `seconds`, for passing to [`MusicPlayer::new_file`](crate::MusicPlayer::new_file).
+    ///
+    /// Like every tempo in symphoxy, it's in sixteenth notes per minute. Since the tempo is a whole number, the
+    /// piece can be a little off the requested length: the error is at most half a beat per minute, which is
+    /// within a fraction of a percent for most pieces. The tempo is at least 1, which is also what an empty
+    /// piece gives, and a length of zero seconds gives the fastest possible tempo.
+    ///
+    /// Rendering rounds each time unit to a whole millisecond by default, which adds up at fast tempos. Render
+    /// with `RoundMode::Compensated` to keep to the fitted length.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // 8 bars of 4/4 is 128 sixteenth notes
+    /// let piece = Piece::from(piano(whole(C4) * 8));
+    ///
+    /// // To last 30 seconds, the piece needs 256 sixteenths per minute
+    /// assert_eq!(piece.fit_to_seconds(30.0), 256);
+    /// ```
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss,
+        reason = "Piece lengths are far below 2^52, and the tempo is clamped to the range of a u32"
+    )]
+    pub fn fit_to_seconds(&self, seconds: f32) -> u32 {
+        let bpm = self.length() as f64 * 60.0 / f64::from(seconds.max(0.0));
+        bpm.round().clamp(1.0, f64::from(u32::MAX)) as u32
     }
 }
 
@@ -167,7 +803,10 @@ impl Mul<Piece> for Piece {
     type Output = Piece;
 
     fn mul(self, rhs: Piece) -> Self::Output {
-        Piece([self.0, rhs.0].concat())
+        Piece {
+            lines: [self.lines, rhs.lines].concat(),
+            metadata: self.metadata.or(rhs.metadata),
+        }
     }
 }
 
@@ -196,17 +835,19 @@ impl Add<Piece> for Piece {
     fn add(self, rhs: Piece) -> Self::Output {
         let self_length = self.length() as u16;
         let rhs_length = rhs.length() as u16;
-        Piece(
-            self.0
+        Piece {
+            lines: self
+                .lines
                 .into_iter()
-                .zip_longest(rhs.0.iter())
+                .zip_longest(rhs.lines.iter())
                 .map(|either_or_both| match either_or_both {
                     EitherOrBoth::Both(first, second) => first.clone() + second.clone(),
                     EitherOrBoth::Left(first) => first.clone().extend(rhs_length),
                     EitherOrBoth::Right(second) => Line::new().extend(self_length) + second.clone(),
                 })
                 .collect(),
-        )
+            metadata: self.metadata.or(rhs.metadata),
+        }
     }
 }
 
@@ -223,7 +864,7 @@ impl Add<Note> for Piece {
     #[expect(clippy::arithmetic_side_effects, reason = "Arithmetic implementation")]
     fn add(self, rhs: Note) -> Self::Output {
         let line: Line = rhs.into();
-        self + Piece(vec![line])
+        self + Piece::from(line)
     }
 }
 
@@ -238,7 +879,7 @@ impl Mul<Line> for Piece {
 
         // Extend pieces to same length for layering
         let extended_self: Vec<_> = self
-            .0
+            .lines
             .into_iter()
             .map(|line| {
                 let padding = new_len.saturating_sub(self_len) as u16;
@@ -249,7 +890,10 @@ impl Mul<Line> for Piece {
         let padding = new_len.saturating_sub(rhs_len) as u16;
         let extended_rhs = vec![rhs.extend(padding)];
 
-        Piece([extended_self, extended_rhs].concat())
+        Piece {
+            lines: [extended_self, extended_rhs].concat(),
+            metadata: self.metadata,
+        }
     }
 }
 
@@ -257,22 +901,48 @@ impl Mul<Note> for Piece {
     type Output = Piece;
 
     fn mul(self, rhs: Note) -> Self::Output {
-        Piece([self.0, vec![rhs.into()]].concat())
+        Piece {
+            lines: [self.lines, vec![rhs.into()]].concat(),
+            metadata: self.metadata,
+        }
     }
 }
 
 impl std::fmt::Display for Piece {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_with(PitchNaming::Scientific))
+    }
+}
+
+impl Piece {
+    /// Formats the piece as a piano roll like its `Display` output, with pitches named in `naming`.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(piano(whole(C4)));
+    /// assert_eq!(piece.display_with(PitchNaming::Scientific).to_string(), piece.to_string());
+    /// assert!(piece.display_with(PitchNaming::Solfege).to_string().contains("do "));
+    /// ```
+    pub fn display_with(&self, naming: PitchNaming) -> impl std::fmt::Display + '_ {
+        DisplayWith(self, naming)
+    }
+}
+
+impl std::fmt::Display for DisplayWith<'_, Piece> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let DisplayWith(piece, naming) = *self;
         let black_keys = [
             false, true, false, true, false, false, true, false, true, false, true, false,
         ];
 
-        for bar_group in 0..self.length().div_ceil(64) {
+        for bar_group in 0..piece.length().div_ceil(64) {
             let (highest_semitone, lowest_semitone) = {
                 let (mut highest, mut lowest) = (i16::MIN, i16::MAX);
                 #[expect(clippy::arithmetic_side_effects, reason = "Guaranteed to be safe, manual bounds checking")]
                 for time in (bar_group * 64)..(bar_group * 64 + 64) {
-                    for note in self.get_notes_during_instant(time) {
+                    for note in piece.get_notes_during_instant(time) {
                         if let NoteKind::Pitched {
                             pitch: NotePitch(frequency),
                             ..
@@ -313,7 +983,7 @@ impl std::fmt::Display for Piece {
                     // Add barline
                     if bar_group_time % 16 == 0 {
                         if bar_group_time == 0 {
-                            line_str.push_str(&format!("{: <3}", tet12::get_note_name_with_octave(pitch, A4)));
+                            line_str.push_str(&format!("{: <3}", tet12::get_note_name_with_naming(pitch, A4, naming)));
                             if black_key {
                                 line_str.push_str("║ ║");
                             } else {
@@ -339,9 +1009,9 @@ impl std::fmt::Display for Piece {
                     };
 
                     // Find notes at this time on this line
-                    if let Some(_note) = self.get_notes_at_instant(time).find(note_matches_line) {
+                    if let Some(_note) = piece.get_notes_at_instant(time).find(note_matches_line) {
                         line_str.push('■');
-                    } else if let Some(_note) = self.get_notes_during_instant(time).find(note_matches_line) {
+                    } else if let Some(_note) = piece.get_notes_during_instant(time).find(note_matches_line) {
                         line_str.push('≡');
                     } else {
                         line_str.push(blank_space);
@@ -386,9 +1056,9 @@ impl std::fmt::Display for Piece {
                     };
 
                     // Find notes at this time on this line
-                    if let Some(_note) = self.get_notes_at_instant(time).find(note_matches_line) {
+                    if let Some(_note) = piece.get_notes_at_instant(time).find(note_matches_line) {
                         line_str.push('■');
-                    } else if let Some(_note) = self.get_notes_during_instant(time).find(note_matches_line) {
+                    } else if let Some(_note) = piece.get_notes_during_instant(time).find(note_matches_line) {
                         line_str.push('≡');
                     } else {
                         line_str.push(' ');
@@ -407,7 +1077,9 @@ impl std::fmt::Display for Piece {
     }
 }
 
+/// A trait for musical elements that can be played backwards.
 pub trait Reversable {
+    /// Reverses the order of the notes, so the element plays from end to start.
     fn reverse(self) -> Self;
 }
 
@@ -419,18 +1091,25 @@ impl Reversable for Piece {
         let mut piece = self;
 
         let max_length = piece.length();
-        piece.0 = piece
-            .0
+        piece.lines = piece
+            .lines
             .into_iter()
             .map(|line| line.extend((max_length - line.length()) as u16))
             .collect();
 
-        Piece(piece.0.into_iter().map(|line| line.reverse()).collect())
+        Piece {
+            lines: piece.lines.into_iter().map(|line| line.reverse()).collect(),
+            metadata: Metadata {
+                pickup_length: 0,
+                ..piece.metadata
+            },
+        }
     }
 }
 
 impl Reversable for Line {
     fn reverse(self) -> Self {
+        let length = self.length();
         Line {
             notes: [
                 self.notes.into_iter().rev().collect::<Vec<_>>(),
@@ -439,6 +1118,211 @@ impl Reversable for Line {
             .concat(),
             pickup: Vec::new(),
             hold_pickup: false,
+            labels: self
+                .labels
+                .into_iter()
+                .map(|label| Label {
+                    start: length.saturating_sub(label.end),
+                    end: length.saturating_sub(label.start),
+                    ..label
+                })
+                .collect(),
         }
     }
 }
+
+#[test]
+fn test_max_polyphony_sweeps_overlaps() {
+    use crate::prelude::*;
+
+    // Notes that only touch don't overlap, and neither do zero-length notes
+    let touching = Piece::from(piano(quarter(C4) + quarter(A4))) * piano(half(REST) + quarter(C4));
+    assert_eq!(touching.max_polyphony(), 1);
+    let zero_length = piano(quarter(C4)) * piano(note(A4, NoteLength(0)));
+    assert_eq!(zero_length.max_polyphony(), 1);
+
+    // Staggered entrances stack up until the first one ends
+    let staggered = (0..4)
+        .map(|entrance| Piece::from(piano(note(REST, NoteLength(entrance * 2)) + whole(C4))))
+        .fold(Piece::new(), |piece, line| piece * line);
+    assert_eq!(staggered.max_polyphony(), 4);
+
+    // The densest moment can be in the middle of the piece
+    let late = |pitch| piano(quarter(REST) + quarter(pitch));
+    let middle = Piece::from(piano(whole(C4))) * late(A4) * late(C4.octave(1)) * late(A4.octave(1));
+    assert_eq!(middle.max_polyphony(), 4);
+}
+
+#[test]
+fn test_addition_is_musically_associative() {
+    use crate::prelude::*;
+
+    let a = Piece::from(piano(quarter(C4) + eighth(A4))) * bass(half(C4.octave(-1)));
+    let b = Piece::from(electric_guitar(dotted(quarter)(A4) + sixteenth(C4)));
+    let c = Piece::from(vec![
+        -Line::from(piano(eighth(A4))) + piano(half(C4)),
+        bass(quarter(REST) + quarter(A4.octave(-1))),
+        sine(Line::from(whole(C4))),
+    ]);
+
+    let left = (a.clone() + b.clone()) + c.clone();
+    let right = a.clone() + (b.clone() + c.clone());
+    assert!(left.musically_eq(&right));
+    assert!(right.musically_eq(&left));
+
+    // Different music still differs
+    assert!(!left.musically_eq(&(a.clone() + c.clone() + b.clone())));
+    assert!(!left.musically_eq(&(a + b)));
+    assert!(Piece::new().musically_eq(&Piece::from(quarter(REST))));
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_fitted_tempo_renders_for_the_requested_time() {
+    use crate::{prelude::*, MusicPlayer, RoundMode};
+
+    let piece = piano(quarter(C4) + dotted(half)(A4) + eighth(C4)) * bass(whole(C4.octave(-1)) * 2);
+    for seconds in [1.0, 7.3, 12.5, 30.0] {
+        let bpm = piece.fit_to_seconds(seconds);
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        MusicPlayer::new_file(bpm, 1.0, 8000)
+            .with_round_mode(RoundMode::Compensated)
+            .write_wav_to(piece.clone(), &mut buffer)
+            .unwrap();
+        let reader = hound::WavReader::new(std::io::Cursor::new(buffer.into_inner())).unwrap();
+        let rendered = f64::from(reader.duration()) / 8000.0;
+        // Rounding the tempo to a whole number is off by at most half a unit of it
+        let tolerance = f64::from(seconds) * 0.5 / f64::from(bpm) + 1.0 / 8000.0;
+        assert!(
+            (rendered - f64::from(seconds)).abs() <= tolerance,
+            "rendered {rendered}s at {bpm} BPM, wanted {seconds}s"
+        );
+    }
+
+    assert_eq!(Piece::new().fit_to_seconds(10.0), 1);
+    assert_eq!(piece.fit_to_seconds(0.0), u32::MAX);
+}
+
+#[test]
+fn test_culling_removes_only_short_notes() {
+    use crate::prelude::*;
+    use std::time::Duration;
+
+    let blip = |pitch: NotePitch, length: u16| piano(Line::from(pitch.with_length(NoteLength(length))));
+    let melody = piano(quarter(C4)) + blip(A4, 1) + quarter(REST) + blip(C4, 0) + piano(eighth(A4)) + blip(C4, 2);
+    let drone = bass(Line::from(whole(C4.octave(-1))));
+    let piece = melody.clone() * drone.clone();
+
+    // At 240 BPM, a sixteenth lasts 250 ms, so only notes under two sixteenths are short
+    let culled = piece.cull_short_notes(Duration::from_millis(400), 240, ShortNotePolicy::Remove);
+    assert_eq!(
+        culled.lines[0],
+        piano(quarter(C4)) + sixteenth(REST) + quarter(REST) + piano(eighth(A4)) + blip(C4, 2)
+    );
+    assert_eq!(culled.lines[1], drone);
+    assert_eq!(culled.length(), piece.length());
+
+    // Every long note is where it was
+    let starts = |line: &Line| -> Vec<TimedNote> {
+        timed_notes(line)
+            .filter(|timed| timed.note.0 .0 >= 2 && !timed.note.is_rest())
+            .collect()
+    };
+    assert_eq!(starts(&culled.lines[0]), starts(&melody));
+
+    // Nothing is short enough with a lower minimum, apart from notes of no length
+    let lenient = piece.cull_short_notes(Duration::from_millis(100), 240, ShortNotePolicy::Remove);
+    assert_eq!(lenient.lines[0].notes.len(), melody.notes.len() - 1);
+}
+
+#[test]
+fn test_exponential_fade_out_gets_quieter_sooner_than_linear() {
+    use crate::prelude::*;
+
+    let piece = Piece::from(sine(sixteenth(A4) * 16) + quarter(REST));
+    let volumes = |piece: Piece| -> Vec<f32> {
+        piece
+            .notes()
+            .map(|timed| match timed.note.1 {
+                NoteKind::Pitched { volume, .. } => volume,
+                NoteKind::Rest => unreachable!(),
+            })
+            .collect()
+    };
+    let linear = volumes(piece.fade_out(NoteLength::WHOLE, FadeCurve::default()));
+    let exponential = volumes(piece.fade_out(NoteLength::WHOLE, FadeCurve::Exponential));
+    let s_curve = volumes(piece.fade_out(NoteLength::WHOLE, FadeCurve::SCurve));
+
+    // The fade covers the last whole note, the rest included, so the first notes are untouched
+    assert_eq!(linear[..4], [1.0; 4]);
+    assert_eq!(exponential[..4], [1.0; 4]);
+    assert_eq!(
+        linear[4..],
+        [0.96875, 0.90625, 0.84375, 0.78125, 0.71875, 0.65625, 0.59375, 0.53125, 0.46875, 0.40625, 0.34375, 0.28125]
+    );
+
+    // Exponential is quieter at every point in the fade, so it passes half volume well before linear does
+    assert!(linear[4..]
+        .iter()
+        .zip(&exponential[4..])
+        .all(|(linear, exponential)| exponential < linear));
+    let below_half = |volumes: &[f32]| volumes.iter().position(|&volume| volume < 0.5);
+    assert!(below_half(&exponential).unwrap() + 4 <= below_half(&linear).unwrap());
+
+    // An S-curve lingers near full volume, then passes half volume at the middle like linear does
+    assert!(s_curve[4] > linear[4]);
+    assert_eq!(below_half(&s_curve), below_half(&linear));
+
+    // Fading in mirrors the curve in time, so an exponential fade in starts slowly
+    let linear_in = volumes(piece.fade_in(NoteLength::WHOLE, FadeCurve::Linear));
+    let exponential_in = volumes(piece.fade_in(NoteLength::WHOLE, FadeCurve::Exponential));
+    assert!(exponential_in
+        .iter()
+        .zip(&linear_in)
+        .all(|(exponential, linear)| exponential < linear));
+}
+
+#[test]
+fn test_crossfade_keeps_metadata_from_either_piece() {
+    use crate::{prelude::*, Metadata};
+
+    let titled = |title: &str| Metadata {
+        title: Some(title.to_string()),
+        ..Default::default()
+    };
+    let a = Piece::from(piano(whole(C4)));
+    let b = Piece::from(bass(whole(C4))).with_metadata(titled("Second"));
+
+    let medley = Piece::crossfade(a.clone(), b.clone(), NoteLength::HALF);
+    assert_eq!(medley.metadata(), &titled("Second"));
+
+    let medley = Piece::crossfade(a.with_metadata(titled("Medley")), b, NoteLength::HALF);
+    assert_eq!(medley.metadata(), &titled("Medley"));
+}
+
+#[test]
+fn test_scaling_splits_notes_too_long_for_a_note_length() {
+    use crate::prelude::*;
+
+    let long = piano(Line::from(note(C4, NoteLength(40_000))));
+    let line = long.clone() + piano(quarter(A4));
+    let piece = line.clone() * bass(note(REST, NoteLength(40_000)) + quarter(C4));
+
+    // The long note is held in two parts, so the note after it still starts at 80000
+    let scaled = line.scale_time(2.0);
+    assert_eq!(
+        scaled,
+        piano(note(C4, NoteLength(u16::MAX)) + note(C4, NoteLength(14_465)) + half(A4))
+    );
+    assert_eq!(scaled.length(), 80_008);
+
+    // Lines stay aligned, and a note that only just fits isn't split
+    let scaled = piece.scale_time(2.0);
+    assert_eq!(scaled.lines[1].notes.len(), 3);
+    assert_eq!(scaled.notes().map(|timed| timed.start).max(), Some(80_000));
+    let fits = piano(Line::from(note(C4, NoteLength(u16::MAX / 3))));
+    assert_eq!(fits.scale_time(3.0), piano(Line::from(note(C4, NoteLength(u16::MAX)))));
+
+    // Infinite factors are treated as zero rather than making endless notes
+    assert_eq!(line.scale_time(f32::INFINITY).length(), 0);
+}
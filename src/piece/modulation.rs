@@ -0,0 +1,42 @@
+use crate::{note::Lfo, Line, ModTarget, Note};
+
+/// Wobbles every note in a line with a low-frequency oscillator, changing its level, pitch, or filter cutoff.
+///
+/// The oscillator is a sine wave, and each cycle lasts `rate_beats` beats (quarter notes), so it stays in
+/// time with the piece whatever the tempo. Its phase is measured from the start of the piece, so the wave
+/// carries on smoothly from note to note, and a long note evolves over its whole length. This is useful
+/// for tremolo, vibrato, and slowly sweeping pads. What `depth` means depends on the `target`: see
+/// [`ModTarget`]. Applying `lfo` to a line that already has one replaces it.
+///
+/// The oscillator only affects file output. Live playback plays the notes without it.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // A drone whose filter opens and closes over every two bars
+/// let drone = lfo(
+///     electric_guitar(whole(C4.octave(-1)) * 4),
+///     ModTarget::FilterCutoff { hz: 800.0 },
+///     8.0,
+///     1.5,
+/// );
+///
+/// // A gentle vibrato, a quarter of a semitone either way, four times a beat
+/// let singing = lfo(sine(half(A4)), ModTarget::Pitch, 0.25, 0.25);
+/// assert!(matches!(singing.notes[0].1, NoteKind::Pitched { lfo: Some(Lfo { rate_beats: 0.25, .. }), .. }));
+/// ```
+pub fn lfo(line: impl Into<Line>, target: ModTarget, rate_beats: f32, depth: f32) -> Line {
+    let line: Line = line.into();
+    let lfo = Lfo {
+        target,
+        rate_beats,
+        depth,
+    };
+    let modulated = |note: &Note| note.lfo(Some(lfo));
+    Line {
+        notes: line.notes.iter().map(modulated).collect(),
+        pickup: line.pickup.iter().map(modulated).collect(),
+        ..line
+    }
+}
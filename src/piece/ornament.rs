@@ -0,0 +1,216 @@
+use std::time::Duration;
+
+use crate::{
+    note::{Glide, LengthFluid, NoteLength},
+    Line, Note, NoteKind, NotePitch,
+};
+
+/// Controls where a grace note's time is taken from.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let before = piano(quarter(C4));
+/// let ornament = piano(sixteenth(C4.semitone(2)));
+/// let principal = piano(quarter(C4.semitone(4)));
+///
+/// // The ornament cuts into the previous note instead of the principal
+/// let line = before + grace_with(ornament, principal, GraceTiming::StealFromPrevious);
+/// assert_eq!(line.notes[0].0, NoteLength(3));
+/// assert_eq!(line.notes[2].0, NoteLength(4));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum GraceTiming {
+    /// The ornament is played on the beat, shortening the principal note
+    #[default]
+    StealFromPrincipal,
+    /// The ornament is played before the beat, shortening whatever came before it.
+    ///
+    /// This works like a pickup, so it only takes effect when the result is added to another line.
+    StealFromPrevious,
+}
+
+/// Plays an ornament (grace notes) just before a principal note or line.
+///
+/// The ornament's notes keep their own lengths, so use short notes (like sixteenths)
+/// for a quick ornament. The time for the ornament is taken from the start of the principal,
+/// so the principal's pitch and the overall rhythm are unchanged. Use [`grace_with`]
+/// to take the time from the previous note instead.
+///
+/// If the ornament is longer than the principal, the principal is dropped entirely.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let [c4, d4, e4] = MajorScale(C4).get_degrees([1, 2, 3]);
+///
+/// let line = piano(quarter(c4)) + grace(piano(sixteenth(d4)), piano(quarter(e4)));
+///
+/// // The grace note starts right where the principal would have, and the principal follows it
+/// assert_eq!(line.get_notes_at_instant(4).next(), Some(piano(sixteenth(d4))));
+/// assert_eq!(line.get_notes_at_instant(5).next(), Some(piano(dotted(eighth)(e4))));
+/// assert_eq!(line.length(), 8);
+/// ```
+pub fn grace(ornament: impl Into<Line>, principal: impl Into<Line>) -> Line {
+    grace_with(ornament, principal, GraceTiming::StealFromPrincipal)
+}
+
+/// Plays an ornament (grace notes) before a principal, taking its time from the specified place.
+///
+/// See [`grace`] and [`GraceTiming`] for details.
+pub fn grace_with(ornament: impl Into<Line>, principal: impl Into<Line>, timing: GraceTiming) -> Line {
+    let ornament: Line = ornament.into();
+    let principal: Line = principal.into();
+
+    match timing {
+        GraceTiming::StealFromPrincipal => Line {
+            notes: [ornament.notes.clone(), trim_start(principal.notes, ornament.length())].concat(),
+            pickup: principal.pickup,
+            hold_pickup: principal.hold_pickup,
+            labels: [ornament.labels, principal.labels].concat(),
+        },
+        GraceTiming::StealFromPrevious => Line {
+            notes: principal.notes,
+            pickup: [principal.pickup, ornament.notes].concat(),
+            hold_pickup: false,
+            labels: principal.labels,
+        },
+    }
+}
+
+/// Removes `amount` time units from the start of a sequence of notes,
+/// shortening the first remaining note if needed.
+#[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+#[expect(clippy::cast_possible_truncation, reason = "Remaining time is less than the note's u16 length")]
+fn trim_start(notes: Vec<Note>, amount: usize) -> Vec<Note> {
+    let mut remaining = amount;
+    let mut trimmed = Vec::with_capacity(notes.len());
+
+    for note in notes {
+        let length = note.0 .0 as usize;
+        if remaining >= length {
+            remaining -= length;
+        } else {
+            trimmed.push(Note(NoteLength(note.0 .0 - remaining as u16), note.1));
+            remaining = 0;
+        }
+    }
+
+    trimmed
+}
+
+/// Creates a trill, rapidly alternating between a main pitch and an upper auxiliary pitch.
+///
+/// The trill lasts for `duration`, with one note for every `rate` that fits. It always starts and
+/// ends on the main pitch, so when an even number of notes fit, the last upper note is replaced by
+/// the main pitch, and the trill ends with two main notes in a row. Any time left over when `rate`
+/// doesn't evenly divide `duration` is added to the final note. A `rate` of zero produces a single
+/// held main note.
+///
+/// Like other pitches passed to length functions, the notes use a sine timbre until one is applied.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let [c4, d4] = MajorScale(C4).get_degrees([1, 2]);
+///
+/// // A half note trill made of sixteenth notes
+/// let line = piano(trill(c4, d4, NoteLength(8), NoteLength(1)));
+///
+/// assert_eq!(line.notes.len(), 8);
+/// assert_eq!(line.length(), 8);
+/// assert_eq!(line.notes[0], piano(sixteenth(c4)));
+/// assert_eq!(line.notes[1], piano(sixteenth(d4)));
+/// // Eight sixteenths would end on the upper note, so the last one is the main note instead
+/// assert_eq!(line.notes[6], piano(sixteenth(c4)));
+/// assert_eq!(line.notes[7], piano(sixteenth(c4)));
+/// ```
+#[expect(clippy::arithmetic_side_effects, reason = "Manual bounds checking")]
+pub fn trill(pitch: NotePitch, upper: NotePitch, duration: NoteLength, rate: NoteLength) -> Line {
+    if rate.0 == 0 || duration.0 <= rate.0 {
+        return Line::from(pitch.with_length(duration));
+    }
+
+    let count = duration.0 / rate.0;
+    let remainder = duration.0 % rate.0;
+
+    let mut notes: Vec<Note> = (0..count)
+        .map(|i| if i % 2 == 0 { pitch } else { upper })
+        .map(|p| p.with_length(rate))
+        .collect();
+
+    // An even count would end on the upper pitch, so the last note is always the main one
+    if let Some(last) = notes.last_mut() {
+        *last = pitch.with_length(NoteLength(rate.0 + remainder));
+    }
+
+    Line::from(notes)
+}
+
+/// Slides into every note of a line from the note before it, over the first `glide` of the note.
+///
+/// Each note starts at the previous note's pitch and moves smoothly to its own, like a singer or
+/// a violinist sliding between notes. A rest breaks the slide, so the note after it starts on its
+/// own pitch, as does the first note of the line. A `glide` of zero removes any slides, for normal
+/// articulation. The slide is in milliseconds rather than time units, so it's the same at any tempo,
+/// and it's never longer than the note.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use symphoxy::prelude::*;
+///
+/// let [c4, e4] = MajorScale(C4).get_degrees([1, 3]);
+/// let line = portamento(sine(quarter(c4) + quarter(e4)), Duration::from_millis(80));
+///
+/// assert!(matches!(line.notes[0].1, NoteKind::Pitched { glide: None, .. }));
+/// assert!(matches!(line.notes[1].1, NoteKind::Pitched { glide: Some(Glide { from, .. }), .. } if from == c4));
+/// ```
+pub fn portamento(line: impl Into<Line>, glide: Duration) -> Line {
+    let line: Line = line.into();
+    let mut previous = None;
+    let mut slide = |note: &Note| match note.1 {
+        NoteKind::Pitched { pitch, .. } => {
+            let from = previous.replace(pitch).filter(|_| !glide.is_zero());
+            note.glide(from.map(|from| Glide { from, duration: glide }))
+        }
+        NoteKind::Rest => {
+            previous = None;
+            *note
+        }
+    };
+    Line {
+        pickup: line.pickup.iter().map(&mut slide).collect(),
+        notes: line.notes.iter().map(&mut slide).collect(),
+        ..line
+    }
+}
+
+#[test]
+fn test_trill_has_a_note_for_every_rate() {
+    use crate::prelude::*;
+
+    let [c4, d4] = MajorScale(C4).get_degrees([1, 2]);
+    let pitches = |line: &Line| -> Vec<NotePitch> { line.notes.iter().filter_map(|note| note.pitch()).collect() };
+
+    // An even count ends with a pair of main notes
+    let even = trill(c4, d4, NoteLength(6), NoteLength(1));
+    assert_eq!(even.notes.len(), 6);
+    assert_eq!(pitches(&even), [c4, d4, c4, d4, c4, c4]);
+    assert_eq!(even.length(), 6);
+
+    // An odd count alternates all the way through
+    let odd = trill(c4, d4, NoteLength(10), NoteLength(2));
+    assert_eq!(pitches(&odd), [c4, d4, c4, d4, c4]);
+
+    // Leftover time goes to the final main note, whatever the count
+    for (duration, count) in [(13, 6), (11, 5)] {
+        let line = trill(c4, d4, NoteLength(duration), NoteLength(2));
+        assert_eq!(line.notes.len(), count);
+        assert_eq!(line.notes[count - 1], c4.with_length(NoteLength(3)));
+        assert_eq!(line.length(), usize::from(duration));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    note::{LengthFluid, NoteLength},
+    Line, Note,
+};
+
+/// Builds a line from bare pitches, which all take a default length.
+///
+/// Writing `quarter(...)` around every note of a scale-like passage gets repetitive. A phrase
+/// builder holds the length instead, so only the pitches need to be written. Changing the length
+/// with [`PhraseBuilder::length`] applies to every note added after it.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let [c4, d4, e4, f4, g4] = MajorScale(C4).get_degrees([1, 2, 3, 4, 5]);
+///
+/// let phrase = PhraseBuilder::new(NoteLength::EIGHTH)
+///     .notes([c4, d4, e4, f4])
+///     .length(NoteLength::HALF)
+///     .note(g4)
+///     .note(REST)
+///     .build();
+///
+/// let explicit = eighth(c4) + eighth(d4) + eighth(e4) + eighth(f4) + half(g4) + half(REST);
+/// assert_eq!(phrase, explicit);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhraseBuilder {
+    length: NoteLength,
+    notes: Vec<Note>,
+}
+
+impl PhraseBuilder {
+    /// Creates an empty phrase, whose notes are `length` long until it's changed.
+    pub fn new(length: NoteLength) -> Self {
+        PhraseBuilder {
+            length,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Changes the length of every note added from now on. Notes already in the phrase keep their length.
+    pub fn length(mut self, length: NoteLength) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Adds a note of the current length, from a pitch, [`REST`](crate::REST), or a note whose timbre should be kept.
+    pub fn note<N: LengthFluid<Output = Note>>(mut self, kind: N) -> Self {
+        self.notes.push(kind.with_length(self.length));
+        self
+    }
+
+    /// Adds a note of the current length for each pitch, in order.
+    pub fn notes<N: LengthFluid<Output = Note>>(self, kinds: impl IntoIterator<Item = N>) -> Self {
+        kinds.into_iter().fold(self, PhraseBuilder::note)
+    }
+
+    /// Finishes the phrase, giving a line of its notes in the order they were added.
+    pub fn build(self) -> Line {
+        Line::from(self.notes)
+    }
+}
+
+#[test]
+fn test_builder_matches_explicit_lengths() {
+    use crate::prelude::*;
+
+    let phrase = PhraseBuilder::new(NoteLength::QUARTER)
+        .note(C4)
+        .note(C4.semitone(4))
+        .length(NoteLength::SIXTEENTH)
+        .notes([NoteKind::from(C4), REST])
+        .build();
+    let explicit = quarter(C4) + quarter(C4.semitone(4)) + sixteenth(C4) + sixteenth(REST);
+    assert_eq!(phrase, explicit);
+    assert_eq!(PhraseBuilder::new(NoteLength::HALF).build(), Line::default());
+}
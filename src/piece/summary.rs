@@ -0,0 +1,164 @@
+use crate::{note::NoteKind, Piece, Timbre};
+#[cfg(feature = "wav-output")]
+use crate::{
+    play::{BitDepth, FileOutputConfig},
+    MusicPlayer,
+};
+
+/// The size of a WAV file's header with a plain format chunk, in bytes
+#[cfg(feature = "wav-output")]
+const WAV_HEADER_BYTES: u64 = 44;
+
+/// The size of a WAV file's header with an extensible format chunk, used for more than two channels or 16 bits
+#[cfg(feature = "wav-output")]
+const EXTENSIBLE_WAV_HEADER_BYTES: u64 = 68;
+
+/// The size of the `smpl` chunk that records a loop region, in bytes
+#[cfg(feature = "wav-output")]
+const LOOP_CHUNK_BYTES: u64 = 68;
+
+/// An overview of a piece, from [`Piece::summary`], for checking what a render will produce without rendering it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PieceSummary {
+    /// The length of the piece, in time units
+    pub length: usize,
+    /// The number of pitched notes in the piece, including any that only play some of the time
+    pub note_count: usize,
+    /// Every timbre used in the piece, in the order they first appear
+    pub timbres: Vec<Timbre>,
+}
+
+impl Piece {
+    /// Summarises the piece: how long it is, how many notes it has, and which timbres play them.
+    ///
+    /// With [`PieceSummary::duration`] and [`PieceSummary::estimated_file_size`], this tells you what
+    /// rendering the piece will produce without doing any of the work, so huge renders can be caught early.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(REST) + half(A4)) * bass(whole(C4.octave(-1)));
+    /// let summary = piece.summary();
+    ///
+    /// assert_eq!(summary.length, 16);
+    /// assert_eq!(summary.note_count, 3);
+    /// assert_eq!(summary.timbres, [Timbre::Piano, Timbre::Bass]);
+    /// ```
+    pub fn summary(&self) -> PieceSummary {
+        let mut timbres = Vec::new();
+        let mut note_count = 0usize;
+        for timed in self.notes() {
+            if let NoteKind::Pitched { timbre, .. } = timed.note.1 {
+                note_count = note_count.saturating_add(1);
+                if !timbres.contains(&timbre) {
+                    timbres.push(timbre);
+                }
+            }
+        }
+
+        PieceSummary {
+            length: self.length(),
+            note_count,
+            timbres,
+        }
+    }
+}
+
+#[cfg(feature = "wav-output")]
+impl PieceSummary {
+    /// Gets how long the piece lasts when rendered by `player`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let summary = Piece::from(piano(whole(C4))).summary();
+    ///
+    /// assert_eq!(summary.duration(&player), Duration::from_millis(3200));
+    /// ```
+    pub fn duration(&self, player: &MusicPlayer<FileOutputConfig>) -> std::time::Duration {
+        let frames = u128::try_from(player.sample_at_time(self.length)).unwrap_or(u128::MAX);
+        let nanos = frames
+            .saturating_mul(1_000_000_000)
+            .checked_div(u128::from(player.output_config.sample_rate))
+            .unwrap_or_default();
+        std::time::Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Estimates the size, in bytes, of the WAV file that `player` would write for the piece.
+    ///
+    /// The estimate includes the header and any loop region. It assumes one channel unless the player's channel
+    /// count was set with [`MusicPlayer::with_channels`], since drum and custom samples with more channels widen
+    /// the output. Trimming silence with [`MusicPlayer::with_trim_silence`] can make the file smaller than this.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_channels(2);
+    /// let summary = Piece::from(piano(whole(C4) * 60)).summary();
+    ///
+    /// // About 3 minutes of CD quality audio
+    /// let megabytes = summary.estimated_file_size(&player) as f64 / 1_000_000.0;
+    /// assert!((33.0..35.0).contains(&megabytes));
+    /// ```
+    pub fn estimated_file_size(&self, player: &MusicPlayer<FileOutputConfig>) -> u64 {
+        let config = &player.output_config;
+        let channels = u64::from(config.channels.unwrap_or(1));
+        let bytes_per_sample = match config.bit_depth {
+            BitDepth::Int16 => 2,
+            BitDepth::Float32 => 4,
+        };
+        let header = if channels > 2 || config.bit_depth != BitDepth::Int16 {
+            EXTENSIBLE_WAV_HEADER_BYTES
+        } else {
+            WAV_HEADER_BYTES
+        };
+        let loop_chunk = if config.loop_region.is_some() {
+            LOOP_CHUNK_BYTES
+        } else {
+            0
+        };
+
+        let frames = u64::try_from(player.sample_at_time(self.length)).unwrap_or(u64::MAX);
+        frames
+            .saturating_mul(channels)
+            .saturating_mul(bytes_per_sample)
+            .saturating_add(header)
+            .saturating_add(loop_chunk)
+    }
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_estimated_size_matches_render() {
+    use crate::prelude::*;
+    use crate::LoopRegion;
+
+    let piece = piano(quarter(C4) + eighth(REST) + dotted(quarter)(A4)) * bass(whole(C4.octave(-1)));
+    let summary = piece.summary();
+
+    for player in [
+        MusicPlayer::new_file(300, 1.0, 8000),
+        MusicPlayer::new_file(250, 1.0, 22050).with_channels(2),
+        MusicPlayer::new_file(300, 1.0, 8000)
+            .with_channels(6)
+            .with_bit_depth(BitDepth::Float32)
+            .with_loop(LoopRegion::WholePiece),
+    ] {
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        player.write_wav_to(piece.clone(), &mut buffer).unwrap();
+        let actual = u64::try_from(buffer.into_inner().len()).unwrap();
+        let estimated = summary.estimated_file_size(&player);
+        // Within 1% of the file that was written
+        assert!(
+            estimated.abs_diff(actual).saturating_mul(100) < actual,
+            "estimated {estimated} bytes, wrote {actual}"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{piece::line::warp_notes, Label, Line};
+
+/// Gradually slows a line down, from `from_bpm` at its start to `to_bpm` at its end.
+///
+/// The tempo changes smoothly across the whole line, and every note is lengthened by how much the
+/// tempo has slowed while it plays. The tempos are relative to the player's: a ritardando from 300 to
+/// 200 slows to 200 BPM when played at 300 BPM, or to 400 BPM when played at 600 BPM.
+///
+/// Since note lengths are whole time units, the start and end of each note are rounded to the
+/// nearest unit. Only the ends are rounded, never the lengths, so the rounding doesn't build up over
+/// the line. For smoother curves over short notes, scale the line up with [`Line::scale_time`]
+/// and play it at a faster tempo. The pickup isn't affected, and labels move along with the notes.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let line = ritardando(piano(quarter(C4) * 8), 300, 150);
+///
+/// // Later notes take longer, until the end is played at nearly half speed.
+/// // Rounding to whole units makes the change a little uneven.
+/// let lengths: Vec<u16> = line.notes.iter().map(|note| note.0 .0).collect();
+/// assert_eq!(lengths, [4, 5, 4, 5, 6, 6, 7, 7]);
+/// assert_eq!(line.length(), 44); // 32 * 2 * ln(2)
+/// ```
+pub fn ritardando(line: impl Into<Line>, from_bpm: u32, to_bpm: u32) -> Line {
+    tempo_ramp(line.into(), from_bpm, to_bpm)
+}
+
+/// Gradually speeds a line up, from `from_bpm` at its start to `to_bpm` at its end.
+///
+/// This works the same way as [`ritardando`], shortening notes instead of lengthening them. The start
+/// and end of each note are rounded to the nearest time unit in the same way, so notes can become as
+/// short as zero units near the end of a steep accelerando.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let line = accelerando(piano(quarter(C4) * 8), 150, 300);
+///
+/// let lengths: Vec<u16> = line.notes.iter().map(|note| note.0 .0).collect();
+/// assert_eq!(lengths, [4, 3, 3, 3, 3, 2, 2, 2]);
+/// assert_eq!(line.length(), 22); // 32 * ln(2)
+/// ```
+pub fn accelerando(line: impl Into<Line>, from_bpm: u32, to_bpm: u32) -> Line {
+    tempo_ramp(line.into(), from_bpm, to_bpm)
+}
+
+/// Changes the tempo of a line linearly from `from_bpm` to `to_bpm`, for both [`ritardando`] and [`accelerando`].
+///
+/// Each time in the line is warped exactly, then rounded to the nearest whole unit.
+fn tempo_ramp(line: Line, from_bpm: u32, to_bpm: u32) -> Line {
+    let from = f64::from(from_bpm.max(1));
+    let to = f64::from(to_bpm.max(1));
+    #[expect(clippy::cast_precision_loss, reason = "Line lengths are far below 2^52")]
+    let length = line.length() as f64;
+
+    // The tempo changes linearly over the line, so a note at `time` is reached after the integral of
+    // 1 / tempo up to it. Measuring that in time units at the starting tempo gives its new position.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss,
+        reason = "Warped times are non-negative, and no longer than the line slowed to 1 BPM"
+    )]
+    let warp = |time: usize| -> usize {
+        let time = time as f64;
+        let warped = if from == to || length == 0.0 {
+            time
+        } else {
+            let tempo = from + (to - from) * time / length;
+            from * length / (to - from) * (tempo / from).ln()
+        };
+        warped.round() as usize
+    };
+
+    warp_line(line, warp)
+}
+
+/// Holds the last note of a line for `factor` times its written length, like a fermata.
+///
+/// A factor of 2.0 holds the note twice as long. Anything after the line, such as notes added with `+`,
+/// starts later by however much longer the note became. The new length is rounded to the nearest time
+/// unit, and negative factors are treated as zero. Labels that end with the line are stretched along with it.
+///
+/// To hold a note in the middle of a line, apply the fermata to the part of the line that ends with it.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let line = piano(quarter(C4) + fermata(quarter(A4), 2.0) + quarter(C4));
+///
+/// let lengths: Vec<u16> = line.notes.iter().map(|note| note.0 .0).collect();
+/// assert_eq!(lengths, [4, 8, 4]);
+/// assert_eq!(line.length(), 16);
+/// ```
+pub fn fermata(line: impl Into<Line>, factor: f32) -> Line {
+    let line = line.into();
+    let Some(last) = line.notes.last() else {
+        return line;
+    };
+    let held_from = line.length().saturating_sub(usize::from(last.0 .0));
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        reason = "The held length is clamped to the range of a note length"
+    )]
+    let held = (f32::from(last.0 .0) * factor.max(0.0))
+        .round()
+        .min(f32::from(u16::MAX)) as u16;
+
+    // Everything up to the held note stays put, and the note's end moves to its new length
+    let warp = |time: usize| {
+        if time <= held_from {
+            time
+        } else {
+            held_from.saturating_add(usize::from(held))
+        }
+    };
+
+    warp_line(line, warp)
+}
+
+/// Moves every note and label of a line to the time `warp` gives for it
+fn warp_line(line: Line, warp: impl Fn(usize) -> usize) -> Line {
+    Line {
+        notes: warp_notes(&line.notes, &warp),
+        labels: line
+            .labels
+            .iter()
+            .map(|label| Label {
+                name: label.name.clone(),
+                start: warp(label.start),
+                end: warp(label.end),
+            })
+            .collect(),
+        ..line
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{Piece, TimedNote};
+
+/// Every pitched note in a piece, sorted by when it starts, for quickly finding the notes in a window of time.
+///
+/// Building the timeline with [`Piece::timeline`] sorts the notes once. After that, each
+/// [`NoteTimeline::notes_in_range`] only looks at the notes near the window, so a scrolling display such as a
+/// piano roll can query it every frame, even for a long piece.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let piece = piano(quarter(C4) * 16) * bass(whole(C4.octave(-1)) * 4);
+/// let timeline = piece.timeline();
+///
+/// // Scrolling through the piece a bar at a time, there are 4 piano notes and a bass note in each bar
+/// for bar in 0..4 {
+///     assert_eq!(timeline.notes_in_range(bar * 16, (bar + 1) * 16).len(), 5);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NoteTimeline {
+    /// The notes, sorted by their start time
+    notes: Vec<TimedNote>,
+    /// The length of the longest note, which bounds how early a note in a window can start
+    longest: usize,
+}
+
+impl NoteTimeline {
+    /// Gets the notes that sound at any point from time unit `start` up to, but not including, time unit `end`.
+    ///
+    /// Notes that are already playing at `start`, or are still playing at `end`, are included, but a note that
+    /// ends exactly at `start` isn't, and nor is one that starts exactly at `end`. The notes are in order of
+    /// when they start. Times are in time units; [`MusicPlayer::time_unit_at`](crate::MusicPlayer::time_unit_at)
+    /// converts a position in the audio, such as the edge of a display, to one.
+    pub fn notes_in_range(&self, start: usize, end: usize) -> Vec<TimedNote> {
+        // Nothing that starts this early can still be playing at the start of the window
+        let earliest = start.saturating_sub(self.longest);
+        let first = self.notes.partition_point(|timed| timed.start < earliest);
+        let last = self.notes.partition_point(|timed| timed.start < end);
+
+        self.notes
+            .get(first..last)
+            .unwrap_or_default()
+            .iter()
+            .filter(|timed| timed.start.saturating_add(usize::from(timed.note.0 .0)) > start)
+            .copied()
+            .collect()
+    }
+
+    /// Gets every note in the timeline, in order of when they start.
+    pub fn notes(&self) -> &[TimedNote] {
+        &self.notes
+    }
+}
+
+impl Piece {
+    /// Sorts the piece's pitched notes by when they start, into a [`NoteTimeline`] for finding the notes playing
+    /// at any time.
+    ///
+    /// The timeline is a snapshot: changing the piece afterwards doesn't change it.
+    pub fn timeline(&self) -> NoteTimeline {
+        let mut notes: Vec<TimedNote> = self.notes().collect();
+        notes.sort_by_key(|timed| timed.start);
+        let longest = notes
+            .iter()
+            .map(|timed| usize::from(timed.note.0 .0))
+            .max()
+            .unwrap_or_default();
+        NoteTimeline { notes, longest }
+    }
+
+    /// Gets the notes that sound at any point from time unit `start` up to, but not including, time unit `end`.
+    ///
+    /// See [`NoteTimeline::notes_in_range`] for which notes are included. This builds a new timeline each time
+    /// it's called, so to look at many windows of the same piece, make one with [`Piece::timeline`] and query that.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let piece = Piece::from(piano(quarter(C4) + half(A4) + quarter(C4)));
+    ///
+    /// // The half note is still playing at the start of the window, and the last note starts at its end
+    /// let notes = piece.notes_in_range(10, 12);
+    /// assert_eq!(notes.len(), 1);
+    /// assert_eq!(notes[0].start, 4);
+    /// ```
+    pub fn notes_in_range(&self, start: usize, end: usize) -> Vec<TimedNote> {
+        self.timeline().notes_in_range(start, end)
+    }
+}
+
+#[test]
+fn test_notes_in_range_include_overlaps_at_the_edges() {
+    use crate::prelude::*;
+
+    // Piano notes at 0..4, 4..12 and 12..16, under a bass note at 2..18 and a sine at 20..21
+    let piece = piano(quarter(C4) + half(A4) + quarter(C4))
+        * bass(eighth(REST) + whole(C4))
+        * sine(Line::from(whole(REST)) + quarter(REST) + sixteenth(A4));
+    let starts = |start, end| -> Vec<usize> {
+        piece
+            .notes_in_range(start, end)
+            .iter()
+            .map(|timed| timed.start)
+            .collect()
+    };
+
+    // Notes overlapping either edge are included, but notes that only touch them aren't
+    assert_eq!(starts(4, 12), [2, 4]);
+    assert_eq!(starts(3, 13), [0, 2, 4, 12]);
+    assert_eq!(starts(11, 12), [2, 4]);
+    assert_eq!(starts(12, 13), [2, 12]);
+
+    // Windows with nothing in them, or outside the piece
+    assert_eq!(starts(19, 20), Vec::<usize>::new());
+    assert_eq!(starts(20, 20), Vec::<usize>::new());
+    assert_eq!(starts(21, 100), Vec::<usize>::new());
+    assert_eq!(starts(0, 100).len(), piece.notes().count());
+
+    // The timeline gives the same notes as filtering all of them
+    let timeline = piece.timeline();
+    for start in 0..22 {
+        for end in start..22 {
+            let filtered: Vec<TimedNote> = timeline
+                .notes()
+                .iter()
+                .filter(|timed| timed.start < end && timed.start + usize::from(timed.note.0 .0) > start)
+                .copied()
+                .collect();
+            assert_eq!(timeline.notes_in_range(start, end), filtered);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Finding the beats of an existing click track, so renders can be lined up with it.
+//!
+//! The click track is mixed down to mono and split into 5 ms frames. A click is wherever the energy of
+//! a frame rises sharply from the frame before, and its exact start is the first sample in that area
+//! that reaches half of the click's peak. The tempo comes from the median time between clicks, so a
+//! single missed or doubled click doesn't throw it off.
+
+#![expect(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use std::{io, path::Path, time::Duration};
+
+use crate::play::{downmix, render_to_wav::hound_to_io_error};
+
+/// The length of each frame the click track's energy is measured over, in seconds
+const FRAME_SECONDS: f32 = 0.005;
+
+/// Clicks closer together than this, in seconds, are treated as one
+const MIN_CLICK_GAP_SECONDS: f32 = 0.05;
+
+/// A rise in energy is a click if it's at least this fraction of the largest rise in the track
+const ONSET_THRESHOLD: f32 = 0.3;
+
+/// The clicks found in a recorded click track, from [`ClickTrack::open`].
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use symphoxy::ClickTrack;
+///
+/// // One second of silence with a short click every half second, starting at 0.1 seconds
+/// let mut samples = vec![0.0; 8000];
+/// for start in [800, 4800] {
+///     samples[start..start + 40].fill(0.8);
+/// }
+///
+/// let clicks = ClickTrack::from_samples(&samples, 1, 8000);
+/// assert_eq!(clicks.onsets, [Duration::from_millis(100), Duration::from_millis(600)]);
+/// assert_eq!(clicks.bpm(), Some(120.0));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClickTrack {
+    /// When each click starts, from the start of the track
+    pub onsets: Vec<Duration>,
+}
+
+impl ClickTrack {
+    /// Finds the clicks in a WAV file.
+    ///
+    /// Any sample rate, bit depth, and number of channels can be read. The clicks should be short and
+    /// louder than anything between them, as they are in a typical metronome track.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or isn't a valid WAV file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<ClickTrack> {
+        ClickTrack::read(io::BufReader::new(std::fs::File::open(path)?))
+    }
+
+    /// Finds the clicks in WAV data read from `reader`, such as an in-memory file.
+    ///
+    /// See [`ClickTrack::open`] for details.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails or the data isn't a valid WAV file.
+    pub fn read<R: io::Read>(reader: R) -> io::Result<ClickTrack> {
+        let reader = hound::WavReader::new(reader).map_err(hound_to_io_error)?;
+        let spec = reader.spec();
+        let samples = match spec.sample_format {
+            hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<Vec<_>, _>>(),
+            hound::SampleFormat::Int => {
+                let full_scale = (1u64 << spec.bits_per_sample.saturating_sub(1)) as f32;
+                reader
+                    .into_samples::<i32>()
+                    .map(|sample| sample.map(|sample| sample as f32 / full_scale))
+                    .collect()
+            }
+        }
+        .map_err(hound_to_io_error)?;
+
+        Ok(ClickTrack::from_samples(&samples, spec.channels, spec.sample_rate))
+    }
+
+    /// Finds the clicks in interleaved samples with the given number of channels and sample rate.
+    pub fn from_samples(samples: &[f32], channels: u16, sample_rate: u32) -> ClickTrack {
+        let mono = downmix(samples, channels.max(1), 1);
+        let frame = ((sample_rate as f32 * FRAME_SECONDS).round() as usize).max(1);
+        let min_gap = (MIN_CLICK_GAP_SECONDS / FRAME_SECONDS).round() as usize;
+
+        let energies: Vec<f32> = mono
+            .chunks(frame)
+            .map(|frame| frame.iter().map(|sample| sample * sample).sum())
+            .collect();
+        // How much more energy each frame has than the one before
+        let rises: Vec<f32> = std::iter::once(energies.first().copied().unwrap_or_default())
+            .chain(energies.windows(2).map(|pair| (pair[1] - pair[0]).max(0.0)))
+            .collect();
+        let threshold = rises.iter().fold(0.0f32, |max, &rise| max.max(rise)) * ONSET_THRESHOLD;
+
+        let mut onsets = Vec::new();
+        let mut last_click: Option<usize> = None;
+        for (index, &rise) in rises.iter().enumerate() {
+            if rise <= threshold || last_click.is_some_and(|last| index - last < min_gap) {
+                continue;
+            }
+            last_click = Some(index);
+
+            // The click may have started near the end of the frame before
+            let start = index.saturating_sub(1) * frame;
+            let end = ((index + 2) * frame).min(mono.len());
+            let area = &mono[start..end];
+            let peak = area.iter().fold(0.0f32, |max, sample| max.max(sample.abs()));
+            let offset = area
+                .iter()
+                .position(|sample| sample.abs() >= peak / 2.0)
+                .unwrap_or_default();
+            onsets.push(Duration::from_secs_f64(
+                (start + offset) as f64 / f64::from(sample_rate.max(1)),
+            ));
+        }
+
+        ClickTrack { onsets }
+    }
+
+    /// Estimates the tempo of the clicks, in clicks per minute, from the median time between them.
+    ///
+    /// Returns `None` if there are fewer than two clicks.
+    pub fn bpm(&self) -> Option<f32> {
+        let mut intervals: Vec<f64> = self
+            .onsets
+            .windows(2)
+            .map(|pair| pair[1].saturating_sub(pair[0]).as_secs_f64())
+            .collect();
+        intervals.sort_by(f64::total_cmp);
+        let middle = intervals.len() / 2;
+        let median = match intervals.len() {
+            0 => return None,
+            len if len % 2 == 0 => (intervals[middle - 1] + intervals[middle]) / 2.0,
+            _ => intervals[middle],
+        };
+        (median > 0.0).then(|| (60.0 / median) as f32)
+    }
+
+    /// Estimates the tempo to render at to match the clicks, if each click is a quarter note.
+    ///
+    /// The tempo is in the same units as [`MusicPlayer::new_file`](crate::MusicPlayer::new_file), sixteenth
+    /// notes per minute, so it's four times [`ClickTrack::bpm`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::ClickTrack;
+    ///
+    /// let clicks = ClickTrack {
+    ///     onsets: vec![Duration::ZERO, Duration::from_millis(500), Duration::from_millis(1000)],
+    /// };
+    /// assert_eq!(clicks.tempo_bpm(), Some(480));
+    /// ```
+    pub fn tempo_bpm(&self) -> Option<u32> {
+        self.bpm().map(|bpm| (bpm * 4.0).round() as u32)
+    }
+}
+
+#[test]
+fn test_finds_clicks_at_a_known_tempo() {
+    use std::io::Cursor;
+
+    // A stereo click track at 100 BPM: a 10 ms, 1 kHz blip every 0.6 seconds, over a little hum
+    let sample_rate = 44100;
+    let clicks: usize = 12;
+    let click_samples: usize = 26460;
+    let first_click: usize = 5000;
+    let mut buffer = Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::new(&mut buffer, spec).unwrap();
+    for i in 0..first_click + clicks * click_samples {
+        let time = i as f32 / sample_rate as f32;
+        let hum = 0.02 * (std::f32::consts::TAU * 50.0 * time).sin();
+        let since_click = i.checked_sub(first_click).map(|since| since % click_samples);
+        let click = match since_click {
+            Some(since) if since < 441 => 0.7 * (std::f32::consts::TAU * 1000.0 * since as f32 / 44100.0).sin(),
+            _ => 0.0,
+        };
+        let sample = ((hum + click) * f32::from(i16::MAX)) as i16;
+        writer.write_sample(sample).unwrap();
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let track = ClickTrack::read(Cursor::new(buffer.into_inner())).unwrap();
+    assert_eq!(track.onsets.len(), clicks);
+    for (index, onset) in track.onsets.iter().enumerate() {
+        let expected = (first_click + index * click_samples) as f64 / f64::from(sample_rate);
+        assert!(
+            (onset.as_secs_f64() - expected).abs() < 0.001,
+            "click {index} at {onset:?}"
+        );
+    }
+    assert!((track.bpm().unwrap() - 100.0).abs() < 0.1);
+    assert_eq!(track.tempo_bpm(), Some(400));
+
+    // Silence has no clicks, and so no tempo
+    let silence = ClickTrack::from_samples(&[0.0; 1000], 1, 8000);
+    assert!(silence.onsets.is_empty());
+    assert_eq!(silence.bpm(), None);
+}
@@ -0,0 +1,274 @@
+//! Effects that process rendered samples, and chains of them that can be reused across renders.
+//!
+//! Every effect works on one channel at a time, so a chain can be applied to a single buffer, or to every
+//! channel of a render with [`MusicPlayer::with_effects`].
+
+#![expect(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::{play::FileOutputConfig, MusicPlayer};
+
+/// The delays of the reverb's parallel comb filters, in seconds, from Freeverb's tuning at 44.1 kHz
+const REVERB_COMB_DELAYS: [f32; 4] = [0.0253, 0.0269, 0.0290, 0.0307];
+
+/// The delays of the reverb's series all-pass filters, in seconds
+const REVERB_ALLPASS_DELAYS: [f32; 2] = [0.0126, 0.0100];
+
+/// A single effect in an [`EffectChain`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Effect {
+    /// Multiplies every sample by this factor. 2.0 doubles the level, and 0.5 halves it
+    Gain(f32),
+    /// Clamps every sample to between minus this level and this level, flattening any peaks above it
+    Clip(f32),
+    /// Cuts frequencies above the cutoff, in Hz, with a gentle (12 dB per octave) slope, for a darker sound
+    LowPass(f32),
+    /// Repeats the sound after a delay, like an echo
+    Delay {
+        /// How long after the sound each repeat is heard
+        time: Duration,
+        /// How much of each repeat is fed back into the next one, from 0.0 (a single repeat) to just below 1.0
+        feedback: f32,
+        /// How loud the repeats are compared to the original sound, from 0.0 (none) to 1.0 (only the repeats)
+        mix: f32,
+    },
+    /// Simulates the sound reflecting around a room
+    Reverb {
+        /// How large the room sounds, from 0.0 (a small room with a short tail) to 1.0 (a hall with a long one)
+        room_size: f32,
+        /// How loud the reflections are compared to the original sound, from 0.0 (none) to 1.0 (only the reflections)
+        mix: f32,
+    },
+}
+
+impl Effect {
+    /// Applies this effect to a single channel of samples, in place
+    fn process(&self, samples: &mut [f32], sample_rate: u32) {
+        match *self {
+            Effect::Gain(gain) => samples.iter_mut().for_each(|sample| *sample *= gain),
+            Effect::Clip(level) => {
+                let level = level.abs();
+                samples
+                    .iter_mut()
+                    .for_each(|sample| *sample = sample.clamp(-level, level));
+            }
+            Effect::LowPass(cutoff) => low_pass(samples, cutoff, sample_rate),
+            Effect::Delay { time, feedback, mix } => {
+                let delay = (time.as_secs_f32() * sample_rate as f32).round() as usize;
+                delay_line(samples, delay, feedback.clamp(0.0, 0.99), mix.clamp(0.0, 1.0));
+            }
+            Effect::Reverb { room_size, mix } => {
+                reverb(samples, room_size.clamp(0.0, 1.0), mix.clamp(0.0, 1.0), sample_rate)
+            }
+        }
+    }
+}
+
+/// An ordered list of effects, which are applied one after another.
+///
+/// A chain can be defined once, such as for a "lead sound", and applied to any number of buffers or renders.
+/// The order matters: clipping and then turning the gain up is louder than turning it up and then clipping.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use symphoxy::{Effect, EffectChain};
+///
+/// let lead = EffectChain::new()
+///     .with(Effect::LowPass(4000.0))
+///     .with(Effect::Delay { time: Duration::from_millis(250), feedback: 0.3, mix: 0.25 })
+///     .with(Effect::Reverb { room_size: 0.5, mix: 0.2 });
+///
+/// // An impulse, with a repeat 250 ms (2000 samples) later
+/// let mut samples = vec![0.0; 8000];
+/// samples[0] = 1.0;
+/// lead.process(&mut samples, 8000);
+/// assert!(samples[2000..2100].iter().any(|&s| s.abs() > 0.01));
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct EffectChain {
+    /// The effects in the chain, in the order they're applied
+    pub effects: Vec<Effect>,
+}
+
+impl EffectChain {
+    /// Creates a chain with no effects, which leaves samples unchanged.
+    pub fn new() -> Self {
+        EffectChain::default()
+    }
+
+    /// Adds an effect to the end of the chain, so it's applied after every effect already in it.
+    pub fn with(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Applies every effect in the chain to a single channel of samples at the given sample rate, in order.
+    ///
+    /// Effects with tails, such as delays and reverbs, are cut off at the end of the buffer, so leave some
+    /// silence at the end for them to ring out into.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::{Effect, EffectChain};
+    ///
+    /// let mut samples = vec![0.25, -0.5];
+    /// EffectChain::new().with(Effect::Gain(2.0)).process(&mut samples, 44100);
+    /// assert_eq!(samples, [0.5, -1.0]);
+    /// ```
+    pub fn process(&self, samples: &mut [f32], sample_rate: u32) {
+        // Nothing else can see this flag, so every effect is applied
+        self.process_until(samples, sample_rate, &AtomicBool::new(false));
+    }
+
+    /// Applies the chain like [`EffectChain::process`], but stops before the next effect once `cancel` is set.
+    ///
+    /// Returns whether every effect was applied.
+    pub(super) fn process_until(&self, samples: &mut [f32], sample_rate: u32, cancel: &AtomicBool) -> bool {
+        for effect in &self.effects {
+            if cancel.load(Ordering::Relaxed) {
+                return false;
+            }
+            effect.process(samples, sample_rate);
+        }
+        true
+    }
+}
+
+impl From<Vec<Effect>> for EffectChain {
+    fn from(effects: Vec<Effect>) -> Self {
+        EffectChain { effects }
+    }
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Applies a chain of effects to every channel of the rendered audio.
+    ///
+    /// The chain runs after the notes are mixed and pitch shifted, and before the output is normalized,
+    /// so effects that only change the overall level, like a single [`Effect::Gain`], are undone by the
+    /// normalization. Delay and reverb tails are cut off at the end of the piece, so end it with a rest
+    /// to let them ring out.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Effect, EffectChain, MusicPlayer};
+    ///
+    /// let chain = EffectChain::new().with(Effect::Reverb { room_size: 0.8, mix: 0.5 });
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_effects(chain);
+    ///
+    /// // The reverb rings on into the rest after the note
+    /// let samples = &player.render_channels(piano(quarter(C4) + quarter(REST)))[0];
+    /// assert!(samples[6400..].iter().any(|&s| s.abs() > 0.01));
+    /// ```
+    pub fn with_effects(mut self, chain: EffectChain) -> Self {
+        self.output_config.effects = chain;
+        self
+    }
+}
+
+/// A biquad low-pass filter with a Butterworth (Q of 1/√2) response, from the Audio EQ Cookbook
+fn low_pass(samples: &mut [f32], cutoff: f32, sample_rate: u32) {
+    let nyquist = sample_rate as f32 / 2.0;
+    let omega = std::f32::consts::PI * (cutoff / nyquist).clamp(0.0001, 0.9999);
+    let alpha = omega.sin() * std::f32::consts::FRAC_1_SQRT_2;
+    let a0 = 1.0 + alpha;
+    let b1 = (1.0 - omega.cos()) / a0;
+    let (b0, a1, a2) = (b1 / 2.0, -2.0 * omega.cos() / a0, (1.0 - alpha) / a0);
+
+    let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+    for sample in samples {
+        let input = *sample;
+        let output = b0 * input + b1 * x1 + b0 * x2 - a1 * y1 - a2 * y2;
+        (x2, x1, y2, y1) = (x1, input, y1, output);
+        *sample = output;
+    }
+}
+
+/// Mixes in repeats of the samples `delay` samples apart, each `feedback` times as loud as the last
+fn delay_line(samples: &mut [f32], delay: usize, feedback: f32, mix: f32) {
+    if delay == 0 {
+        return;
+    }
+    // The repeats on their own, built up from the dry signal and the repeats before them
+    let mut wet = vec![0.0; samples.len()];
+    for i in delay..samples.len() {
+        wet[i] = samples[i - delay] + wet[i - delay] * feedback;
+    }
+    for (sample, wet) in samples.iter_mut().zip(wet) {
+        *sample = *sample * (1.0 - mix) + wet * mix;
+    }
+}
+
+/// A Schroeder reverb: parallel feedback comb filters for the reflections, then all-pass filters to diffuse them
+fn reverb(samples: &mut [f32], room_size: f32, mix: f32, sample_rate: u32) {
+    let to_samples = |seconds: f32| ((seconds * sample_rate as f32).round() as usize).max(1);
+    // Larger rooms take longer for their reflections to die away
+    let feedback = 0.7 + 0.28 * room_size;
+
+    let mut wet = vec![0.0; samples.len()];
+    for delay in REVERB_COMB_DELAYS.map(to_samples) {
+        let mut comb = vec![0.0; samples.len()];
+        for i in 0..samples.len() {
+            let echo = i.checked_sub(delay).map_or(0.0, |j| comb[j]);
+            comb[i] = samples[i] + echo * feedback;
+            wet[i] += comb[i] / REVERB_COMB_DELAYS.len() as f32;
+        }
+    }
+    for delay in REVERB_ALLPASS_DELAYS.map(to_samples) {
+        let input = wet.clone();
+        for i in 0..wet.len() {
+            let (delayed_in, delayed_out) = i.checked_sub(delay).map_or((0.0, 0.0), |j| (input[j], wet[j]));
+            wet[i] = -0.5 * input[i] + delayed_in + 0.5 * delayed_out;
+        }
+    }
+
+    for (sample, wet) in samples.iter_mut().zip(wet) {
+        *sample = *sample * (1.0 - mix) + wet * mix;
+    }
+}
+
+#[test]
+fn test_effect_order_is_preserved() {
+    let process = |chain: EffectChain| {
+        let mut samples = vec![0.25, -0.5, 0.75];
+        chain.process(&mut samples, 44100);
+        samples
+    };
+
+    // Two gains multiply together
+    let doubled_twice = process(EffectChain::new().with(Effect::Gain(2.0)).with(Effect::Gain(2.0)));
+    assert_eq!(doubled_twice, [1.0, -2.0, 3.0]);
+
+    // Clipping after the gain flattens the peaks, while clipping first lets the gain push them past it
+    let gain_then_clip = process(EffectChain::new().with(Effect::Gain(2.0)).with(Effect::Clip(1.0)));
+    let clip_then_gain = process(EffectChain::new().with(Effect::Clip(1.0)).with(Effect::Gain(2.0)));
+    assert_eq!(gain_then_clip, [0.5, -1.0, 1.0]);
+    assert_eq!(clip_then_gain, [0.5, -1.0, 1.5]);
+}
+
+#[test]
+fn test_low_pass_cuts_high_frequencies() {
+    let level = |frequency: f32| {
+        let mut samples: Vec<f32> = (0..8000)
+            .map(|i| (std::f32::consts::TAU * frequency * i as f32 / 8000.0).sin())
+            .collect();
+        EffectChain::new()
+            .with(Effect::LowPass(500.0))
+            .process(&mut samples, 8000);
+        samples[4000..].iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+    };
+
+    assert!(level(100.0) > 0.9);
+    assert!(level(2000.0) < 0.1);
+}
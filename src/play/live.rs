@@ -0,0 +1,269 @@
+//! Scheduling live playback by sample, so every note starts on exactly the sample it's due on.
+//!
+//! Every note is mixed into a single stream a block at a time. The frame each note starts on is worked out
+//! from the tempo before playback begins, and the note is mixed in at that offset within whichever block it
+//! falls in. How promptly the playback thread wakes up only changes how far ahead the stream is filled, never
+//! when a note is heard, so timing can't drift or jitter over a long piece.
+
+#![expect(clippy::arithmetic_side_effects, reason = "Frames are bounded by the voices being mixed")]
+
+use std::{cmp::Reverse, time::Duration};
+
+use rodio::Source;
+
+use crate::{
+    piece::TimedNote,
+    play::{
+        mixing,
+        sources::{get_source, mix_seed, seed_to_unit, with_glide},
+        EnvelopeCurves, LiveOutputConfig, OscillatorPhase,
+    },
+    Note, NoteKind,
+};
+
+/// The sample rate of live playback. The built-in timbres are generated at this rate, and samples loaded
+/// from files are resampled to it.
+pub(super) const LIVE_SAMPLE_RATE: u32 = 48000;
+
+/// Mixes the notes of a live performance into blocks of interleaved samples, in order.
+pub(super) struct Scheduler {
+    /// Notes that haven't started yet, with the latest first so the next one can be taken off the end
+    pending: Vec<PendingNote>,
+    /// Notes that have started and are still sounding
+    voices: Vec<Voice>,
+    /// The frame the next block starts on
+    frame: u64,
+    /// The frame the piece ends on, if no note rings on past it
+    end: u64,
+    channels: u16,
+    output: LiveOutputConfig,
+    tuning_ratio: f32,
+    envelope_curves: EnvelopeCurves,
+    oscillator_phase: OscillatorPhase,
+}
+
+/// A note that will be rendered once the block it starts in is reached
+struct PendingNote {
+    start: u64,
+    frames: u64,
+    note: Note,
+    seed: u64,
+}
+
+/// A rendered note, mixed into every block it overlaps
+struct Voice {
+    start: u64,
+    /// Interleaved samples, already mixed into the output's channels
+    samples: Vec<f32>,
+}
+
+impl Scheduler {
+    /// Schedules `notes`, which are timed in units at `tempo_bpm`, in a piece `length` units long.
+    pub(super) fn new(
+        notes: impl IntoIterator<Item = TimedNote>,
+        length: usize,
+        tempo_bpm: u32,
+        output: LiveOutputConfig,
+        tuning_ratio: f32,
+        envelope_curves: EnvelopeCurves,
+        oscillator_phase: OscillatorPhase,
+    ) -> Self {
+        let frame_at = |time: usize| frame_at(time, tempo_bpm);
+        // Live playback doesn't need to be reproducible, so seed from the clock
+        #[expect(clippy::cast_possible_truncation, reason = "Only the low bits are needed")]
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64);
+
+        let mut pending = Vec::new();
+        for (index, TimedNote { start, note }) in (0u64..).zip(notes) {
+            let NoteKind::Pitched {
+                probability, nudge_ms, ..
+            } = note.1
+            else {
+                continue;
+            };
+            let seed = mix_seed(seed, index);
+            if probability < 1.0 && seed_to_unit(mix_seed(seed, 1)) >= probability {
+                continue;
+            }
+            // Nudging moves the note without changing its length, but it can't start before the piece
+            #[expect(clippy::cast_possible_truncation, reason = "Nudges are far shorter than i64::MAX frames")]
+            let nudge = (f64::from(nudge_ms) * f64::from(LIVE_SAMPLE_RATE) / 1000.0).round() as i64;
+            let end = frame_at(start.saturating_add(note.0 .0 as usize));
+            pending.push(PendingNote {
+                start: frame_at(start).saturating_add_signed(nudge),
+                frames: end.saturating_sub(frame_at(start)),
+                note,
+                seed,
+            });
+        }
+        pending.sort_by_key(|note| Reverse(note.start));
+
+        Scheduler {
+            pending,
+            voices: Vec::new(),
+            frame: 0,
+            end: frame_at(length),
+            channels: output.output_handle.channels().max(1),
+            output,
+            tuning_ratio,
+            envelope_curves,
+            oscillator_phase,
+        }
+    }
+
+    /// Gets how many channels each frame has.
+    pub(super) fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Gets how far into the piece the blocks mixed so far reach.
+    pub(super) fn position(&self) -> Duration {
+        Duration::from_micros(self.frame.saturating_mul(1_000_000) / u64::from(LIVE_SAMPLE_RATE))
+    }
+
+    /// Mixes the next block of up to `frames` frames, or returns `None` once the piece and every note in it has ended.
+    ///
+    /// Every block is `frames` long except the last, which stops as soon as the piece does.
+    #[expect(clippy::cast_possible_truncation, reason = "Offsets within a block or a voice, which are in memory")]
+    pub(super) fn next_block(&mut self, frames: usize) -> Option<Vec<f32>> {
+        let block_end = self.frame.saturating_add(frames as u64);
+        while self.pending.last().is_some_and(|note| note.start < block_end) {
+            if let Some(note) = self.pending.pop() {
+                let samples = self.render(&note);
+                self.voices.push(Voice {
+                    start: note.start,
+                    samples,
+                });
+            }
+        }
+
+        let channels = usize::from(self.channels);
+        let voice_end = |voice: &Voice| voice.start.saturating_add((voice.samples.len() / channels) as u64);
+        let end = self.voices.iter().map(voice_end).fold(self.end, u64::max);
+        let block_end = if self.pending.is_empty() {
+            block_end.min(end)
+        } else {
+            block_end
+        };
+        if block_end <= self.frame {
+            return None;
+        }
+
+        let mut block = vec![0.0; (block_end - self.frame) as usize * channels];
+        for voice in &self.voices {
+            let from = voice.start.max(self.frame);
+            let to = voice_end(voice).min(block_end);
+            for frame in from..to {
+                let into_block = (frame - self.frame) as usize * channels;
+                let into_voice = (frame - voice.start) as usize * channels;
+                for channel in 0..channels {
+                    block[into_block + channel] += voice.samples[into_voice + channel];
+                }
+            }
+        }
+
+        self.frame = block_end;
+        let frame = self.frame;
+        self.voices.retain(|voice| voice_end(voice) > frame);
+        Some(block)
+    }
+
+    /// Renders a note's samples, mixed into the output's channels at the live sample rate
+    fn render(&self, note: &PendingNote) -> Vec<f32> {
+        let Note(
+            _,
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                glide,
+                ..
+            },
+        ) = note.note
+        else {
+            return Vec::new();
+        };
+        // Long enough to cover every frame of the note
+        let duration_ms = note.frames.saturating_mul(1000).div_ceil(u64::from(LIVE_SAMPLE_RATE));
+        // For some reason, playing live is way louder than file output. 64 is arbitrary, but seems about right.
+        let source = with_glide(duration_ms, pitch, glide, |duration_ms| {
+            get_source(
+                duration_ms,
+                pitch.0 * self.tuning_ratio,
+                timbre,
+                volume / 64.0,
+                note.seed,
+                self.envelope_curves,
+                self.oscillator_phase,
+            )
+        });
+        let (source_channels, sample_rate) = (source.channels(), source.sample_rate());
+        let mixed = mixing::mix_live_note(
+            &source.collect::<Vec<_>>(),
+            source_channels,
+            sample_rate,
+            pan,
+            self.channels,
+            self.output.stereo_width,
+            self.output.pan_law,
+        );
+
+        let channels = usize::from(self.channels);
+        let mut samples = resample(mixed, channels, sample_rate);
+        samples.truncate(
+            usize::try_from(note.frames)
+                .unwrap_or(usize::MAX)
+                .saturating_mul(channels),
+        );
+        samples
+    }
+}
+
+/// Gets the frame a time in units starts on at `tempo_bpm`, without rounding each unit on the way
+fn frame_at(time: usize, tempo_bpm: u32) -> u64 {
+    (time as u128)
+        .saturating_mul(u128::from(LIVE_SAMPLE_RATE).saturating_mul(60))
+        .checked_div(u128::from(tempo_bpm))
+        .map_or(u64::MAX, |frame| u64::try_from(frame).unwrap_or(u64::MAX))
+}
+
+/// Resamples interleaved samples from `sample_rate` to the live sample rate, by linear interpolation
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Positions within a single note"
+)]
+fn resample(samples: Vec<f32>, channels: usize, sample_rate: u32) -> Vec<f32> {
+    if sample_rate == LIVE_SAMPLE_RATE || sample_rate == 0 {
+        return samples;
+    }
+    let frames = samples.len() / channels;
+    let resampled_frames = (frames as u64).saturating_mul(u64::from(LIVE_SAMPLE_RATE)) / u64::from(sample_rate);
+    let step = f64::from(sample_rate) / f64::from(LIVE_SAMPLE_RATE);
+    let samples = &samples;
+    (0..resampled_frames as usize)
+        .flat_map(|frame| {
+            let position = frame as f64 * step;
+            let before = position as usize;
+            let fraction = (position - before as f64) as f32;
+            (0..channels).map(move |channel| {
+                let from = samples[before * channels + channel];
+                let to = samples.get((before + 1) * channels + channel).copied().unwrap_or(from);
+                from + (to - from) * fraction
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_resampling_keeps_the_length_in_seconds() {
+    let stereo: Vec<f32> = (0..400u16).map(|i| f32::from(i / 2)).collect();
+    let resampled = resample(stereo, 2, 24000);
+    assert_eq!(resampled.len(), 800);
+    assert!(resampled.chunks(2).all(|frame| frame[0] == frame[1]));
+    assert_eq!(&resampled[..8], [0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 1.5, 1.5]);
+}
@@ -0,0 +1,318 @@
+//! Loudness measurement, following a simplified version of ITU-R BS.1770.
+//!
+//! The measurement works in three steps:
+//! 1. Every channel is "K-weighted" with two filters. The first boosts high frequencies, like the
+//!    human head does, and the second cuts out the low frequencies that we barely hear.
+//! 2. The weighted audio is split into 400 ms blocks, overlapping by 75%, and the mean square of each
+//!    block is summed across channels.
+//! 3. Blocks quieter than -70 LUFS (silence) are ignored, as are blocks more than 10 LU quieter than the
+//!    average of the rest. The remaining blocks are averaged into the integrated loudness.
+//!
+//! The simplification is that every channel is weighted equally. The standard gives surround
+//! channels slightly more weight, which doesn't apply to the mono and stereo output of most pieces.
+
+#![expect(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use crate::{
+    play::{FileOutputConfig, Playable},
+    MusicPlayer, Piece,
+};
+
+/// Blocks quieter than this are considered silence, and don't count towards the loudness
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Blocks this much quieter than the average loudness don't count towards the loudness
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+/// The limiter keeps samples under this level when normalizing loudness, about 1 dB below full scale
+const LIMITER_CEILING: f32 = 0.891;
+
+impl Piece {
+    /// Measures the integrated loudness of this piece when rendered by `player`, in LUFS.
+    ///
+    /// This is the loudness the rendered WAV file would have, including the player's output gain
+    /// and clipping. LUFS (Loudness Units relative to Full Scale) measure how loud audio sounds
+    /// rather than how high its peaks are, so it's what streaming services use to match the volume
+    /// of different songs. Most of them aim for around -14 LUFS.
+    ///
+    /// This follows a simplified version of ITU-R BS.1770, which weights every channel equally.
+    /// Silent pieces have a loudness of negative infinity.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 48000).without_limiter();
+    ///
+    /// // A full scale sine wave at about 1 kHz is calibrated to be -3.01 LUFS
+    /// let tone = Piece::from(sine(whole(NotePitch::new(997.0))));
+    /// assert!((tone.loudness_lufs(&player) - -3.01).abs() < 0.2);
+    ///
+    /// // Halving the output gain makes it about 6 dB quieter
+    /// let quieter = MusicPlayer::new_file(300, 0.5, 48000).without_limiter();
+    /// assert!((tone.loudness_lufs(&quieter) - -9.03).abs() < 0.2);
+    ///
+    /// assert_eq!(Piece::from(whole(REST)).loudness_lufs(&player), f32::NEG_INFINITY);
+    /// ```
+    pub fn loudness_lufs(&self, player: &MusicPlayer<FileOutputConfig>) -> f32 {
+        player.measure_loudness(self.clone())
+    }
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders the piece exactly as it would be written, and measures its integrated loudness.
+    fn measure_loudness<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T) -> f32 {
+        let clip_mode = self.output_config.clip_mode;
+        let channels: Vec<Vec<f32>> = self
+            .render_channels(piece)
+            .into_iter()
+            .map(|channel| {
+                channel
+                    .into_iter()
+                    .map(|s| f32::from(super::render_to_wav::quantize_sample(s, clip_mode)) / f32::from(i16::MAX))
+                    .collect()
+            })
+            .collect();
+
+        integrated_loudness(&channels, self.output_config.sample_rate)
+    }
+}
+
+/// Measures the integrated loudness of the given channels, in LUFS.
+pub(super) fn integrated_loudness(channels: &[Vec<f32>], sample_rate: u32) -> f32 {
+    let sample_rate = f64::from(sample_rate.max(1));
+    let weighted: Vec<Vec<f64>> = channels.iter().map(|channel| k_weight(channel, sample_rate)).collect();
+    let total_samples = weighted.first().map(Vec::len).unwrap_or_default();
+
+    // 400 ms blocks, starting every 100 ms. Anything too short for a block is measured as a single block.
+    let block_len = ((sample_rate * 0.4).round() as usize).clamp(1, total_samples.max(1));
+    let step = (block_len / 4).max(1);
+    let block_powers: Vec<f64> = (0..=total_samples.saturating_sub(block_len))
+        .step_by(step)
+        .map(|start| {
+            weighted
+                .iter()
+                .map(|channel| {
+                    let block = channel.get(start..start + block_len).unwrap_or_default();
+                    block.iter().map(|s| s * s).sum::<f64>() / block_len as f64
+                })
+                .sum()
+        })
+        .collect();
+
+    let gated_loudness = |threshold: f64| {
+        let gated: Vec<f64> = block_powers
+            .iter()
+            .copied()
+            .filter(|&power| power_to_lufs(power) > threshold)
+            .collect();
+        if gated.is_empty() {
+            f64::NEG_INFINITY
+        } else {
+            power_to_lufs(gated.iter().sum::<f64>() / gated.len() as f64)
+        }
+    };
+
+    let relative_gate = gated_loudness(ABSOLUTE_GATE_LUFS) - RELATIVE_GATE_LU;
+    gated_loudness(relative_gate.max(ABSOLUTE_GATE_LUFS)) as f32
+}
+
+/// Scales the channels so that their integrated loudness is `target`, in LUFS.
+///
+/// Peaks that the gain would push over [`LIMITER_CEILING`] are limited, which lowers the loudness a
+/// little, so the gain is corrected a few times to make up for it. Silent channels are left alone.
+pub(super) fn normalize_loudness(channels: &mut [Vec<f32>], sample_rate: u32, target: f32) {
+    let original = channels.to_vec();
+    let mut gain_db = 0.0;
+
+    for _ in 0..4 {
+        let error = target - integrated_loudness(channels, sample_rate);
+        if !error.is_finite() || error.abs() < 0.01 {
+            return;
+        }
+
+        gain_db += error;
+        let gain = 10.0f32.powf(gain_db / 20.0);
+        for (channel, original) in channels.iter_mut().zip(&original) {
+            for (s, &o) in channel.iter_mut().zip(original) {
+                *s = o * gain;
+            }
+        }
+        limit(channels, sample_rate, LIMITER_CEILING);
+    }
+}
+
+/// Keeps every sample under `ceiling`, by turning the gain down smoothly around peaks.
+///
+/// The gain is shared by all channels, so that the balance between them doesn't shift. It starts
+/// turning down about 5 ms before a peak, and recovers over about 50 ms after it.
+pub(super) fn limit(channels: &mut [Vec<f32>], sample_rate: u32, ceiling: f32) {
+    let len = channels.first().map(Vec::len).unwrap_or_default();
+
+    // The gain each sample needs to fit under the ceiling on its own
+    let mut gains: Vec<f32> = (0..len)
+        .map(|i| {
+            let peak = channels.iter().map(|channel| channel[i].abs()).fold(0.0, f32::max);
+            if peak > ceiling {
+                ceiling / peak
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    // Only ever lowering the gains means no sample can end up over the ceiling
+    let release = 1.0 - (-1.0 / (0.05 * sample_rate as f32)).exp();
+    let attack = 1.0 - (-1.0 / (0.005 * sample_rate as f32)).exp();
+    // Once the gain has recovered to within a hundredth of a decibel, it snaps back to 1, so the audio
+    // away from peaks passes through exactly as it was
+    let recover = |gain: f32, rate: f32| {
+        let gain = gain + (1.0 - gain) * rate;
+        if gain > 0.9988 {
+            1.0
+        } else {
+            gain
+        }
+    };
+    for i in 1..len {
+        gains[i] = gains[i].min(recover(gains[i - 1], release));
+    }
+    for i in (1..len).rev() {
+        gains[i - 1] = gains[i - 1].min(recover(gains[i], attack));
+    }
+
+    for channel in channels {
+        for (s, gain) in channel.iter_mut().zip(&gains) {
+            // Clamping only catches rounding errors
+            *s = (*s * gain).clamp(-ceiling, ceiling);
+        }
+    }
+}
+
+/// Converts the mean square of a K-weighted block into its loudness
+fn power_to_lufs(power: f64) -> f64 {
+    -0.691 + 10.0 * power.log10()
+}
+
+/// Applies the K-weighting filters to a channel.
+///
+/// The standard only gives coefficients at 48 kHz, so they're derived from the filters'
+/// analog parameters here, to work at any sample rate.
+fn k_weight(channel: &[f32], sample_rate: f64) -> Vec<f64> {
+    // Stage 1: a high shelf of about +4 dB above 1.5 kHz, modelling the acoustic effect of the head
+    let k = (std::f64::consts::PI * 1_681.974_450_955_533 / sample_rate).tan();
+    let q = 0.707_175_236_955_419_6;
+    let vh = 10.0f64.powf(3.999_843_853_973_347 / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    // Stage 2: a high pass at about 38 Hz (the "revised low-frequency B-curve")
+    let k = (std::f64::consts::PI * 38.135_470_876_024_44 / sample_rate).tan();
+    let q = 0.500_327_037_323_877_3;
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    high_pass.apply(shelf.apply(channel.iter().map(|&s| f64::from(s))))
+}
+
+/// A second order IIR filter, with its coefficients normalized so that `a0` is 1
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn apply(&self, input: impl IntoIterator<Item = f64>) -> Vec<f64> {
+        let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+        input
+            .into_iter()
+            .map(|x| {
+                let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2 - self.a[0] * y1 - self.a[1] * y2;
+                (x2, x1, y2, y1) = (x1, x, y1, y);
+                y
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_loudness_of_calibrated_sine() {
+    // BS.1770 defines a full scale 997 Hz sine in one channel to measure -3.01 LUFS, at any sample rate
+    for sample_rate in [44100, 48000, 96000] {
+        let sine: Vec<f32> = (0..sample_rate * 3)
+            .map(|i| (std::f32::consts::TAU * 997.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+        let loudness = integrated_loudness(std::slice::from_ref(&sine), sample_rate);
+        assert!((loudness - -3.01).abs() < 0.05, "{loudness} LUFS at {sample_rate} Hz");
+
+        // Two channels of the same sine are twice the power, which is 3 dB louder
+        let loudness = integrated_loudness(&[sine.clone(), sine], sample_rate);
+        assert!((loudness - 0.0).abs() < 0.05, "{loudness} LUFS at {sample_rate} Hz");
+    }
+}
+
+#[test]
+fn test_loudness_normalization_limits_peaks() {
+    // A quiet sine with occasional loud clicks, which would clip if simply turned up
+    let signal: Vec<f32> = (0..48000 * 3)
+        .map(|i| {
+            let click = if i % 12000 < 48 { 0.8 } else { 0.0 };
+            0.1 * (std::f32::consts::TAU * 440.0 * i as f32 / 48000.0).sin() + click
+        })
+        .collect();
+
+    for target in [-20.0, -14.0, -9.0] {
+        let mut channels = vec![signal.clone(), signal.clone()];
+        normalize_loudness(&mut channels, 48000, target);
+
+        let loudness = integrated_loudness(&channels, 48000);
+        assert!((loudness - target).abs() < 0.1, "{loudness} LUFS instead of {target}");
+        assert!(channels.iter().flatten().all(|s| s.abs() <= LIMITER_CEILING));
+    }
+}
+
+#[test]
+fn test_limiter_only_turns_down_peaks() {
+    let ceiling = 0.5;
+    let sine = |amplitude: f32| -> Vec<f32> {
+        (0..48000 * 2)
+            .map(|i| amplitude * (std::f32::consts::TAU * 440.0 * i as f32 / 48000.0).sin())
+            .collect()
+    };
+
+    // Audio that's already under the ceiling passes through untouched
+    let quiet = sine(0.4);
+    let mut channels = vec![quiet.clone()];
+    limit(&mut channels, 48000, ceiling);
+    assert_eq!(channels[0], quiet);
+
+    // A loud burst in the middle is turned down, without affecting the audio well before or after it
+    let mut signal = quiet.clone();
+    for s in &mut signal[24000..24480] {
+        *s *= 2.0;
+    }
+    let mut channels = vec![signal.clone()];
+    limit(&mut channels, 48000, ceiling);
+    assert!(channels[0].iter().all(|s| s.abs() <= ceiling));
+    assert!(channels[0][24000..24480].iter().any(|s| s.abs() > 0.9 * ceiling));
+    assert_eq!(channels[0][..22000], quiet[..22000]);
+    assert_eq!(channels[0][40000..], quiet[40000..]);
+}
@@ -0,0 +1,288 @@
+//! Rendering a metronome click alongside a piece, as a separate stem for mixing.
+
+#![expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use crate::{
+    play::{render_to_wav::audible_range, FileOutputConfig, Playable},
+    MeterError, MusicPlayer, Piece, TimeSignature,
+};
+
+/// The length of each click, in seconds
+const CLICK_SECONDS: f32 = 0.01;
+
+/// The pitch of a click on a beat, in Hz
+const BEAT_HZ: f32 = 1000.0;
+
+/// The pitch of the click on the first beat of each bar, in Hz
+const DOWNBEAT_HZ: f32 = 2000.0;
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders a musical piece to a WAV file, and a metronome click for it to a second WAV file.
+    ///
+    /// The click is its own stem, for handing to whoever mixes the piece: it has a short blip on every quarter
+    /// note, with a higher one on the first beat of every bar of 4/4. Bars are counted from the first downbeat
+    /// after the piece's [pickup](Piece::with_pickup), as in [`Piece::bars`]. Both files come from the same render,
+    /// so they have the same tempo, sample rate, number of channels, and length, and line up exactly when played
+    /// together. If the player trims silence, both files are trimmed by the same amount.
+    ///
+    /// For other time signatures, use [`MusicPlayer::render_to_wav_with_click_in_meter`].
+    ///
+    /// Tempo changes such as [`ritardando`](crate::ritardando) move the piece's notes rather than the player's
+    /// tempo, so the click stays at the player's tempo throughout.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the piece should be written
+    /// * `click_path` - The file path where the click should be written
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{ClickTrack, MusicPlayer};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let dir = std::env::temp_dir();
+    /// let (path, click_path) = (dir.join("symphoxy_mix.wav"), dir.join("symphoxy_click.wav"));
+    /// player
+    ///     .render_to_wav_with_click(piano(whole(C4)), path.to_str().unwrap(), click_path.to_str().unwrap())
+    ///     .unwrap();
+    ///
+    /// // A whole note is four quarter note clicks, at 75 quarter notes per minute
+    /// let clicks = ClickTrack::open(&click_path).unwrap();
+    /// assert_eq!(clicks.onsets.len(), 4);
+    /// assert_eq!(clicks.tempo_bpm(), Some(300));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # std::fs::remove_file(&click_path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if either file can't be created or written to.
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn render_to_wav_with_click<T: Playable + Clone + Send + Sync + Into<Piece> + 'static>(
+        &self,
+        piece: T,
+        path: &str,
+        click_path: &str,
+    ) -> io::Result<()> {
+        self.render_to_wav_with_click_in_meter(piece, path, click_path, TimeSignature::COMMON, &[])
+    }
+
+    /// Renders a musical piece and its metronome click like [`MusicPlayer::render_to_wav_with_click`], with
+    /// bars and beats in the given time signatures.
+    ///
+    /// The bars are the ones [`Piece::bars_with_meter`] splits the piece into. There's a click on every beat,
+    /// which is the bottom note value of the time signature, so 6/8 clicks on every eighth note. The first
+    /// beat of each bar is higher, except in the pickup, whose beats lead up to the first downbeat.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{ClickTrack, MusicPlayer, TimeSignature};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let dir = std::env::temp_dir();
+    /// let (path, click_path) = (dir.join("symphoxy_waltz.wav"), dir.join("symphoxy_waltz_click.wav"));
+    ///
+    /// // A quarter note pickup, then two bars of 3/4
+    /// let waltz = Piece::from(piano(quarter(C4) + dotted(half)(A4) * 2)).with_pickup(NoteLength::QUARTER);
+    /// player
+    ///     .render_to_wav_with_click_in_meter(
+    ///         waltz,
+    ///         path.to_str().unwrap(),
+    ///         click_path.to_str().unwrap(),
+    ///         TimeSignature::new(3, 4),
+    ///         &[],
+    ///     )
+    ///     .unwrap();
+    ///
+    /// // One click for the pickup, then three in each bar
+    /// assert_eq!(ClickTrack::open(&click_path).unwrap().onsets.len(), 7);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # std::fs::remove_file(&click_path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error of kind [`io::ErrorKind::InvalidInput`], holding a [`MeterError`], if the piece can't
+    /// be split into bars of the time signatures, or any error from creating or writing to either file.
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn render_to_wav_with_click_in_meter<T: Playable + Clone + Send + Sync + Into<Piece> + 'static>(
+        &self,
+        piece: T,
+        path: &str,
+        click_path: &str,
+        initial: TimeSignature,
+        changes: &[(usize, TimeSignature)],
+    ) -> io::Result<()> {
+        let piece: Piece = piece.into();
+        let beats = click_beats(&piece, initial, changes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        // Silence is trimmed from both files together below, so they stay lined up
+        let mut samples = self.clone().with_trim_silence(false).render_channels(piece);
+        let total_samples = samples.first().map(Vec::len).unwrap_or_default();
+        let mut click = vec![self.click_samples(&beats, total_samples); samples.len().max(1)];
+
+        if self.output_config.trim_silence {
+            let audible = audible_range(&samples);
+            for channel in samples.iter_mut().chain(&mut click) {
+                channel.truncate(audible.end);
+                channel.drain(..audible.start);
+            }
+        }
+
+        for (samples, path) in [(samples, path), (click, click_path)] {
+            let mut writer = BufWriter::new(File::create(path)?);
+            self.write_samples_to(&samples, &mut writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Renders one channel of clicks on every beat, given as its time and whether it's a downbeat
+    fn click_samples(&self, beats: &[(usize, bool)], total_samples: usize) -> Vec<f32> {
+        let sample_rate = self.output_config.sample_rate as f32;
+        let click_samples = (sample_rate * CLICK_SECONDS) as usize;
+        let mut samples = vec![0.0; total_samples];
+
+        for &(beat, downbeat) in beats {
+            let frequency = if downbeat { DOWNBEAT_HZ } else { BEAT_HZ };
+            let start = self.sample_at_time(beat);
+            for (i, sample) in samples.iter_mut().skip(start).take(click_samples).enumerate() {
+                // A sine blip that fades out, so it doesn't click on the way out as well as in
+                let fade = 1.0 - i as f32 / click_samples as f32;
+                let phase = std::f32::consts::TAU * frequency * i as f32 / sample_rate;
+                *sample = self.output_config.output_gain * fade * phase.sin();
+            }
+        }
+        samples
+    }
+}
+
+/// Gets the time of every beat in the piece's bars, and whether it's the first beat of a bar after the pickup
+fn click_beats(
+    piece: &Piece,
+    initial: TimeSignature,
+    changes: &[(usize, TimeSignature)],
+) -> Result<Vec<(usize, bool)>, MeterError> {
+    let bars = piece.bars_with_meter(initial, changes)?;
+    let pickup = piece.pickup_length().min(piece.length());
+    // The time signature in force at a time, where changes at 0 apply from the first downbeat
+    let signature_at = |time: usize| {
+        let mut sorted = changes.to_vec();
+        sorted.sort_by_key(|&(time, _)| time);
+        sorted
+            .into_iter()
+            .take_while(|&(change, _)| change <= time.max(pickup))
+            .last()
+            .map_or(initial, |(_, signature)| signature)
+    };
+    // Every signature was checked by splitting the piece into bars, so the beats are at least a sixteenth
+    let beat_units =
+        |signature: TimeSignature| usize::from(16u16.checked_div(signature.beat_value).unwrap_or(1).max(1));
+
+    let mut beats = Vec::new();
+    for bar in bars {
+        let beat = beat_units(signature_at(bar.start));
+        if bar.start < pickup {
+            // The pickup's beats count back from the first downbeat
+            let mut pickup_beats: Vec<_> = (1..)
+                .map_while(|n| bar.end.checked_sub(beat.saturating_mul(n)))
+                .collect();
+            pickup_beats.reverse();
+            beats.extend(pickup_beats.into_iter().map(|time| (time, false)));
+        } else {
+            beats.extend(bar.clone().step_by(beat).map(|time| (time, time == bar.start)));
+        }
+    }
+    Ok(beats)
+}
+
+#[test]
+fn test_clicks_follow_the_bars_of_the_piece() {
+    use crate::prelude::*;
+
+    // A dotted quarter pickup, then a bar of 4/4 and a bar of 6/8
+    let piece = Piece::from(piano(dotted(quarter)(C4) + whole(A4) + dotted(half)(C4))).with_pickup(NoteLength(6));
+    let beats = click_beats(&piece, TimeSignature::COMMON, &[(22, TimeSignature::new(6, 8))]).unwrap();
+    assert_eq!(
+        beats,
+        [
+            (2, false),
+            (6, true),
+            (10, false),
+            (14, false),
+            (18, false),
+            (22, true),
+            (24, false),
+            (26, false),
+            (28, false),
+            (30, false),
+            (32, false),
+        ]
+    );
+
+    // Without a pickup, every quarter note clicks and every bar starts with a downbeat
+    let beats = click_beats(&Piece::from(piano(whole(C4) * 2)), TimeSignature::COMMON, &[]).unwrap();
+    let downbeats: Vec<_> = beats
+        .iter()
+        .filter(|&&(_, downbeat)| downbeat)
+        .map(|&(time, _)| time)
+        .collect();
+    assert_eq!(beats.len(), 8);
+    assert_eq!(downbeats, [0, 16]);
+
+    assert_eq!(
+        click_beats(&piece, TimeSignature::COMMON, &[(20, TimeSignature::new(3, 4))]),
+        Err(MeterError::Unaligned { time: 20 })
+    );
+}
+
+#[test]
+fn test_click_stem_matches_the_main_render() {
+    use crate::{prelude::*, ClickTrack};
+
+    let dir = std::env::temp_dir();
+    let path = dir.join("symphoxy_click_stem_test.wav");
+    let click_path = dir.join("symphoxy_click_stem_test_click.wav");
+    let piece = piano(whole(C4) + half(A4) + dotted(quarter)(C4)) * bass(whole(C4.octave(-1)) * 2);
+
+    for player in [
+        MusicPlayer::new_file(300, 1.0, 22050),
+        MusicPlayer::new_file(480, 0.8, 44100).with_channels(2),
+    ] {
+        player
+            .render_to_wav_with_click(piece.clone(), path.to_str().unwrap(), click_path.to_str().unwrap())
+            .unwrap();
+        let main = hound::WavReader::open(&path).unwrap();
+        let click = hound::WavReader::open(&click_path).unwrap();
+        assert_eq!(click.duration(), main.duration());
+        assert_eq!(click.spec(), main.spec());
+
+        // The piece is 32 units long, so there's a click on each of its 8 quarter notes
+        let clicks = ClickTrack::open(&click_path).unwrap();
+        assert_eq!(clicks.onsets.len(), 8);
+        assert_eq!(clicks.tempo_bpm(), Some(player.tempo_bpm));
+    }
+
+    // Trimming silence keeps the stems the same length
+    let rest_first = quarter(REST) + piano(whole(C4));
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_trim_silence(true);
+    player
+        .render_to_wav_with_click(rest_first, path.to_str().unwrap(), click_path.to_str().unwrap())
+        .unwrap();
+    let main = hound::WavReader::open(&path).unwrap();
+    assert_eq!(hound::WavReader::open(&click_path).unwrap().duration(), main.duration());
+    assert!(main.duration() < player.sample_at_time(20) as u32);
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&click_path).unwrap();
+}
@@ -0,0 +1,209 @@
+//! Placing notes within the output channels, shared by file and live output.
+
+#![allow(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Complex audio processing code"
+)]
+
+use crate::play::PanLaw;
+
+/// Splits a mono signal between the two output channels nearest to `pan`.
+///
+/// `pan` runs from -1.0 (first channel) to 1.0 (last channel), and the gains of neighbouring
+/// channels follow the pan law.
+pub(super) fn pan_to_channel_gains(pan: f32, channels: usize, pan_law: PanLaw) -> Vec<(usize, f32)> {
+    if channels <= 1 {
+        return vec![(0, 1.0)];
+    }
+
+    let position = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0 * (channels - 1) as f32;
+    let low = position.floor() as usize;
+    let high = (low + 1).min(channels - 1);
+    let frac = position - low as f32;
+
+    if low == high {
+        vec![(low, 1.0)]
+    } else {
+        let (low_gain, high_gain) = pan_law.gains(frac);
+        vec![(low, low_gain), (high, high_gain)]
+    }
+}
+
+/// How far the copy of the sound added by [`widen_stereo`] is delayed, in seconds.
+///
+/// This is within the range where the ear hears the copy as part of the same sound (the Haas effect),
+/// rather than as an echo.
+const STEREO_WIDTH_DELAY: f32 = 0.012;
+
+/// Spreads a pair of channels apart using mid/side processing.
+///
+/// The mid (sum) signal is kept as it is, and a delayed copy of it is mixed into the side (difference)
+/// signal. Since the copy is added to one channel and subtracted from the other, they still sum to the same mono signal.
+pub(super) fn widen_stereo(samples: &mut [Vec<f32>], width: f32, sample_rate: u32) {
+    let [left, right] = samples else {
+        return;
+    };
+    let delay = (STEREO_WIDTH_DELAY * sample_rate as f32).round() as usize;
+
+    let mid: Vec<f32> = left.iter().zip(right.iter()).map(|(l, r)| (l + r) / 2.0).collect();
+    for i in 0..mid.len() {
+        let delayed = i.checked_sub(delay).map_or(0.0, |j| mid[j]);
+        let side = (left[i] - right[i]) / 2.0 + delayed * width;
+        left[i] = mid[i] + side;
+        right[i] = mid[i] - side;
+    }
+}
+
+/// Mixes the interleaved samples of a live note into `channels` interleaved output channels.
+///
+/// Panned mono notes are placed with the pan law, like they are in file output. Unpanned mono notes play at
+/// full level in every channel, as loud as they were when they were played as mono streams. Sources with more
+/// channels than the output, such as stereo samples on mono hardware, are averaged down to fit.
+#[cfg(feature = "live-output")]
+pub(super) fn mix_live_note(
+    samples: &[f32],
+    source_channels: u16,
+    sample_rate: u32,
+    pan: Option<f32>,
+    channels: u16,
+    stereo_width: f32,
+    pan_law: PanLaw,
+) -> Vec<f32> {
+    let source_channels = usize::from(source_channels.max(1));
+    let channels = usize::from(channels.max(1));
+    let frames = samples.len() / source_channels;
+
+    let mut mixed = vec![vec![0.0; frames]; channels];
+    for in_ch in 0..source_channels {
+        let out_ch = if source_channels == 1 {
+            match pan {
+                Some(pan) => pan_to_channel_gains(pan, channels, pan_law),
+                None => (0..channels).map(|ch| (ch, 1.0)).collect(),
+            }
+        } else {
+            let idx = ((in_ch as f32) * (channels as f32 - 1.0) / (source_channels as f32 - 1.0)).round() as usize;
+            vec![(idx, (channels as f32 / source_channels as f32).min(1.0))]
+        };
+        for (frame, &s) in samples
+            .iter()
+            .skip(in_ch)
+            .step_by(source_channels)
+            .take(frames)
+            .enumerate()
+        {
+            for &(ch, gain) in &out_ch {
+                mixed[ch][frame] += s * gain;
+            }
+        }
+    }
+
+    if channels == 2 && stereo_width > 0.0 {
+        widen_stereo(&mut mixed, stereo_width, sample_rate);
+    }
+
+    (0..frames)
+        .flat_map(|frame| mixed.iter().map(move |channel| channel[frame]))
+        .collect()
+}
+
+/// Converts interleaved samples from one number of channels to another, such as stereo to mono.
+///
+/// When there are fewer output channels, each input channel is folded into the output channel at the
+/// same relative position, and the channels folded together are averaged. A sound that's the same in
+/// every channel, like a centered mono part, keeps its level. When there are more output channels,
+/// each one copies the nearest input channel. Any partial frame at the end is dropped.
+///
+/// Averaging can make sounds that differ between channels, such as wide stereo reverb, a little quieter.
+/// Use [`downmix_with`] to fold channels together with a different pan law.
+///
+/// # Examples
+/// ```
+/// use symphoxy::downmix;
+///
+/// // Two frames of stereo audio
+/// let stereo = [1.0, 0.0, 0.5, 0.5];
+/// assert_eq!(downmix(&stereo, 2, 1), [0.5, 0.5]);
+///
+/// // Mono is copied to both channels
+/// assert_eq!(downmix(&[0.25, -0.5], 1, 2), [0.25, 0.25, -0.5, -0.5]);
+/// ```
+pub fn downmix(samples: &[f32], from: u16, to: u16) -> Vec<f32> {
+    downmix_with(samples, from, to, PanLaw::Linear)
+}
+
+/// Converts interleaved samples from one number of channels to another, folding channels together with a pan law.
+///
+/// This works like [`downmix`], except for the level of the folded channels. [`PanLaw::Linear`] averages
+/// them, which keeps the level of sounds that are the same in every channel. The other pan laws turn each
+/// channel down by 3 dB per halving instead, which keeps the overall power of sounds that differ between
+/// channels, but makes sounds that are the same in every channel louder.
+///
+/// # Examples
+/// ```
+/// use symphoxy::{downmix_with, PanLaw};
+///
+/// let mono = downmix_with(&[1.0, 0.0], 2, 1, PanLaw::ConstantPower);
+/// assert!((mono[0] - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+/// ```
+pub fn downmix_with(samples: &[f32], from: u16, to: u16, pan_law: PanLaw) -> Vec<f32> {
+    let (from, to) = (usize::from(from.max(1)), usize::from(to.max(1)));
+    // The output channel at the same relative position as an input channel, or the other way around
+    let nearest = |channel: usize, channels: usize, other: usize| {
+        if channels <= 1 {
+            0
+        } else {
+            ((channel as f32) * (other as f32 - 1.0) / (channels as f32 - 1.0)).round() as usize
+        }
+    };
+
+    let gains: Vec<Vec<(usize, f32)>> = if to >= from {
+        (0..to).map(|out| vec![(nearest(out, to, from), 1.0)]).collect()
+    } else {
+        let mut inputs = vec![Vec::new(); to];
+        for input in 0..from {
+            inputs[nearest(input, from, to)].push(input);
+        }
+        inputs
+            .into_iter()
+            .map(|group| {
+                let count = group.len().max(1) as f32;
+                let gain = match pan_law {
+                    PanLaw::Linear => count.recip(),
+                    PanLaw::ConstantPower | PanLaw::NegThreeDb => count.sqrt().recip(),
+                };
+                group.into_iter().map(|input| (input, gain)).collect()
+            })
+            .collect()
+    };
+
+    samples
+        .chunks_exact(from)
+        .flat_map(|frame| {
+            gains
+                .iter()
+                .map(|inputs| inputs.iter().map(|&(input, gain)| frame[input] * gain).sum::<f32>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_stereo_to_mono_averages_channels() {
+    // A centered sound keeps its level, and a hard-panned one is halved
+    let stereo = [0.5, 0.5, 1.0, 0.0, 0.0, -1.0];
+    assert_eq!(downmix(&stereo, 2, 1), [0.5, 0.5, -0.5]);
+
+    // The constant power law keeps the mean power of sounds that are only in one channel instead
+    let hard_panned = &stereo[2..];
+    let mono = downmix_with(hard_panned, 2, 1, PanLaw::ConstantPower);
+    let power = |samples: &[f32]| samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+    assert!((power(&mono) - power(hard_panned)).abs() < 1e-5);
+
+    // Folding quad to stereo averages each side, and converting to the same count changes nothing
+    assert_eq!(downmix(&[1.0, 0.0, 0.5, -0.5], 4, 2), [0.5, 0.0]);
+    assert_eq!(downmix(&stereo, 2, 2), stereo);
+    assert_eq!(downmix(&[1.0, 2.0, 3.0], 2, 1), [1.5]);
+}
@@ -2,19 +2,55 @@
 
 #[cfg(feature = "live-output")]
 use std::{
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "wav-output")]
+mod click_track;
+#[cfg(feature = "wav-output")]
+mod effects;
+#[cfg(feature = "live-output")]
+mod live;
+#[cfg(feature = "wav-output")]
+mod loudness;
+#[cfg(feature = "wav-output")]
+mod metronome;
+mod mixing;
+#[cfg(feature = "wav-output")]
+mod modulation;
+#[cfg(feature = "live-output")]
+mod output;
+#[cfg(feature = "wav-output")]
+mod piece_player;
 #[cfg(feature = "wav-output")]
 mod render_to_wav;
 pub mod sources;
+#[cfg(feature = "wav-output")]
+mod spectrogram;
+#[cfg(feature = "wav-output")]
+mod stems;
+
+#[cfg(feature = "wav-output")]
+pub use click_track::ClickTrack;
+#[cfg(feature = "wav-output")]
+pub use effects::{Effect, EffectChain};
+pub use mixing::{downmix, downmix_with};
+#[cfg(feature = "live-output")]
+pub use output::{AudioOutput, AudioStream};
+#[cfg(feature = "wav-output")]
+pub use piece_player::PiecePlayer;
+#[cfg(feature = "wav-output")]
+pub use spectrogram::SpectrogramOptions;
 
 #[cfg(feature = "live-output")]
-use crate::{play::sources::get_source, NoteKind};
+use crate::play::live::{Scheduler, LIVE_SAMPLE_RATE};
 
-use crate::{Line, Note, Piece};
+use crate::{Line, Note, NotePitch, Piece, A4};
 
 /// Creates a configuration for this music library
 ///
@@ -29,9 +65,16 @@ use crate::{Line, Note, Piece};
 ///
 /// player.render_to_wav(piece, "path/to/output.wav");
 /// ```
+#[derive(Clone)]
 pub struct MusicPlayer<O: MusicOutput + Clone> {
     /// Tempo in beats per minute (default: 300 BPM which gives 200ms per beat)
     pub(crate) tempo_bpm: u32,
+    /// Tuning reference for A4 (default: 440 Hz)
+    pub(crate) reference_pitch: NotePitch,
+    /// Shapes of the built-in timbres' fades (default: linear)
+    pub(crate) envelope_curves: EnvelopeCurves,
+    /// Where the built-in timbres' oscillators start in their cycles (default: zero phase)
+    pub(crate) oscillator_phase: OscillatorPhase,
     pub(crate) output_config: O,
 }
 
@@ -40,6 +83,121 @@ impl<O: MusicOutput + Clone> MusicPlayer<O> {
     pub(crate) fn beat_duration_ms(&self) -> u64 {
         60_000u64.checked_div(self.tempo_bpm as u64).unwrap_or(u64::MAX)
     }
+
+    /// Gets the time unit (sixteenth note) being played at a position, measured from the start of playback.
+    ///
+    /// This is useful with [`MusicPlayer::play_with_position`], for finding the note to highlight in a score.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // At 300 BPM, each time unit lasts 200 ms
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// assert_eq!(player.time_unit_at(Duration::from_millis(199)), 0);
+    /// assert_eq!(player.time_unit_at(Duration::from_millis(1000)), 5);
+    /// ```
+    pub fn time_unit_at(&self, position: std::time::Duration) -> usize {
+        let time_unit = position
+            .as_millis()
+            .checked_div(u128::from(self.beat_duration_ms()))
+            .unwrap_or(0);
+        usize::try_from(time_unit).unwrap_or(usize::MAX)
+    }
+
+    /// Sets the pitch that A4 is tuned to during playback and rendering.
+    ///
+    /// Every frequency is scaled by the same ratio, so a piece written with the
+    /// standard [`A4`] at 440 Hz can be played back at, for example, 442 Hz.
+    /// References that aren't a finite number above zero, such as infinity or NaN, are ignored.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let concert = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let orchestra = MusicPlayer::new_file(300, 1.0, 44100).with_reference_pitch(NotePitch::new(442.0));
+    ///
+    /// // Estimate the frequency of each render by counting zero crossings
+    /// let frequencies = [concert, orchestra].map(|player| {
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(sine(whole(C4)), &mut buffer).unwrap();
+    ///     let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    ///     let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    ///     let crossings = samples.windows(2).filter(|w| (w[0] < 0) != (w[1] < 0)).count();
+    ///     crossings as f32 / 2.0 / (samples.len() as f32 / 44100.0)
+    /// });
+    ///
+    /// assert!((frequencies[1] / frequencies[0] - 442.0 / 440.0).abs() < 0.001);
+    /// ```
+    pub fn with_reference_pitch(mut self, a4: NotePitch) -> Self {
+        if a4.0.is_finite() && a4.0 > 0.0 {
+            self.reference_pitch = a4;
+        }
+        self
+    }
+
+    /// Sets the shapes of the fades at the start (attack) and end (release) of each note.
+    ///
+    /// The built-in timbres fade in and out linearly by default, which can sound mechanical.
+    /// A [`CurveShape::Exponential`] release decays quickly and then trails off, like a plucked string,
+    /// and an exponential attack makes notes speak sooner. Custom sources and drums aren't affected.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{CurveShape, MusicPlayer};
+    ///
+    /// let render = |release| {
+    ///     let player = MusicPlayer::new_file(300, 1.0, 8000).with_envelope_curves(CurveShape::Linear, release);
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(electric_guitar(whole(A4)), &mut buffer).unwrap();
+    ///     let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    ///     let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    ///     // How loud the middle of the note is, compared to its loudest point
+    ///     let peak = |samples: &[i16]| samples.iter().map(|s| s.unsigned_abs()).max().unwrap() as f32;
+    ///     peak(&samples[samples.len() / 2..]) / peak(&samples)
+    /// };
+    ///
+    /// // An exponential release has mostly faded away by the middle of the note
+    /// assert!(render(CurveShape::Exponential) < render(CurveShape::Linear));
+    /// ```
+    pub fn with_envelope_curves(mut self, attack: CurveShape, release: CurveShape) -> Self {
+        self.envelope_curves = EnvelopeCurves { attack, release };
+        self
+    }
+
+    /// Sets where in its cycle each note's oscillators start.
+    ///
+    /// Every note starts at zero phase by default, so the output is the same every time. Layering identical
+    /// oscillators at the same phase can cause comb filtering, or cancel out entirely when one is inverted.
+    /// [`OscillatorPhase::Random`] gives every note, and every layer of a [`Timbre::Layered`](crate::Timbre::Layered),
+    /// its own starting phase. For file output, this is chosen from the seed, so it's still reproducible.
+    ///
+    /// Only the built-in sine based timbres are affected. Noise, drums and custom sources aren't.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, OscillatorPhase};
+    ///
+    /// // A quarter of a cycle in, a sine wave starts at its peak rather than at zero
+    /// let player = MusicPlayer::new_file(300, 1.0, 48000).with_oscillator_phase(OscillatorPhase::Fixed(0.25));
+    /// let random = MusicPlayer::new_file(300, 1.0, 48000).with_oscillator_phase(OscillatorPhase::Random);
+    /// ```
+    pub fn with_oscillator_phase(mut self, phase: OscillatorPhase) -> Self {
+        self.oscillator_phase = phase;
+        self
+    }
+
+    /// The ratio every frequency is multiplied by to match the reference pitch
+    pub(crate) fn tuning_ratio(&self) -> f32 {
+        self.reference_pitch.0 / A4.0
+    }
 }
 
 #[cfg(feature = "live-output")]
@@ -60,9 +218,51 @@ impl MusicPlayer<LiveOutputConfig> {
     /// let player = MusicPlayer::new_live(300, handle);
     /// ```
     pub fn new_live(tempo_bpm: u32, output_handle: Arc<rodio::OutputStreamHandle>) -> Self {
+        Self::new_live_with_output(tempo_bpm, output_handle)
+    }
+
+    /// Creates a new music player for live audio output, which sends its audio to the given output.
+    ///
+    /// This is for playing through audio backends other than rodio, or for capturing what would
+    /// be played without an audio device. See [`AudioOutput`].
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{AudioOutput, AudioStream, MusicPlayer};
+    ///
+    /// /// Counts the samples it's given, instead of playing them
+    /// struct Counter(Arc<Mutex<usize>>);
+    ///
+    /// impl AudioOutput for Counter {
+    ///     fn open_stream(&self, _channels: u16, _sample_rate: u32) -> std::io::Result<Box<dyn AudioStream>> {
+    ///         Ok(Box::new(Counter(self.0.clone())))
+    ///     }
+    /// }
+    ///
+    /// impl AudioStream for Counter {
+    ///     fn push_samples(&mut self, samples: &[f32]) {
+    ///         *self.0.lock().unwrap() += samples.len();
+    ///     }
+    /// }
+    ///
+    /// let count = Arc::new(Mutex::new(0));
+    /// let player = MusicPlayer::new_live_with_output(6000, Arc::new(Counter(count.clone())));
+    /// player.play(piano(quarter(C4))).join().unwrap();
+    /// assert!(*count.lock().unwrap() > 0);
+    /// ```
+    pub fn new_live_with_output(tempo_bpm: u32, output: Arc<dyn AudioOutput>) -> Self {
         Self {
             tempo_bpm,
-            output_config: LiveOutputConfig { output_handle },
+            reference_pitch: A4,
+            envelope_curves: EnvelopeCurves::default(),
+            oscillator_phase: OscillatorPhase::default(),
+            output_config: LiveOutputConfig {
+                output_handle: output,
+                stereo_width: 0.0,
+                pan_law: PanLaw::default(),
+            },
         }
     }
 
@@ -91,10 +291,310 @@ impl MusicPlayer<LiveOutputConfig> {
     /// handle.join().unwrap(); // Wait for playback to finish
     /// ```
     pub fn play<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T) -> std::thread::JoinHandle<()> {
-        piece.play(self.output_config.output_handle.clone(), self.beat_duration_ms())
+        piece.play(
+            self.output_config.clone(),
+            self.tempo_bpm,
+            self.tuning_ratio(),
+            self.envelope_curves,
+            self.oscillator_phase,
+        )
+    }
+
+    /// Plays a musical piece through the live audio output, reporting how far playback has got as it goes.
+    ///
+    /// This works like [`MusicPlayer::play`], and also calls `on_position` with the time since playback started,
+    /// about every 10 ms, until playback finishes. It's meant for drawing a moving cursor over a score, and
+    /// [`MusicPlayer::time_unit_at`] turns each position into the time unit being played.
+    ///
+    /// The callback runs on its own timer thread, not on the audio threads, so a slow callback can't
+    /// interrupt the sound, though it makes later positions arrive late. It's never called from two threads
+    /// at once. Positions only go forwards, and the last one is reported after every note has finished,
+    /// which may be a little after the end of the piece.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle));
+    /// let piece = piano(quarter(C4) + quarter(A4));
+    ///
+    /// let cursor = player.clone();
+    /// let handle = player.play_with_position(piece, move |position| {
+    ///     println!("Playing time unit {}", cursor.time_unit_at(position));
+    /// });
+    /// handle.join().unwrap();
+    /// ```
+    pub fn play_with_position<T, F>(&self, piece: T, on_position: F) -> std::thread::JoinHandle<()>
+    where
+        T: Playable + Clone + Send + Sync + 'static,
+        F: FnMut(Duration) + Send + 'static,
+    {
+        let playback = self.play(piece);
+        thread::spawn(move || report_position(playback, on_position))
+    }
+
+    /// Sets how far the sound is spread between the left and right channels, from 0.0 (mono) to 1.0 (full).
+    ///
+    /// This works the same way as it does for file output (see the file player's `with_stereo_width`),
+    /// widening each note as it's played. Only outputs with two channels are widened.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::MusicPlayer;
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle)).with_stereo_width(0.5);
+    /// ```
+    pub fn with_stereo_width(mut self, stereo_width: f32) -> Self {
+        self.output_config.stereo_width = stereo_width.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how panned notes are split between the left and right channels as they're played.
+    ///
+    /// The default, [`PanLaw::ConstantPower`], keeps a note equally loud wherever it's panned.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::{MusicPlayer, PanLaw};
+    /// use std::sync::Arc;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_live(300, Arc::new(handle)).with_pan_law(PanLaw::Linear);
+    /// ```
+    pub fn with_pan_law(mut self, pan_law: PanLaw) -> Self {
+        self.output_config.pan_law = pan_law;
+        self
     }
 }
 
+/// How many frames of samples are sent to an [`AudioStream`] at a time during live playback
+#[cfg(feature = "live-output")]
+const LIVE_BLOCK_FRAMES: usize = 1024;
+
+/// How far ahead of what's been heard live playback fills its stream
+#[cfg(feature = "live-output")]
+const LIVE_LOOKAHEAD: Duration = Duration::from_millis(100);
+
+/// Plays every block the scheduler mixes through a single stream of `output`, until they've all been played or
+/// live playback is stopped.
+///
+/// The wall clock only keeps the stream from getting too far ahead of what's been heard, so that stopping is
+/// quick. When each note is heard comes from where the scheduler put it in the stream.
+#[cfg(feature = "live-output")]
+fn play_scheduled(mut scheduler: Scheduler, output: &dyn AudioOutput) {
+    let mut stream = match output.open_stream(scheduler.channels(), LIVE_SAMPLE_RATE) {
+        Ok(stream) => stream,
+        Err(error) => {
+            eprintln!("Warning: Could not open an audio stream ({error}). Nothing will be played.");
+            return;
+        }
+    };
+    let start = Instant::now();
+    while let Some(block) = scheduler.next_block(LIVE_BLOCK_FRAMES) {
+        stream.push_samples(&block);
+        let ahead = scheduler.position().saturating_sub(start.elapsed());
+        if !sleep_unless_stopped(ahead.saturating_sub(LIVE_LOOKAHEAD)) {
+            stream.stop();
+            return;
+        }
+    }
+
+    if sleep_unless_stopped(scheduler.position().saturating_sub(start.elapsed())) {
+        stream.wait_until_played();
+    } else {
+        stream.stop();
+    }
+}
+
+/// How often [`MusicPlayer::play_with_position`] reports the position
+#[cfg(feature = "live-output")]
+const POSITION_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Calls `on_position` with the time since this was called, until `playback` finishes or live playback is stopped.
+#[cfg(feature = "live-output")]
+fn report_position(playback: JoinHandle<()>, mut on_position: impl FnMut(Duration)) {
+    let start = Instant::now();
+    while !playback.is_finished() {
+        on_position(start.elapsed());
+        if !sleep_unless_stopped(POSITION_INTERVAL) {
+            break;
+        }
+    }
+    let _ = playback.join();
+    on_position(start.elapsed());
+}
+
+#[test]
+#[cfg(feature = "live-output")]
+fn test_report_position_until_playback_finishes() {
+    // A stand-in for playback that doesn't need an audio device
+    let playback = thread::spawn(|| thread::sleep(Duration::from_millis(100)));
+
+    let mut positions = Vec::new();
+    report_position(playback, |position| positions.push(position));
+
+    assert!(positions.len() > 2);
+    assert!(positions.windows(2).all(|pair| pair[0] <= pair[1]));
+    assert!(positions[0] < Duration::from_millis(50));
+    assert!(*positions.last().unwrap() >= Duration::from_millis(100));
+}
+
+/// Measures the spectrum of rendered samples in tests
+#[cfg(test)]
+pub(crate) mod spectrum {
+    /// Estimates the energy of `samples` at a frequency with the Goertzel algorithm
+    #[expect(clippy::cast_precision_loss, reason = "Sample rates are far below f32's exact integer range")]
+    pub(crate) fn energy_at(samples: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+        let coefficient = 2.0 * (std::f32::consts::TAU * frequency / sample_rate as f32).cos();
+        let (mut s1, mut s2) = (0.0, 0.0);
+        for &sample in samples {
+            (s1, s2) = (sample + coefficient * s1 - s2, s1);
+        }
+        s1 * s1 + s2 * s2 - coefficient * s1 * s2
+    }
+}
+
+/// Stores every stream opened during live playback and the samples pushed to it, instead of playing them
+#[cfg(all(test, feature = "live-output"))]
+mod mock_output {
+    use std::sync::{Arc, Mutex};
+
+    use super::{AudioOutput, AudioStream};
+
+    pub type Streams = Arc<Mutex<Vec<(u16, u32, Vec<f32>)>>>;
+
+    /// An output with the given number of channels
+    pub struct MockOutput(pub Streams, pub u16);
+
+    impl AudioOutput for MockOutput {
+        fn open_stream(&self, channels: u16, sample_rate: u32) -> std::io::Result<Box<dyn AudioStream>> {
+            let mut streams = self.0.lock().unwrap();
+            streams.push((channels, sample_rate, Vec::new()));
+            Ok(Box::new(MockStream(self.0.clone(), streams.len().saturating_sub(1))))
+        }
+
+        fn channels(&self) -> u16 {
+            self.1
+        }
+    }
+
+    struct MockStream(Streams, usize);
+
+    impl AudioStream for MockStream {
+        fn push_samples(&mut self, samples: &[f32]) {
+            self.0.lock().unwrap()[self.1].2.extend_from_slice(samples);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "live-output")]
+fn test_live_notes_start_on_exact_samples() {
+    use crate::prelude::*;
+    use mock_output::{MockOutput, Streams};
+
+    // At 7000 BPM a time unit is 411.43 frames at 48 kHz, which rounding to whole frames or milliseconds
+    // would drift away from over a hundred units
+    let streams = Streams::default();
+    let player = MusicPlayer::new_live_with_output(7000, Arc::new(MockOutput(streams.clone(), 1)));
+    let rest = |units| Line::from(Note(NoteLength(units), REST));
+    let line = sine(sixteenth(A4)) + rest(4) + sine(sixteenth(C4)) + rest(95) + sine(sixteenth(A4));
+    player.play(line).join().unwrap();
+
+    // Every note is mixed into a single stream, starting on the frame its time unit starts on
+    let streams = streams.lock().unwrap();
+    assert_eq!(streams.len(), 1);
+    let (channels, sample_rate, samples) = &streams[0];
+    assert_eq!((*channels, *sample_rate), (1, 48000));
+
+    // Frames 0, 5 units * 2880000 / 7000, and 101 units * 2880000 / 7000, each 411 frames long
+    let mut expected = vec![0.0; 41965];
+    for (start, pitch) in [(0, A4), (2057, C4), (41554, A4)] {
+        let (curves, phase) = (EnvelopeCurves::default(), OscillatorPhase::default());
+        let source = sources::get_source(9, pitch.0, crate::Timbre::Sine, 1.0 / 64.0, 0, curves, phase);
+        for (sample, expected) in source.take(411).zip(&mut expected[start..]) {
+            *expected = sample;
+        }
+    }
+    assert!(expected[2057..2468].iter().any(|&sample| sample != 0.0));
+    assert_eq!(*samples, expected);
+}
+
+#[test]
+#[cfg(feature = "live-output")]
+#[expect(clippy::cast_precision_loss, reason = "Sample counts are small")]
+fn test_live_playback_pans_and_downmixes() {
+    use crate::prelude::{quarter, sine};
+    use mock_output::{MockOutput, Streams};
+
+    let play = |channels, pan, pan_law| {
+        let streams = Streams::default();
+        let player = MusicPlayer::new_live_with_output(6000, Arc::new(MockOutput(streams.clone(), channels)))
+            .with_pan_law(pan_law);
+        player.play(sine(quarter(crate::A4)).pan(pan)).join().unwrap();
+        let (stream_channels, _, samples) = streams.lock().unwrap().remove(0);
+        assert_eq!(stream_channels, channels);
+        samples
+    };
+    // The RMS level of one channel of interleaved samples
+    let level = |samples: &[f32], channel: usize, channels: usize| {
+        let channel: Vec<f32> = samples.iter().skip(channel).step_by(channels).copied().collect();
+        (channel.iter().map(|s| s * s).sum::<f32>() / channel.len() as f32).sqrt()
+    };
+
+    // Hard left is silent on the right, and a centered note follows the pan law
+    let left = play(2, -1.0, PanLaw::ConstantPower);
+    assert!(level(&left, 1, 2) == 0.0 && level(&left, 0, 2) > 0.0);
+    let full = level(&left, 0, 2);
+    for (pan_law, expected) in [(PanLaw::ConstantPower, 0.5f32.sqrt()), (PanLaw::Linear, 0.5)] {
+        let center = play(2, 0.0, pan_law);
+        assert!((level(&center, 0, 2) / full - expected).abs() < 1e-3, "{pan_law:?}");
+        assert!((level(&center, 1, 2) / full - expected).abs() < 1e-3, "{pan_law:?}");
+    }
+
+    // Mono outputs get a single channel at the note's full level, wherever it's panned
+    let mono = play(1, 0.7, PanLaw::ConstantPower);
+    assert!((level(&mono, 0, 1) - full).abs() < 1e-6);
+}
+
+#[test]
+#[cfg(feature = "live-output")]
+fn test_live_mixer_widens_and_averages_channels() {
+    // A stereo source on a mono output is averaged down
+    let stereo = [1.0, 0.0, 0.5, 0.5];
+    assert_eq!(
+        mixing::mix_live_note(&stereo, 2, 100, None, 1, 0.0, PanLaw::default()),
+        [0.5, 0.5]
+    );
+
+    // Widening a centered note makes its channels differ, but they still add up to the same mono signal
+    let mono: Vec<f32> = (0..100u8).map(|i| f32::from(i % 7) - 3.0).collect();
+    let narrow = mixing::mix_live_note(&mono, 1, 1000, None, 2, 0.0, PanLaw::default());
+    let wide = mixing::mix_live_note(&mono, 1, 1000, None, 2, 1.0, PanLaw::default());
+    assert!(narrow.chunks(2).all(|frame| frame[0] == frame[1]));
+    assert!(wide.chunks(2).any(|frame| frame[0] != frame[1]));
+    for (narrow, wide) in narrow.chunks(2).zip(wide.chunks(2)) {
+        assert!((narrow[0] + narrow[1] - wide[0] - wide[1]).abs() < 1e-4);
+    }
+}
+
+#[test]
+#[cfg(feature = "wav-output")]
+fn test_reference_pitch_must_be_finite_and_positive() {
+    let player = MusicPlayer::new_file(300, 1.0, 44100);
+    let tuned = |a4: f32| player.clone().with_reference_pitch(NotePitch::new(a4)).reference_pitch;
+    for ignored in [f32::INFINITY, f32::NEG_INFINITY, f32::NAN, 0.0, -442.0] {
+        assert_eq!(tuned(ignored), A4);
+    }
+    assert_eq!(tuned(442.0), NotePitch::new(442.0));
+}
+
+#[cfg(feature = "wav-output")]
 impl MusicPlayer<FileOutputConfig> {
     /// Creates a new music player for file output (WAV rendering).
     ///
@@ -117,30 +617,934 @@ impl MusicPlayer<FileOutputConfig> {
     pub fn new_file(tempo_bpm: u32, output_gain: f32, sample_rate: u32) -> Self {
         Self {
             tempo_bpm,
+            reference_pitch: A4,
+            envelope_curves: EnvelopeCurves::default(),
+            oscillator_phase: OscillatorPhase::default(),
             output_config: FileOutputConfig {
                 output_gain,
                 sample_rate,
+                ..Default::default()
             },
         }
     }
 
+    /// Sets the number of channels in the rendered audio.
+    ///
+    /// By default, the channel count is chosen automatically from the audio sources
+    /// used in the piece (mono for synthesized timbres). Use [`Note::pan`] to place
+    /// notes within the channels.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // Four channels, with the note placed entirely in the first one
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(4);
+    /// let piece = sine(quarter(A4)).pan(-1.0);
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piece, &mut buffer).unwrap();
+    ///
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// assert_eq!(reader.spec().channels, 4);
+    /// assert_eq!(reader.duration(), 8000 * 4 * 200 / 1000);
+    ///
+    /// // Samples are interleaved, one per channel per frame
+    /// let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    /// assert_eq!(samples.len() as u32, 4 * 8000 * 4 * 200 / 1000);
+    /// assert!(samples.iter().skip(0).step_by(4).any(|s| *s != 0));
+    /// for channel in 1..4 {
+    ///     assert!(samples.iter().skip(channel).step_by(4).all(|s| *s == 0));
+    /// }
+    /// ```
+    pub fn with_channels(mut self, channels: u16) -> Self {
+        self.output_config.channels = Some(channels.max(1));
+        self.output_config.channel_layout = None;
+        self
+    }
+
+    /// Sets the speaker layout of the output, which also sets the number of channels.
+    ///
+    /// Files with more than two channels record which speaker each channel is for, so that audio
+    /// workstations and players send them to the right place. Without a layout, the channels are
+    /// assumed to be the first speakers in the standard WAV order. Mono and stereo files don't record
+    /// a layout, so they're written the same way as with [`MusicPlayer::with_channels`].
+    ///
+    /// Panning still spreads notes evenly across the channels in order, whatever speaker they're for.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{ChannelLayout, MusicPlayer};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channel_layout(ChannelLayout::Surround5_1);
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piano(quarter(C4)), &mut buffer).unwrap();
+    ///
+    /// let reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// assert_eq!(reader.spec().channels, 6);
+    /// ```
+    pub fn with_channel_layout(mut self, layout: ChannelLayout) -> Self {
+        self.output_config.channels = Some(layout.channels());
+        self.output_config.channel_layout = Some(layout);
+        self
+    }
+
+    /// Shifts the pitch of the rendered audio by the given number of semitones, without changing its duration.
+    ///
+    /// Unlike transposing the notes with [`Tet12::semitone`](crate::scales::tet12::Tet12::semitone),
+    /// this is applied to the mixed output, so it affects every source, including custom samples
+    /// and drums. Fractional semitones are allowed.
+    ///
+    /// The shift uses synchronized granular overlap-add: the output is built from short (50 ms)
+    /// overlapping grains, each read from around the same point in time of the original render
+    /// but played back at the shifted rate. Grains are lined up with each other to avoid phase
+    /// cancellation. This keeps timing exact, at the cost of some "grainy" artifacts on large
+    /// shifts and slower rendering.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let original = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let shifted = MusicPlayer::new_file(300, 1.0, 44100).with_pitch_shift(7.0);
+    ///
+    /// // Estimate the frequency of each render by counting zero crossings
+    /// let renders = [original, shifted].map(|player| {
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(sine(whole(A4)), &mut buffer).unwrap();
+    ///     let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    ///     let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    ///     let crossings = samples.windows(2).filter(|w| (w[0] < 0) != (w[1] < 0)).count();
+    ///     (samples.len(), crossings as f32 / 2.0 / (samples.len() as f32 / 44100.0))
+    /// });
+    ///
+    /// // The duration is unchanged, and the frequency is a fifth higher
+    /// assert_eq!(renders[0].0, renders[1].0);
+    /// let ratio = renders[1].1 / renders[0].1;
+    /// assert!((ratio - 2.0f32.powf(7.0 / 12.0)).abs() < 0.01);
+    /// ```
+    pub fn with_pitch_shift(mut self, semitones: f32) -> Self {
+        self.output_config.pitch_shift = semitones;
+        self
+    }
+
+    /// Sets how samples are interpolated when sources are resampled, such as when
+    /// pitching a custom source.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Interpolation, MusicPlayer};
+    ///
+    /// let sample = Timbre::CustomSourcePitched("path/to/sample.wav");
+    /// let piece = Piece::from(quarter(C4.semitone(7)).with_timbre(sample));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_interpolation(Interpolation::Cubic);
+    /// player.render_to_wav(piece, "path/to/output.wav");
+    /// ```
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.output_config.interpolation = interpolation;
+        self
+    }
+
+    /// Sets how samples that are too loud are handled, such as when overdriving the output gain.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{ClipMode, MusicPlayer};
+    ///
+    /// // Overdrive the output for a distorted sound
+    /// let player = MusicPlayer::new_file(300, 4.0, 8000).with_clip_mode(ClipMode::Soft);
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(sine(quarter(A4)), &mut buffer).unwrap();
+    ///
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// let peak = reader.samples::<i16>().map(|s| s.unwrap().unsigned_abs()).max().unwrap();
+    /// assert!(peak < i16::MAX as u16);
+    /// ```
+    pub fn with_clip_mode(mut self, clip_mode: ClipMode) -> Self {
+        self.output_config.clip_mode = clip_mode;
+        self
+    }
+
+    /// Sets how the rendered audio is dithered when it's converted to 16 bit samples.
+    ///
+    /// Converting to whole numbers rounds every sample, and in quiet passages that rounding follows
+    /// the waveform closely enough to be heard as distortion. Dithering adds a tiny amount of noise
+    /// first, which turns the distortion into a faint, even hiss. The noise comes from the player's
+    /// seed, so dithered renders are still reproducible.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{DitherMode, MusicPlayer};
+    ///
+    /// // A very quiet render, where rounding is most noticeable
+    /// let player = MusicPlayer::new_file(300, 0.001, 44100).with_dither(DitherMode::Triangular);
+    /// player.render_to_wav(piano(whole(C4)), "quiet.wav");
+    /// # std::fs::remove_file("quiet.wav").unwrap();
+    /// ```
+    pub fn with_dither(mut self, dither: DitherMode) -> Self {
+        self.output_config.dither = dither;
+        self
+    }
+
+    /// Sets how each sample is stored in the WAV file.
+    ///
+    /// With [`BitDepth::Float32`], the rendered samples are written exactly as they are, so nothing is lost
+    /// to rounding, and peaks above 1.0 are kept for later processing rather than clipped. Dithering and
+    /// the clip mode only apply to 16 bit output.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{BitDepth, MusicPlayer};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_bit_depth(BitDepth::Float32);
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piano(quarter(C4)), &mut buffer).unwrap();
+    ///
+    /// let reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// assert_eq!(reader.spec().sample_format, hound::SampleFormat::Float);
+    /// assert_eq!(reader.spec().bits_per_sample, 32);
+    /// ```
+    pub fn with_bit_depth(mut self, bit_depth: BitDepth) -> Self {
+        self.output_config.bit_depth = bit_depth;
+        self
+    }
+
+    /// Sets the precision notes are added together at while they're mixed.
+    ///
+    /// Every note is added into the mix one at a time, and with [`MixPrecision::Single`] each addition rounds
+    /// to 32 bits. That's inaudible for most pieces, but the rounding adds up when hundreds of quiet notes
+    /// overlap. [`MixPrecision::Double`] mixes at 64 bits instead, which takes twice the memory for the mix.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MixPrecision, MusicPlayer};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_mix_precision(MixPrecision::Double);
+    /// let chord = piano(quarter_chord([C4, C4.semitone(4), C4.semitone(7)]));
+    ///
+    /// // A few notes sound the same at either precision
+    /// let single = MusicPlayer::new_file(300, 1.0, 8000).render_channels(chord.clone());
+    /// let double = player.render_channels(chord);
+    /// assert!(single[0].iter().zip(&double[0]).all(|(a, b)| (a - b).abs() < 1e-6));
+    /// ```
+    pub fn with_mix_precision(mut self, precision: MixPrecision) -> Self {
+        self.output_config.mix_precision = precision;
+        self
+    }
+
+    /// Sets the seed for all randomness used while rendering, such as [`Timbre::Noise`](crate::Timbre::Noise).
+    ///
+    /// Rendering the same piece with the same seed and settings always produces identical output,
+    /// which is useful for testing and reproducible builds. The default seed is 0.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let piece = noise(quarter(C4) + quarter(C4)) * piano(half(C4));
+    ///
+    /// let render = |seed| {
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     let player = MusicPlayer::new_file(300, 1.0, 8000).with_seed(seed);
+    ///     player.write_wav_to(piece.clone(), &mut buffer).unwrap();
+    ///     buffer.into_inner()
+    /// };
+    ///
+    /// assert_eq!(render(1), render(1));
+    /// assert_ne!(render(1), render(2));
+    /// ```
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.output_config.seed = seed;
+        self
+    }
+
+    /// Normalizes the rendered audio to the given integrated loudness, in LUFS, instead of to its peak.
+    ///
+    /// By default, every channel is scaled so that its loudest sample has the volume of the output gain.
+    /// With a loudness target, a single gain is applied to the whole render instead, so that it measures
+    /// `lufs` according to [`Piece::loudness_lufs`]. Streaming services usually expect around -14 LUFS.
+    ///
+    /// Any peaks that the gain would push above -1 dBFS are turned down by a limiter, so the output never
+    /// clips. This replaces the output gain. Very loud targets may be out of reach without limiting the
+    /// audio heavily, so they're only approached as closely as possible.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let piece = piano(quarter(C4) + quarter(A4) + half(C4)) * bass(whole(C4.octave(-1)));
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100).with_loudness_target(-14.0);
+    ///
+    /// assert!((piece.loudness_lufs(&player) - -14.0).abs() < 0.1);
+    ///
+    /// // Even when pushed loud enough to need limiting, the output stays below full scale
+    /// let loud = MusicPlayer::new_file(300, 1.0, 44100).with_loudness_target(-6.0);
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// loud.write_wav_to(piece, &mut buffer).unwrap();
+    ///
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// let peak = reader.samples::<i16>().map(|s| s.unwrap().unsigned_abs()).max().unwrap();
+    /// assert!(peak < i16::MAX as u16);
+    /// ```
+    pub fn with_loudness_target(mut self, lufs: f32) -> Self {
+        self.output_config.loudness_target = Some(lufs);
+        self
+    }
+
+    /// Limits the rendered audio so that it never goes above `threshold`, in dBFS.
+    ///
+    /// Audio quieter than the threshold passes through unchanged. Around louder peaks, the volume is
+    /// turned down smoothly, starting just before the peak and recovering shortly after it, rather than
+    /// flattening the peaks like clipping does. This is useful with an output gain above 1.0, or when
+    /// many loud voices are layered together.
+    ///
+    /// The output is limited to -1 dBFS by default, which is a good choice for most music. Use
+    /// [`MusicPlayer::without_limiter`] to turn the limiter off.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // An output gain of 2 would clip, but the limiter keeps the peaks at -1 dBFS
+    /// let player = MusicPlayer::new_file(300, 2.0, 8000).with_limiter(-1.0);
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piano(quarter(C4) + quarter(A4)), &mut buffer).unwrap();
+    ///
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// let peak = reader.samples::<i16>().map(|s| s.unwrap().unsigned_abs()).max().unwrap();
+    /// assert!(peak <= (0.892 * i16::MAX as f32) as u16);
+    /// ```
+    pub fn with_limiter(mut self, threshold: f32) -> Self {
+        self.output_config.limiter_threshold = Some(threshold);
+        self
+    }
+
+    /// Turns off the limiter, so the loudest peaks reach the output gain.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let peak = |player: MusicPlayer<_>| {
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(piano(quarter(C4) + quarter(A4)), &mut buffer).unwrap();
+    ///     let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    ///     reader.samples::<i16>().map(|s| s.unwrap().unsigned_abs()).max().unwrap()
+    /// };
+    ///
+    /// // Without the limiter, peaks are normalized all the way to full scale
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// assert!(peak(player.clone()) < peak(player.without_limiter()));
+    /// ```
+    pub fn without_limiter(mut self) -> Self {
+        self.output_config.limiter_threshold = None;
+        self
+    }
+
+    /// Sets how the length of each time unit is rounded to a whole number of samples.
+    ///
+    /// Use [`RoundMode::Compensated`] to keep long pieces from drifting away from the tempo.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, RoundMode};
+    ///
+    /// // Each time unit is 8000 * 60 / 7 = 68571.43 samples long
+    /// let piece = sine(whole(A4));
+    ///
+    /// let duration = |round_mode| {
+    ///     let player = MusicPlayer::new_file(7, 1.0, 8000).with_round_mode(round_mode);
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(piece.clone(), &mut buffer).unwrap();
+    ///     hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap().duration()
+    /// };
+    ///
+    /// assert_eq!(duration(RoundMode::Milliseconds), 8571 * 16 * 8); // 8 samples in each whole millisecond
+    /// assert_eq!(duration(RoundMode::Floor), 68571 * 16);
+    /// assert_eq!(duration(RoundMode::Ceil), 68572 * 16);
+    /// assert_eq!(duration(RoundMode::Compensated), 1097143); // 68571.43 * 16, rounded
+    /// ```
+    pub fn with_round_mode(mut self, round_mode: RoundMode) -> Self {
+        self.output_config.round_mode = round_mode;
+        self
+    }
+
+    /// Sets whether silence is removed from the start and end of the rendered audio.
+    ///
+    /// This is useful for pieces that begin or end with rests. Only samples quieter than -80 dBFS
+    /// in every channel are removed, and silence between notes is always kept. If the whole piece
+    /// is silent, the result is empty.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let piece = piano(quarter(REST) + quarter(C4) + quarter(REST) + quarter(C4));
+    ///
+    /// let render = |trim_silence| {
+    ///     let player = MusicPlayer::new_file(300, 1.0, 8000).with_trim_silence(trim_silence);
+    ///     let mut buffer = Cursor::new(Vec::new());
+    ///     player.write_wav_to(piece.clone(), &mut buffer).unwrap();
+    ///     let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    ///     reader.samples::<i16>().map(Result::unwrap).collect::<Vec<_>>()
+    /// };
+    ///
+    /// // Each quarter note is 8000 * 4 * 200 / 1000 = 6400 samples long
+    /// let untrimmed = render(false);
+    /// assert_eq!(untrimmed.len(), 4 * 6400);
+    /// assert!(untrimmed[..6400].iter().all(|&s| s == 0));
+    ///
+    /// // The leading rest is gone, but the one between the notes is kept
+    /// let trimmed = render(true);
+    /// assert!(trimmed.len() <= 3 * 6400 && trimmed.len() > 2 * 6400);
+    /// assert_ne!(trimmed[0], 0);
+    /// assert!(trimmed[6400..2 * 6400].iter().all(|&s| s == 0));
+    /// ```
+    pub fn with_trim_silence(mut self, trim_silence: bool) -> Self {
+        self.output_config.trim_silence = trim_silence;
+        self
+    }
+
+    /// Sets how far the sound is spread between the left and right channels, from 0.0 (mono) to 1.0 (full).
+    ///
+    /// This adds a delayed copy of the sound to the side (left minus right) signal, which gives a fuller,
+    /// wider sound without panning each note. The left and right channels still add up to the original
+    /// sound, so the output sounds the same when played in mono. Only two-channel output is widened.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2).with_stereo_width(0.5);
+    /// let piece = piano(quarter(C4) + quarter(A4));
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piece, &mut buffer).unwrap();
+    ///
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    /// assert!(samples.chunks(2).any(|frame| frame[0] != frame[1]));
+    /// ```
+    pub fn with_stereo_width(mut self, stereo_width: f32) -> Self {
+        self.output_config.stereo_width = stereo_width.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Lets every note keep sounding for a while after it ends, blending into the notes that follow.
+    ///
+    /// Each note is rendered as if it were `overlap` longer, including its release, so a legato line
+    /// flows from one note to the next instead of stopping between them. This doesn't change the
+    /// timing of any note, and the tail of the last note is cut off at the end of the piece.
+    ///
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_release_overlap(Duration::from_millis(100));
+    /// let samples = &player.render_channels(sine(quarter(C4) + quarter(REST)))[0];
+    ///
+    /// // The note carries on into the rest, for 100 ms (800 samples)
+    /// assert!(samples[6400..7000].iter().any(|&s| s != 0.0));
+    /// assert!(samples[7200..].iter().all(|&s| s == 0.0));
+    /// ```
+    pub fn with_release_overlap(mut self, overlap: std::time::Duration) -> Self {
+        self.output_config.release_overlap = overlap;
+        self
+    }
+
+    /// Fades every note in and out over `ms` milliseconds, so that notes starting or stopping abruptly don't click.
+    ///
+    /// Timbres have envelopes of their own, but some start or stop sharply enough to click, especially when a
+    /// note is cut short. This evens them out, by ramping the level of every note up from silence at its start,
+    /// and back down to silence at its end. Very short notes get shorter ramps, so that no more than a quarter of
+    /// a note is spent on each ramp. A length of 0.0, the default, turns the ramps off.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// // An electric guitar note starts at full level
+    /// let note = || electric_guitar(Line::from(quarter(A4)));
+    /// let plain = MusicPlayer::new_file(300, 1.0, 8000).render_channels(note());
+    /// let declicked = MusicPlayer::new_file(300, 1.0, 8000).with_declick(5.0).render_channels(note());
+    ///
+    /// // Ramped over 5 ms, or 40 samples
+    /// assert_eq!(declicked[0][0], 0.0);
+    /// assert!(declicked[0][20].abs() < plain[0][20].abs());
+    ///
+    /// // The rest of the note is barely changed
+    /// assert!((declicked[0][3000] - plain[0][3000]).abs() < 0.01);
+    /// ```
+    pub fn with_declick(mut self, ms: f32) -> Self {
+        self.output_config.declick_ms = ms.max(0.0);
+        self
+    }
+
+    /// Sets how panned notes are split between the channels either side of them.
+    ///
+    /// The default, [`PanLaw::ConstantPower`], keeps a note equally loud wherever it's panned.
+    /// See [`PanLaw`] for the other laws.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, PanLaw};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000)
+    ///     .with_channels(2)
+    ///     .with_pan_law(PanLaw::Linear)
+    ///     .without_limiter();
+    ///
+    /// // A hard right note sets the level, then a centered note is half as loud in the right channel
+    /// let samples = player.render_channels(sine(quarter(A4)).pan(1.0) + sine(quarter(A4)).pan(0.0));
+    /// let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    /// assert!((peak(&samples[1][6400..]) - 0.5).abs() < 1e-3);
+    /// ```
+    pub fn with_pan_law(mut self, pan_law: PanLaw) -> Self {
+        self.output_config.pan_law = pan_law;
+        self
+    }
+
+    /// Marks part of the audio to be looped, by writing a `smpl` chunk to the WAV file.
+    ///
+    /// Samplers and game engines read the loop points from this chunk to loop the audio seamlessly.
+    /// The loop is clipped to the end of the piece, and nothing is written if it's empty. Loop points
+    /// are counted from the start of the rendered audio, so they aren't moved if [silence is trimmed](Self::with_trim_silence).
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{LoopRegion, MusicPlayer};
+    ///
+    /// // An intro that's played once, followed by a section that loops
+    /// let piece = Piece::from(piano(half(C4)) + label("loop", piano(quarter(A4) + quarter(C4))));
+    /// let looped = &piece.labels()[0];
+    /// assert_eq!((looped.start, looped.end), (8, 16));
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_loop(LoopRegion::TimeUnits(looped.start..looped.end));
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piece, &mut buffer).unwrap();
+    ///
+    /// let bytes = buffer.into_inner();
+    /// assert!(bytes.windows(4).any(|tag| tag == b"smpl"));
+    /// ```
+    pub fn with_loop(mut self, region: LoopRegion) -> Self {
+        self.output_config.loop_region = Some(region);
+        self
+    }
+
     /* See render_to_wav.rs for implementation */
 }
 
 trait MusicOutput {}
 
+/// The shape of a fade, such as the attack or release of a note. See [`MusicPlayer::with_envelope_curves`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum CurveShape {
+    /// Changes at a constant rate
+    #[default]
+    Linear,
+    /// Changes quickly at first, then slows down as it approaches the end, like a natural decay
+    Exponential,
+    /// Changes slowly at first, then speeds up towards the end
+    Logarithmic,
+}
+
+impl CurveShape {
+    /// How far through the change the curve is, from 0.0 to 1.0, at `progress` through the fade
+    pub(crate) fn apply(self, progress: f32) -> f32 {
+        /// How sharply the non-linear curves bend
+        const STEEPNESS: f32 = 5.0;
+
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            CurveShape::Linear => progress,
+            CurveShape::Exponential => (1.0 - (-STEEPNESS * progress).exp()) / (1.0 - (-STEEPNESS).exp()),
+            CurveShape::Logarithmic => 1.0 - CurveShape::Exponential.apply(1.0 - progress),
+        }
+    }
+}
+
+/// Where in its cycle an oscillator starts. See [`MusicPlayer::with_oscillator_phase`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OscillatorPhase {
+    /// Every note starts at this point in its cycle, from 0.0 to 1.0. Higher harmonics start at the same
+    /// point in time, so the shape of the wave is kept
+    Fixed(f32),
+    /// Every note starts at a random point in its cycle, chosen by the render seed
+    Random,
+}
+
+impl Default for OscillatorPhase {
+    fn default() -> Self {
+        OscillatorPhase::Fixed(0.0)
+    }
+}
+
+/// The shapes of the fades at the start and end of each note
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub(crate) struct EnvelopeCurves {
+    pub(crate) attack: CurveShape,
+    pub(crate) release: CurveShape,
+}
+
+/// When set, all live playback stops as soon as possible, such as when the user presses Ctrl-C.
+#[cfg(feature = "live-output")]
+pub(crate) static STOP_LIVE_PLAYBACK: AtomicBool = AtomicBool::new(false);
+
+/// Sleeps for the given duration, waking up early if live playback is stopped.
+///
+/// Returns `false` if playback was stopped.
+#[cfg(feature = "live-output")]
+fn sleep_unless_stopped(duration: Duration) -> bool {
+    let end = Instant::now().checked_add(duration);
+    loop {
+        if STOP_LIVE_PLAYBACK.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        let remaining = end.map_or(Duration::MAX, |end| end.saturating_duration_since(Instant::now()));
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(Duration::from_millis(10)));
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
+#[cfg(feature = "wav-output")]
 pub struct FileOutputConfig {
     /// Gain applied to the output audio (default: 1.0)
     pub output_gain: f32,
     /// Sample rate for audio generation (default: 44100 Hz)
     pub sample_rate: u32,
+    /// Number of output channels, or `None` to choose from the sources used (default: `None`)
+    pub channels: Option<u16>,
+    /// Pitch shift applied to the rendered audio, in semitones (default: 0.0)
+    pub pitch_shift: f32,
+    /// How samples are interpolated when resampling sources (default: [`Interpolation::Linear`])
+    pub interpolation: Interpolation,
+    /// How samples outside of the -1.0 to 1.0 range are handled (default: [`ClipMode::Hard`])
+    pub clip_mode: ClipMode,
+    /// Seed for all randomness used while rendering, such as noise (default: 0)
+    pub seed: u64,
+    /// Integrated loudness the output is normalized to, in LUFS, or `None` to normalize peaks instead (default: `None`)
+    pub loudness_target: Option<f32>,
+    /// How time units are rounded to whole samples (default: [`RoundMode::Milliseconds`])
+    pub round_mode: RoundMode,
+    /// Whether silence is removed from the start and end of the rendered audio (default: false)
+    pub trim_silence: bool,
+    /// How far two-channel output is spread between the left and right channels, from 0.0 to 1.0 (default: 0.0)
+    pub stereo_width: f32,
+    /// The level the limiter keeps the output under, in dBFS, or `None` for no limiter (default: -1 dBFS)
+    pub limiter_threshold: Option<f32>,
+    /// How the output is dithered when it's converted to 16 bit samples (default: [`DitherMode::None`])
+    pub dither: DitherMode,
+    /// How each sample is stored in the WAV file (default: [`BitDepth::Int16`])
+    pub bit_depth: BitDepth,
+    /// The precision notes are added together at (default: [`MixPrecision::Single`])
+    pub mix_precision: MixPrecision,
+    /// How long each note keeps sounding past its end, overlapping the notes after it (default: zero)
+    pub release_overlap: std::time::Duration,
+    /// How long the fades at the start and end of every note are, in milliseconds, to stop them clicking (default: 0.0)
+    pub declick_ms: f32,
+    /// The speaker each channel is for, or `None` for the standard WAV order (default: `None`)
+    pub channel_layout: Option<ChannelLayout>,
+    /// How panned notes are split between neighbouring channels (default: [`PanLaw::ConstantPower`])
+    pub pan_law: PanLaw,
+    /// The part of the audio that's marked to be looped, or `None` for no loop (default: `None`)
+    pub loop_region: Option<LoopRegion>,
+    /// Effects applied to every channel of the rendered audio, in order (default: none)
+    pub effects: EffectChain,
+}
+
+/// Statistics about a render, from [`MusicPlayer::render_to_wav_with_stats`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg(feature = "wav-output")]
+pub struct RenderStats {
+    /// How long the render took, including writing the file
+    pub elapsed: std::time::Duration,
+    /// The number of pitched notes in the piece, including any that were skipped by chance
+    pub note_count: usize,
+    /// The most notes that were playing at the same time
+    pub peak_voices: usize,
+    /// The largest absolute sample value, before it was converted to 16 bits
+    pub peak_amplitude: f32,
+}
+
+/// The error held by the [`io::Error`](std::io::Error) that [`MusicPlayer::render_to_wav_cancelable`] returns
+/// when its render is cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg(feature = "wav-output")]
+pub struct RenderCancelled;
+
+#[cfg(feature = "wav-output")]
+impl std::fmt::Display for RenderCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the render was cancelled")
+    }
+}
+
+#[cfg(feature = "wav-output")]
+impl std::error::Error for RenderCancelled {}
+
+/// Which speakers the channels of the output are for, in order.
+///
+/// Layouts with more than two channels are recorded in the WAV file as a channel mask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg(feature = "wav-output")]
+pub enum ChannelLayout {
+    /// One channel: center
+    Mono,
+    /// Two channels: left and right
+    Stereo,
+    /// Four channels: front left, front right, back left, and back right
+    Quad,
+    /// Six channels: left, right, center, low frequency effects (LFE), side left, and side right
+    Surround5_1,
+    /// Eight channels: left, right, center, LFE, back left, back right, side left, and side right
+    Surround7_1,
+}
+
+#[cfg(feature = "wav-output")]
+impl ChannelLayout {
+    /// The number of channels in the layout.
+    pub fn channels(self) -> u16 {
+        match self {
+            ChannelLayout::Mono => 1,
+            ChannelLayout::Stereo => 2,
+            ChannelLayout::Quad => 4,
+            ChannelLayout::Surround5_1 => 6,
+            ChannelLayout::Surround7_1 => 8,
+        }
+    }
+
+    /// The WAV channel mask of the layout, with one bit set for each speaker.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::ChannelLayout;
+    ///
+    /// assert_eq!(ChannelLayout::Stereo.channel_mask(), 0b11);
+    /// assert_eq!(ChannelLayout::Surround5_1.channel_mask().count_ones(), 6);
+    /// ```
+    pub fn channel_mask(self) -> u32 {
+        const FRONT_LEFT: u32 = 0x1;
+        const FRONT_RIGHT: u32 = 0x2;
+        const FRONT_CENTER: u32 = 0x4;
+        const LOW_FREQUENCY: u32 = 0x8;
+        const BACK_LEFT: u32 = 0x10;
+        const BACK_RIGHT: u32 = 0x20;
+        const SIDE_LEFT: u32 = 0x200;
+        const SIDE_RIGHT: u32 = 0x400;
+
+        let front = FRONT_LEFT | FRONT_RIGHT | FRONT_CENTER | LOW_FREQUENCY;
+        match self {
+            ChannelLayout::Mono => FRONT_CENTER,
+            ChannelLayout::Stereo => FRONT_LEFT | FRONT_RIGHT,
+            ChannelLayout::Quad => FRONT_LEFT | FRONT_RIGHT | BACK_LEFT | BACK_RIGHT,
+            ChannelLayout::Surround5_1 => front | SIDE_LEFT | SIDE_RIGHT,
+            ChannelLayout::Surround7_1 => front | BACK_LEFT | BACK_RIGHT | SIDE_LEFT | SIDE_RIGHT,
+        }
+    }
+}
+
+/// How samples outside of the -1.0 to 1.0 range are handled when the audio is written.
+///
+/// Samples only leave this range when the output gain is above 1.0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum ClipMode {
+    /// Clamps samples to the range, flattening the peaks of the waveform
+    #[default]
+    Hard,
+    /// Saturates samples with a `tanh` curve, for a smoother, warmer distortion.
+    ///
+    /// This also slightly compresses quiet samples, since the curve is never fully straight.
+    Soft,
+    /// Does nothing, so out-of-range samples wrap around when converted to integers.
+    ///
+    /// This is dangerous: wrapped samples jump between the loudest positive and negative
+    /// values, producing loud, harsh noise.
+    None,
+}
+
+/// Which part of the rendered audio is marked to be looped. See [`MusicPlayer::with_loop`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg(feature = "wav-output")]
+pub enum LoopRegion {
+    /// Loops the whole piece, from its first sample to its last
+    WholePiece,
+    /// Loops a range of time units from the start of the piece, such as the span of a [`Label`](crate::Label)
+    TimeUnits(std::ops::Range<usize>),
+}
+
+/// How a panned note's gain is split between the two channels it's placed between.
+///
+/// Each law is described by how loud a note panned to the center is in each channel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum PanLaw {
+    /// Crossfades the gains in a straight line, so a centered note is -6 dB in each channel.
+    ///
+    /// The channels always add up to the original signal, but centered notes sound quieter than panned ones.
+    Linear,
+    /// Follows a quarter sine and cosine, so a centered note is -3 dB in each channel.
+    ///
+    /// The total power stays the same wherever the note is panned, so it keeps a steady perceived loudness.
+    #[default]
+    ConstantPower,
+    /// Takes the square root of the linear gains, so a centered note is -3 dB in each channel.
+    ///
+    /// This also keeps the total power the same, but more of the sound stays in the far channel
+    /// until the note is panned close to the edge.
+    NegThreeDb,
+}
+
+impl PanLaw {
+    /// Gets the gains for the two channels a note is placed between, from how far it is towards the second one (0.0 to 1.0)
+    pub(crate) fn gains(self, position: f32) -> (f32, f32) {
+        match self {
+            PanLaw::Linear => (1.0 - position, position),
+            PanLaw::ConstantPower => {
+                let (sin, cos) = (position * std::f32::consts::FRAC_PI_2).sin_cos();
+                (cos, sin)
+            }
+            PanLaw::NegThreeDb => ((1.0 - position).sqrt(), position.sqrt()),
+        }
+    }
+}
+
+/// How noise is added to the output before it's rounded to 16 bit samples, to hide the rounding.
+///
+/// Amounts of noise are measured in least significant bits (LSB): the gap between neighbouring 16 bit values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum DitherMode {
+    /// Adds no noise, so the output is exactly the rounded samples
+    #[default]
+    None,
+    /// Adds noise spread evenly within half an LSB either way.
+    ///
+    /// This hides the distortion, but the hiss gets louder and quieter along with the music.
+    Rectangular,
+    /// Adds noise within one LSB either way, more often near zero than at the edges.
+    ///
+    /// This is a little louder than rectangular dither, but the hiss stays steady, so it's the usual choice.
+    Triangular,
+}
+
+/// How each sample is stored in a WAV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum BitDepth {
+    /// 16 bit integers, as on a CD. Samples are rounded, and clipped to between -1.0 and 1.0
+    #[default]
+    Int16,
+    /// 32 bit floating point numbers, which store the rendered samples exactly.
+    ///
+    /// The file is marked as `WAVE_FORMAT_IEEE_FLOAT` through a `WAVE_FORMAT_EXTENSIBLE` header, which
+    /// is required for samples larger than 16 bits.
+    Float32,
+}
+
+/// The precision notes are added together at when they're mixed, from [`MusicPlayer::with_mix_precision`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum MixPrecision {
+    /// 32 bit floating point numbers, which are plenty for most pieces and use half the memory
+    #[default]
+    Single,
+    /// 64 bit floating point numbers, which keep sums of many quiet notes accurate.
+    ///
+    /// The mix is converted back to 32 bits once every note has been added, before any effects.
+    Double,
+}
+
+/// How the length of each time unit is rounded to a whole number of samples when rendering.
+///
+/// A time unit usually lasts a fractional number of samples, such as 8268.75 at 320 BPM and 44100 Hz.
+/// Rounding every unit the same way adds up over a long piece, so it ends up slightly faster or
+/// slower than the tempo it was rendered at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum RoundMode {
+    /// Rounds every time unit down to a whole millisecond, then each position down to a sample, so the piece
+    /// drifts early by up to a millisecond a unit. This is how time was always rounded before it was configurable.
+    #[default]
+    Milliseconds,
+    /// Rounds every time unit down, so the piece drifts early
+    Floor,
+    /// Rounds every time unit to the nearest sample, so the piece drifts whichever way is closer
+    Round,
+    /// Rounds every time unit up, so the piece drifts late
+    Ceil,
+    /// Lets time units vary in length by one sample, carrying the rounding error over to the next.
+    ///
+    /// Every note starts within a sample of its exact time, no matter how long the piece is.
+    Compensated,
+}
+
+/// How audio samples are interpolated when a source is resampled.
+///
+/// Sources are resampled whenever their sample rate differs from the output's, which
+/// includes every pitched custom source played at a pitch other than C4.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg(feature = "wav-output")]
+pub enum Interpolation {
+    /// Uses the nearest sample. Fast, but adds a crunchy, aliased quality
+    NearestNeighbor,
+    /// Draws a straight line between neighbouring samples
+    #[default]
+    Linear,
+    /// Fits a smooth curve through the four nearest samples
+    Cubic,
 }
 
 #[derive(Clone)]
 #[cfg(feature = "live-output")]
 pub struct LiveOutputConfig {
-    pub output_handle: Arc<rodio::OutputStreamHandle>,
+    /// Where the audio is sent, such as a rodio [`OutputStreamHandle`](rodio::OutputStreamHandle)
+    pub output_handle: Arc<dyn AudioOutput>,
+    /// How far two-channel output is spread between the left and right channels, from 0.0 to 1.0 (default: 0.0)
+    pub stereo_width: f32,
+    /// How panned notes are split between the left and right channels (default: [`PanLaw::ConstantPower`])
+    pub pan_law: PanLaw,
 }
 
 #[cfg(feature = "wav-output")]
@@ -152,6 +1556,25 @@ impl Default for FileOutputConfig {
         FileOutputConfig {
             output_gain: 1.0,
             sample_rate: 44100,
+            channels: None,
+            pitch_shift: 0.0,
+            interpolation: Interpolation::default(),
+            clip_mode: ClipMode::default(),
+            seed: 0,
+            loudness_target: None,
+            round_mode: RoundMode::default(),
+            trim_silence: false,
+            stereo_width: 0.0,
+            limiter_threshold: Some(-1.0),
+            dither: DitherMode::default(),
+            bit_depth: BitDepth::default(),
+            mix_precision: MixPrecision::default(),
+            release_overlap: std::time::Duration::ZERO,
+            declick_ms: 0.0,
+            channel_layout: None,
+            pan_law: PanLaw::default(),
+            loop_region: None,
+            effects: EffectChain::default(),
         }
     }
 }
@@ -167,7 +1590,14 @@ pub(crate) trait Playable {
     fn get_notes_at_instant(&self, instant: usize) -> impl Iterator<Item = Note>;
 
     #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()>
+    fn play(
+        &self,
+        output: LiveOutputConfig,
+        tempo_bpm: u32,
+        tuning_ratio: f32,
+        envelope_curves: EnvelopeCurves,
+        oscillator_phase: OscillatorPhase,
+    ) -> JoinHandle<()>
     where
         Self: Send + Sync + Clone + 'static;
 }
@@ -184,23 +1614,25 @@ impl Playable for Piece {
     }
 
     #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        let piece = self.clone();
-
-        thread::spawn(move || {
-            let mut handles = Vec::new();
-            for instant in 0..piece.length() {
-                for note in piece.get_notes_at_instant(instant) {
-                    handles.push(note.play(output_handle.clone(), beat_duration_ms));
-                }
-
-                thread::sleep(Duration::from_millis(beat_duration_ms));
-            }
-
-            for handle in handles {
-                let _ = handle.join();
-            }
-        })
+    fn play(
+        &self,
+        output: LiveOutputConfig,
+        tempo_bpm: u32,
+        tuning_ratio: f32,
+        envelope_curves: EnvelopeCurves,
+        oscillator_phase: OscillatorPhase,
+    ) -> JoinHandle<()> {
+        let stream_output = output.output_handle.clone();
+        let scheduler = Scheduler::new(
+            self.notes(),
+            self.length(),
+            tempo_bpm,
+            output,
+            tuning_ratio,
+            envelope_curves,
+            oscillator_phase,
+        );
+        thread::spawn(move || play_scheduled(scheduler, &*stream_output))
     }
 }
 
@@ -217,23 +1649,15 @@ impl Playable for Line {
     }
 
     #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        let line = self.clone();
-
-        thread::spawn(move || {
-            let mut handles = Vec::new();
-            for instant in 0..line.length() {
-                for note in line.get_notes_at_instant(instant) {
-                    handles.push(note.play(output_handle.clone(), beat_duration_ms));
-                }
-
-                thread::sleep(Duration::from_millis(beat_duration_ms));
-            }
-
-            for handle in handles {
-                let _ = handle.join();
-            }
-        })
+    fn play(
+        &self,
+        output: LiveOutputConfig,
+        tempo_bpm: u32,
+        tuning_ratio: f32,
+        envelope_curves: EnvelopeCurves,
+        oscillator_phase: OscillatorPhase,
+    ) -> JoinHandle<()> {
+        Piece::from(self.clone()).play(output, tempo_bpm, tuning_ratio, envelope_curves, oscillator_phase)
     }
 }
 
@@ -253,20 +1677,14 @@ impl Playable for Note {
     }
 
     #[cfg(feature = "live-output")]
-    fn play(&self, output_handle: Arc<rodio::OutputStreamHandle>, beat_duration_ms: u64) -> JoinHandle<()> {
-        if let Note(length, NoteKind::Pitched { pitch, timbre, volume }) = *self {
-            #[expect(clippy::arithmetic_side_effects, reason = "User's fault")]
-            let duration_ms = length.0 as u64 * beat_duration_ms;
-
-            thread::spawn(move || {
-                let sink = rodio::Sink::try_new(&output_handle.clone()).unwrap();
-                // For some reason, playing live is way louder than file output. 64 is arbitrary, but seems about right.
-                sink.append(get_source(duration_ms, pitch.0, timbre, volume / 64.0));
-                thread::sleep(Duration::from_millis(duration_ms));
-                sink.sleep_until_end();
-            })
-        } else {
-            thread::spawn(|| {})
-        }
+    fn play(
+        &self,
+        output: LiveOutputConfig,
+        tempo_bpm: u32,
+        tuning_ratio: f32,
+        envelope_curves: EnvelopeCurves,
+        oscillator_phase: OscillatorPhase,
+    ) -> JoinHandle<()> {
+        Piece::from(*self).play(output, tempo_bpm, tuning_ratio, envelope_curves, oscillator_phase)
     }
 }
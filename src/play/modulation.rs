@@ -0,0 +1,68 @@
+//! Wobbling rendered notes' level, pitch, or filter cutoff in time with the piece, for [`lfo`](crate::lfo).
+
+#![expect(
+    clippy::arithmetic_side_effects,
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use crate::note::{Lfo, ModTarget};
+
+impl Lfo {
+    /// Gets the oscillator's value, from -1.0 to 1.0, at `beat` quarter notes from the start of the piece
+    fn value_at(&self, beat: f64) -> f32 {
+        if !(self.rate_beats > 0.0 && self.rate_beats.is_finite()) {
+            return 0.0;
+        }
+        let phase = (beat / f64::from(self.rate_beats)).rem_euclid(1.0);
+        (phase * std::f64::consts::TAU).sin() as f32
+    }
+}
+
+/// Applies `lfo` to one channel of a rendered note, whose first sample is `start` samples into the piece
+pub(super) fn modulate(samples: &mut Vec<f32>, lfo: Lfo, start: usize, samples_per_beat: f64, sample_rate: u32) {
+    let wave = |index: usize| lfo.value_at((start + index) as f64 / samples_per_beat);
+    match lfo.target {
+        ModTarget::Amplitude => {
+            let depth = lfo.depth.clamp(0.0, 1.0);
+            for (index, sample) in samples.iter_mut().enumerate() {
+                *sample *= 1.0 + depth * wave(index);
+            }
+        }
+        ModTarget::Pitch => {
+            // Read through the note faster or slower than normal, which raises or lowers its pitch
+            let at = |index: usize| samples.get(index).copied().unwrap_or_default();
+            let mut position = 0.0f64;
+            let modulated = (0..samples.len())
+                .map(|index| {
+                    let whole = position as usize;
+                    let fraction = (position - whole as f64) as f32;
+                    position += f64::from(2.0f32.powf(lfo.depth * wave(index) / 12.0));
+                    at(whole) + (at(whole + 1) - at(whole)) * fraction
+                })
+                .collect();
+            *samples = modulated;
+        }
+        ModTarget::FilterCutoff { hz } => {
+            let nyquist = sample_rate as f32 / 2.0;
+            // The last two inputs and outputs of the filter
+            let mut history = [0.0f32; 4];
+            for (index, sample) in samples.iter_mut().enumerate() {
+                let cutoff = hz * 2.0f32.powf(lfo.depth * wave(index));
+                // A Butterworth low-pass from the Audio EQ Cookbook, with its coefficients moving with the cutoff
+                let omega = std::f32::consts::PI * (cutoff / nyquist).clamp(0.0001, 0.9999);
+                let alpha = omega.sin() * std::f32::consts::FRAC_1_SQRT_2;
+                let a0 = 1.0 + alpha;
+                let b1 = (1.0 - omega.cos()) / a0;
+                let (b0, a1, a2) = (b1 / 2.0, -2.0 * omega.cos() / a0, (1.0 - alpha) / a0);
+
+                let [x1, x2, y1, y2] = history;
+                let output = b0 * (*sample + x2) + b1 * x1 - a1 * y1 - a2 * y2;
+                history = [*sample, x1, output, y1];
+                *sample = output;
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::io;
+
+use rodio::{
+    buffer::SamplesBuffer,
+    cpal::traits::{DeviceTrait, HostTrait},
+    OutputStreamHandle, Sink,
+};
+
+/// Somewhere live playback can send audio, such as the system's speakers.
+///
+/// Each piece that's played live opens its own stream, with every note already mixed in at the
+/// sample it starts on, so an output only needs to mix together pieces that are played at the same
+/// time. Streams are opened from the playback threads, so outputs must be shareable between threads.
+///
+/// The default output, used by [`MusicPlayer::new_live`](crate::MusicPlayer::new_live), is a rodio
+/// [`OutputStreamHandle`]. Implementing this trait lets other audio backends be used instead, or
+/// lets tests capture the audio without an audio device.
+///
+/// # Examples
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use symphoxy::{AudioOutput, AudioStream};
+///
+/// /// Collects every sample it's given, instead of playing it
+/// #[derive(Default)]
+/// struct Recorder(Arc<Mutex<Vec<f32>>>);
+///
+/// impl AudioOutput for Recorder {
+///     fn open_stream(&self, _channels: u16, _sample_rate: u32) -> std::io::Result<Box<dyn AudioStream>> {
+///         Ok(Box::new(RecorderStream(self.0.clone())))
+///     }
+/// }
+///
+/// struct RecorderStream(Arc<Mutex<Vec<f32>>>);
+///
+/// impl AudioStream for RecorderStream {
+///     fn push_samples(&mut self, samples: &[f32]) {
+///         self.0.lock().unwrap().extend_from_slice(samples);
+///     }
+/// }
+/// ```
+pub trait AudioOutput: Send + Sync {
+    /// Opens a new stream, which will be sent samples with the given number of channels and sample rate.
+    ///
+    /// # Errors
+    /// Returns an error if the stream can't be opened, such as when the audio device has gone away.
+    fn open_stream(&self, channels: u16, sample_rate: u32) -> io::Result<Box<dyn AudioStream>>;
+
+    /// Gets how many channels the output plays, which live playback mixes every note into.
+    ///
+    /// The default is two, for stereo. Outputs that can only play one channel should return 1,
+    /// so that panned and stereo notes are mixed down instead of losing a channel.
+    fn channels(&self) -> u16 {
+        2
+    }
+}
+
+/// A single stream of audio opened by an [`AudioOutput`], which plays a single piece.
+pub trait AudioStream: Send {
+    /// Queues a block of samples to be played after any that were pushed before.
+    ///
+    /// If the stream has more than one channel, the samples are interleaved.
+    fn push_samples(&mut self, samples: &[f32]);
+
+    /// Blocks until every sample that was pushed has been played.
+    ///
+    /// The default does nothing, for outputs that don't play in real time.
+    fn wait_until_played(&mut self) {}
+
+    /// Stops playing straight away, dropping any samples that haven't been played yet.
+    ///
+    /// The default does nothing, for outputs that don't play in real time.
+    fn stop(&mut self) {}
+}
+
+impl AudioOutput for OutputStreamHandle {
+    fn open_stream(&self, channels: u16, sample_rate: u32) -> io::Result<Box<dyn AudioStream>> {
+        let sink = Sink::try_new(self).map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+        Ok(Box::new(RodioStream {
+            sink,
+            channels,
+            sample_rate,
+        }))
+    }
+
+    /// Uses the channel count of the default output device, which is what [`OutputStream::try_default`](rodio::OutputStream::try_default) opens
+    fn channels(&self) -> u16 {
+        rodio::cpal::default_host()
+            .default_output_device()
+            .and_then(|device| device.default_output_config().ok())
+            .map_or(2, |config| config.channels().clamp(1, 2))
+    }
+}
+
+/// A stream that plays through a rodio sink
+struct RodioStream {
+    sink: Sink,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl AudioStream for RodioStream {
+    fn push_samples(&mut self, samples: &[f32]) {
+        self.sink
+            .append(SamplesBuffer::new(self.channels, self.sample_rate, samples.to_vec()));
+    }
+
+    fn wait_until_played(&mut self) {
+        self.sink.sleep_until_end();
+    }
+
+    fn stop(&mut self) {
+        self.sink.stop();
+    }
+}
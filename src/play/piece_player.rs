@@ -0,0 +1,218 @@
+//! Pulling rendered audio a few frames at a time, for embedding in other apps' audio callbacks.
+
+use crate::{play::FileOutputConfig, MusicPlayer, Piece};
+
+/// The number of frames in each block a [`PiecePlayer`] iterates over, unless set with [`PiecePlayer::with_block_size`]
+const DEFAULT_BLOCK_SIZE: usize = 512;
+
+impl Piece {
+    /// Creates a [`PiecePlayer`], which hands out the piece's audio in chunks of any size on demand.
+    ///
+    /// This is for embedding symphoxy in an audio app of your own: call [`PiecePlayer::fill`] from
+    /// its audio callback, and it writes the next frames of the piece into the callback's buffer.
+    /// The samples are exactly the ones [`MusicPlayer::render_channels`] gives, interleaved.
+    ///
+    /// The piece is rendered with `player`'s settings when the `PiecePlayer` is created, so create it
+    /// ahead of time. Filling is then only a copy, which is quick enough for a real-time callback.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    /// let mut stream = Piece::from(piano(quarter(C4))).player(&player);
+    ///
+    /// // Pull 256 frames of stereo audio at a time, as an audio callback would
+    /// let mut buffer = [0.0; 512];
+    /// let mut frames = 0;
+    /// loop {
+    ///     let written = stream.fill(&mut buffer);
+    ///     frames += written;
+    ///     if written < 256 {
+    ///         break;
+    ///     }
+    /// }
+    /// assert_eq!(frames, 8000 * 4 * 200 / 1000); // 4 beats at 200ms each
+    /// assert!(stream.is_finished());
+    /// ```
+    pub fn player(&self, player: &MusicPlayer<FileOutputConfig>) -> PiecePlayer {
+        PiecePlayer {
+            channels: player.render_channels(self.clone()),
+            sample_rate: player.output_config.sample_rate,
+            position: 0,
+            block_size: DEFAULT_BLOCK_SIZE,
+        }
+    }
+}
+
+/// A rendered piece that's read a chunk of frames at a time, from [`Piece::player`].
+///
+/// A frame is one sample for every channel, so a buffer of stereo audio holds two samples per frame,
+/// interleaved as left, right, left, right, and so on.
+///
+/// As well as filling buffers of any size with [`PiecePlayer::fill`], a `PiecePlayer` is an iterator over
+/// blocks of [`PiecePlayer::block_size`] frames each.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PiecePlayer {
+    channels: Vec<Vec<f32>>,
+    sample_rate: u32,
+    /// The next frame to be written
+    position: usize,
+    /// The number of frames in each block given by the iterator
+    block_size: usize,
+}
+
+impl PiecePlayer {
+    /// Writes the next frames of the piece into `buffer`, interleaved, and returns how many frames were written.
+    ///
+    /// As many whole frames are written as fit. Once the piece ends, the rest of the buffer is filled
+    /// with silence, so fewer frames than fit means the piece has finished, and 0 means it already had.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let mut stream = Piece::from(piano(sixteenth(C4))).player(&player);
+    ///
+    /// // A sixteenth note lasts 200ms, or 1600 frames of mono audio
+    /// let mut buffer = vec![0.0; 1000];
+    /// assert_eq!(stream.fill(&mut buffer), 1000);
+    /// assert_eq!(stream.fill(&mut buffer), 600);
+    /// assert!(buffer[600..].iter().all(|&sample| sample == 0.0));
+    /// assert_eq!(stream.fill(&mut buffer), 0);
+    /// ```
+    pub fn fill(&mut self, buffer: &mut [f32]) -> usize {
+        let channels = self.channels.len().max(1);
+        let mut written = 0;
+        for (frame, samples) in buffer.chunks_mut(channels).enumerate() {
+            let index = self.position.saturating_add(frame);
+            let in_piece = index < self.total_frames() && samples.len() == channels;
+            for (channel, sample) in samples.iter_mut().enumerate() {
+                *sample = if in_piece { self.channels[channel][index] } else { 0.0 };
+            }
+            if in_piece {
+                written = frame.saturating_add(1);
+            }
+        }
+        self.position = self.position.saturating_add(written);
+        written
+    }
+
+    /// Gets the number of channels in each frame.
+    pub fn channels(&self) -> u16 {
+        u16::try_from(self.channels.len()).unwrap_or(u16::MAX)
+    }
+
+    /// Gets the sample rate of the audio, in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Gets the number of frames in the whole piece.
+    pub fn total_frames(&self) -> usize {
+        self.channels.first().map(Vec::len).unwrap_or_default()
+    }
+
+    /// Gets whether every frame of the piece has been written.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.total_frames()
+    }
+
+    /// Sets the number of frames in each block the iterator gives, which is 512 unless set.
+    ///
+    /// Larger blocks mean fewer calls for the whole piece, and smaller ones can be handed on sooner. The
+    /// samples are the same whatever the block size, only split up differently. Zero is treated as one.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    /// let stream = Piece::from(piano(sixteenth(C4))).player(&player).with_block_size(1000);
+    ///
+    /// // 1600 frames of stereo audio, in blocks of up to 1000 frames
+    /// let blocks: Vec<Vec<f32>> = stream.collect();
+    /// assert_eq!(blocks.len(), 2);
+    /// assert_eq!(blocks[0].len(), 2000);
+    /// assert_eq!(blocks[1].len(), 1200);
+    /// ```
+    pub fn with_block_size(mut self, frames: usize) -> Self {
+        self.block_size = frames.max(1);
+        self
+    }
+
+    /// Gets the number of frames in each block the iterator gives.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Starts again from the beginning of the piece.
+    pub fn rewind(&mut self) {
+        self.position = 0;
+    }
+}
+
+/// Gives the next block of [`PiecePlayer::block_size`] frames, interleaved. The last block is shorter if the
+/// piece doesn't fill it, and there are no more blocks once the piece has finished.
+impl Iterator for PiecePlayer {
+    type Item = Vec<f32>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let frames = self.block_size.min(self.total_frames().saturating_sub(self.position));
+        if frames == 0 {
+            return None;
+        }
+        let mut block = vec![0.0; frames.saturating_mul(self.channels.len().max(1))];
+        self.fill(&mut block);
+        Some(block)
+    }
+}
+
+#[test]
+fn test_small_chunks_match_full_render() {
+    use crate::prelude::*;
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    let piece = piano(quarter(C4) + eighth(A4)) * bass(dotted(quarter)(C4.octave(-1)).pan(0.5));
+    let rendered = player.render_channels(piece.clone());
+    let interleaved: Vec<f32> = (0..rendered[0].len())
+        .flat_map(|i| rendered.iter().map(move |channel| channel[i]))
+        .collect();
+
+    // Chunks of odd sizes, including one that doesn't hold a whole frame
+    let mut stream = piece.player(&player);
+    let mut pulled = Vec::new();
+    for size in [1, 7, 64, 3, 2].into_iter().cycle() {
+        let mut buffer = vec![0.0; size];
+        let frames = stream.fill(&mut buffer);
+        pulled.extend_from_slice(&buffer[..frames.saturating_mul(2)]);
+        if stream.is_finished() {
+            break;
+        }
+    }
+    assert_eq!(pulled, interleaved);
+
+    let mut buffer = [1.0; 4];
+    assert_eq!(stream.fill(&mut buffer), 0);
+    assert_eq!(buffer, [0.0; 4]);
+}
+
+#[test]
+fn test_block_sizes_give_the_same_samples() {
+    use crate::prelude::*;
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    let piece = piano(quarter(C4) + eighth(A4)).pan(-0.5) * bass(half(C4.octave(-1)));
+    let stream = piece.player(&player);
+
+    let small: Vec<Vec<f32>> = stream.clone().with_block_size(64).collect();
+    let large: Vec<Vec<f32>> = stream.clone().with_block_size(4096).collect();
+    assert!(small.iter().rev().skip(1).all(|block| block.len() == 128));
+    assert!(large.iter().rev().skip(1).all(|block| block.len() == 8192));
+    assert_eq!(small.concat(), large.concat());
+    assert_eq!(small.concat().len(), stream.total_frames() * 2);
+}
@@ -17,11 +17,24 @@
 )]
 #![allow(clippy::needless_range_loop, clippy::needless_collect, reason = "Complex audio processing code")]
 
-use std::ops::Div;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    ops::Div,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
 
+#[cfg(feature = "live-output")]
+use crate::Piece;
 use crate::{
-    play::{FileOutputConfig, Playable},
-    MusicPlayer,
+    play::{
+        mixing::{pan_to_channel_gains, widen_stereo},
+        sources::{mix_seed, seed_to_unit},
+        BitDepth, ClipMode, DitherMode, FileOutputConfig, Interpolation, LoopRegion, MixPrecision, Playable,
+        RenderCancelled, RenderStats, RoundMode,
+    },
+    MusicPlayer, NotePitch, Timbre,
 };
 
 impl MusicPlayer<FileOutputConfig> {
@@ -31,6 +44,8 @@ impl MusicPlayer<FileOutputConfig> {
     /// to a WAV file at the specified path. The audio is rendered using the
     /// player's configured sample rate, gain, and tempo.
     ///
+    /// This is a convenience wrapper around [`MusicPlayer::write_wav_to`].
+    ///
     /// # Arguments
     /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
     /// * `path` - The file path where the WAV file should be written
@@ -49,35 +64,354 @@ impl MusicPlayer<FileOutputConfig> {
     /// This function panics if the file path is unable to be created or written to.
     #[expect(private_bounds, reason = "Only internal types should be playable")]
     pub fn render_to_wav<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T, path: &str) {
+        let file = File::create(path).unwrap();
+        self.write_wav_to(piece, BufWriter::new(file)).unwrap();
+    }
+
+    /// Renders a musical piece to a WAV file, stopping early if `cancel` is set.
+    ///
+    /// This writes the same file as [`MusicPlayer::render_to_wav`], but checks `cancel` before rendering each
+    /// note and between each stage of mixing, effects, and mastering. Setting it from another thread, such as when a user presses a GUI's cancel
+    /// button, stops the render soon afterwards. The file is only created once rendering has finished, so
+    /// a cancelled render leaves no partial file behind, and doesn't touch any file already at `path`.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the WAV file should be written
+    /// * `cancel` - Set this to `true` to stop the render
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::atomic::AtomicBool;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, RenderCancelled};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let path = std::env::temp_dir().join("symphoxy_cancel_example.wav");
+    ///
+    /// // A render that's cancelled before it starts never writes anything
+    /// let cancel = AtomicBool::new(true);
+    /// let error = player.render_to_wav_cancelable(piano(whole(C4)), path.to_str().unwrap(), &cancel).unwrap_err();
+    /// assert!(error.get_ref().is_some_and(|error| error.is::<RenderCancelled>()));
+    /// assert!(!path.exists());
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error of kind [`io::ErrorKind::Interrupted`], holding a [`RenderCancelled`], if the render
+    /// was cancelled, or any error from creating or writing to the file.
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn render_to_wav_cancelable<T: Playable + Clone + Send + Sync + 'static>(
+        &self,
+        piece: T,
+        path: &str,
+        cancel: &AtomicBool,
+    ) -> io::Result<()> {
+        let cancelled = || io::Error::new(io::ErrorKind::Interrupted, RenderCancelled);
+        let samples = self.render_channels_until(piece, cancel).ok_or_else(cancelled)?;
+        if cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_samples_to(&samples, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Renders a musical piece to a WAV file, and reports statistics about the render.
+    ///
+    /// This writes the same file as [`MusicPlayer::render_to_wav`], which is useful for finding out
+    /// why a dense piece is slow to render. See [`RenderStats`] for what's measured.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `path` - The file path where the WAV file should be written
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let piece = piano(quarter(C4) + quarter(A4)) * bass(half(C4.octave(-1)));
+    ///
+    /// let path = std::env::temp_dir().join("symphoxy_stats_example.wav");
+    /// let stats = player.render_to_wav_with_stats(piece, path.to_str().unwrap()).unwrap();
+    /// assert_eq!(stats.note_count, 3);
+    /// assert_eq!(stats.peak_voices, 2);
+    /// assert!(stats.peak_amplitude <= 1.0);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be created or written to.
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn render_to_wav_with_stats<T: Playable + Clone + Send + Sync + 'static>(
+        &self,
+        piece: T,
+        path: &str,
+    ) -> io::Result<RenderStats> {
+        let start = Instant::now();
+
+        // Every pitched note, and the number playing at each time
+        let length = piece.length();
+        let mut note_count = 0usize;
+        let mut voices = vec![0usize; length];
+        for instant in 0..length {
+            for note in piece.get_notes_at_instant(instant).filter(|note| !note.is_rest()) {
+                note_count += 1;
+                let end = instant.saturating_add(note.0 .0 as usize).min(length);
+                voices[instant..end].iter_mut().for_each(|count| *count += 1);
+            }
+        }
+
+        let samples = self.render_channels(piece);
+        let peak_amplitude = samples.iter().flatten().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        self.write_samples_to(&samples, BufWriter::new(File::create(path)?))?;
+
+        Ok(RenderStats {
+            elapsed: start.elapsed(),
+            note_count,
+            peak_voices: voices.into_iter().max().unwrap_or_default(),
+            peak_amplitude,
+        })
+    }
+
+    /// Renders a musical piece as WAV data into any seekable writer.
+    ///
+    /// This is useful for writing to an in-memory buffer, or to streams other than files.
+    ///
+    /// The writer must implement `Seek`, because the WAV header stores the total size of
+    /// the audio data, which is only known once every sample has been written. The header
+    /// is written first with placeholder sizes, then patched at the end. To stream to
+    /// something that can't seek (like stdout), render into a `Cursor<Vec<u8>>` first
+    /// and copy the finished buffer.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    /// * `writer` - The destination for the WAV data, starting at offset 0
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::{Cursor, Write};
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let piece = piano(quarter(C4) + quarter(A4));
+    ///
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piece, &mut buffer).unwrap();
+    ///
+    /// // The buffer now holds a complete WAV file
+    /// let reader = hound::WavReader::new(Cursor::new(buffer.get_ref())).unwrap();
+    /// assert_eq!(reader.spec().sample_rate, 8000);
+    /// assert_eq!(reader.duration(), 8000 * 8 * 200 / 1000); // 8 beats at 200ms each
+    ///
+    /// // Which can then be sent anywhere, e.g. stdout
+    /// std::io::stdout().lock().write_all(buffer.get_ref()).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if writing to or seeking within the writer fails.
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn write_wav_to<T: Playable + Clone + Send + Sync + 'static, W: Write + Seek>(
+        &self,
+        piece: T,
+        writer: W,
+    ) -> io::Result<()> {
+        self.write_samples_to(&self.render_channels(piece), writer)
+    }
+
+    /// Writes rendered channels as WAV data, quantizing, dithering, and interleaving them as the bit depth requires
+    pub(super) fn write_samples_to<W: Write + Seek>(&self, samples: &[Vec<f32>], mut writer: W) -> io::Result<()> {
+        let channels = samples.len();
+        let total_samples = samples.first().map(Vec::len).unwrap_or_default();
+
+        // Write to WAV (interleaved)
+        let (bits_per_sample, sample_format) = match self.output_config.bit_depth {
+            // This is apparently CD quality
+            BitDepth::Int16 => (16, hound::SampleFormat::Int),
+            BitDepth::Float32 => (32, hound::SampleFormat::Float),
+        };
+        let spec = hound::WavSpec {
+            channels: channels as u16,
+            sample_rate: self.output_config.sample_rate,
+            bits_per_sample,
+            sample_format,
+        };
+
+        let start = writer.stream_position()?;
+        let mut wav_writer = hound::WavWriter::new(&mut writer, spec).map_err(hound_to_io_error)?;
+
+        for i in 0..total_samples {
+            for ch in 0..channels {
+                match self.output_config.bit_depth {
+                    BitDepth::Int16 => {
+                        let dither =
+                            dither_offset(self.output_config.dither, self.output_config.seed, i * channels + ch);
+                        let s = quantize_sample(samples[ch][i] + dither, self.output_config.clip_mode);
+                        wav_writer.write_sample(s).map_err(hound_to_io_error)?;
+                    }
+                    // Float samples are stored exactly, so there's nothing to dither or clip
+                    BitDepth::Float32 => wav_writer.write_sample(samples[ch][i]).map_err(hound_to_io_error)?,
+                }
+            }
+        }
+
+        wav_writer.finalize().map_err(hound_to_io_error)?;
+
+        // hound writes WAVE_FORMAT_EXTENSIBLE for more than two channels, but always with the default channel mask
+        match self.output_config.channel_layout {
+            Some(layout) if channels > 2 && usize::from(layout.channels()) == channels => {
+                let end = writer.stream_position()?;
+                writer.seek(SeekFrom::Start(start + CHANNEL_MASK_OFFSET))?;
+                writer.write_all(&layout.channel_mask().to_le_bytes())?;
+                writer.seek(SeekFrom::Start(end))?;
+            }
+            _ => {}
+        }
+
+        match self.loop_samples(total_samples) {
+            Some((loop_start, loop_end)) => {
+                write_smpl_chunk(&mut writer, start, self.sample_period_ns(), loop_start, loop_end)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Gets the first and last sample of the loop region, if there is one and it isn't empty
+    fn loop_samples(&self, total_samples: usize) -> Option<(usize, usize)> {
+        let (loop_start, loop_end) = match self.output_config.loop_region.as_ref()? {
+            LoopRegion::WholePiece => (0, total_samples),
+            LoopRegion::TimeUnits(units) => (self.sample_at_time(units.start), self.sample_at_time(units.end)),
+        };
+        // The loop ends on its last sample, rather than the one after it
+        let loop_end = loop_end.min(total_samples).checked_sub(1)?;
+        (loop_start <= loop_end).then_some((loop_start, loop_end))
+    }
+
+    /// Gets the length of each sample in nanoseconds, as recorded in the `smpl` chunk
+    fn sample_period_ns(&self) -> u32 {
+        1_000_000_000 / self.output_config.sample_rate.max(1)
+    }
+
+    /// Renders a musical piece into one buffer of samples per output channel, without writing a WAV file.
+    ///
+    /// This is useful for sending the audio on to other processing, or to a multichannel audio workstation.
+    /// The samples are the same ones [`MusicPlayer::write_wav_to`] writes, before they're converted to 16 bit
+    /// integers and interleaved: every buffer has the same length, and samples are normally between -1.0 and 1.0.
+    ///
+    /// # Arguments
+    /// * `piece` - Any playable musical content (Note, Chord, Line, Piece, etc.)
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    /// let piece = piano(quarter(C4) + quarter(A4)).pan(-0.5) * bass(half(C4.octave(-1))).pan(0.5);
+    ///
+    /// let channels = player.render_channels(piece.clone());
+    /// assert_eq!(channels.len(), 2);
+    /// assert_eq!(channels[0].len(), 8000 * 8 * 200 / 1000);
+    ///
+    /// // Interleaving the channels gives the samples in the WAV file
+    /// let mut buffer = Cursor::new(Vec::new());
+    /// player.write_wav_to(piece, &mut buffer).unwrap();
+    /// let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+    /// let written: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+    ///
+    /// let interleaved: Vec<i16> = (0..channels[0].len())
+    ///     .flat_map(|i| channels.iter().map(move |channel| channel[i]))
+    ///     .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+    ///     .collect();
+    /// assert_eq!(interleaved, written);
+    /// ```
+    #[expect(private_bounds, reason = "Only internal types should be playable")]
+    pub fn render_channels<T: Playable + Clone + Send + Sync + 'static>(&self, piece: T) -> Vec<Vec<f32>> {
+        // Nothing else can see this flag, so the render is never cancelled
+        self.render_channels_until(piece, &AtomicBool::new(false))
+            .unwrap_or_default()
+    }
+
+    /// Renders the piece's channels like [`MusicPlayer::render_channels`], or returns `None` as soon as `cancel` is set
+    fn render_channels_until<T: Playable + Clone + Send + Sync + 'static>(
+        &self,
+        piece: T,
+        cancel: &AtomicBool,
+    ) -> Option<Vec<Vec<f32>>> {
+        let mut samples = self.mix_channels_until(piece, cancel, None)?;
+        self.master_until(&mut samples, cancel)?;
+        if self.output_config.trim_silence {
+            trim_silent_edges(&mut samples);
+        }
+        Some(samples)
+    }
+
+    /// Mixes the piece's notes into channels and applies the player's effects, but doesn't normalize them.
+    ///
+    /// With `only`, notes of every other timbre are left silent, but the channel count and random choices are
+    /// the same as for the whole piece, so the mixes of each timbre add up to the mix of everything.
+    pub(super) fn mix_channels_until<T: Playable + Clone + Send + Sync + 'static>(
+        &self,
+        piece: T,
+        cancel: &AtomicBool,
+        only: Option<Timbre>,
+    ) -> Option<Vec<Vec<f32>>> {
         let FileOutputConfig {
-            output_gain,
             sample_rate,
+            channels,
+            pitch_shift,
+            interpolation,
+            seed,
+            stereo_width,
+            release_overlap,
+            declick_ms,
+            pan_law,
+            mix_precision,
+            ..
         } = self.output_config;
 
         let beat_duration_ms = self.beat_duration_ms();
+        let tuning_ratio = self.tuning_ratio();
+        let overlap_samples = (release_overlap.as_secs_f64() * f64::from(sample_rate)).round() as usize;
+        let declick_samples = (f64::from(declick_ms.max(0.0)) * f64::from(sample_rate) / 1000.0).round() as usize;
+        // The tempo counts sixteenth notes, and LFOs count beats of four of them
+        let samples_per_beat = f64::from(sample_rate) * 240.0 / f64::from(self.tempo_bpm.max(1));
         let length = piece.length();
 
-        // Compute total duration in ms
-        let total_ms = (length as u64).saturating_mul(beat_duration_ms);
-
-        let total_samples: usize = (sample_rate as u64)
-            .saturating_mul(total_ms)
-            .div(1000)
-            .try_into()
-            .unwrap_or(usize::MAX);
+        let total_samples = self.sample_at_time(length);
+        // Checked between every stage and every note, so a cancelled render stops within one of them
+        let cancelled = || cancel.load(Ordering::Relaxed);
 
-        // Step 1: Find max channel count
-        let mut max_channels = 1;
+        // Step 1: Find max channel count, unless the user asked for a specific one
+        let mut max_channels = channels.map_or(1, usize::from);
 
         // This could be more efficient if you made a Piece::get_all_notes() method,
         // but creating wav files doesn't take eons at the moment, so this is fine.
-        for instant in 0..length {
+        for instant in (0..length).filter(|_| channels.is_none()) {
             let notes: Vec<_> = piece.get_notes_at_instant(instant).collect();
             for note in notes {
-                if let crate::note::NoteKind::Pitched { pitch, timbre, volume } = note.1 {
+                if cancelled() {
+                    return None;
+                }
+                if let crate::note::NoteKind::Pitched {
+                    pitch, timbre, volume, ..
+                } = note.1
+                {
                     let duration_ms = (note.0 .0 as u64).saturating_mul(beat_duration_ms);
-                    let frequency = pitch.0;
-                    let src = super::sources::get_source(duration_ms, frequency, timbre, volume);
+                    let frequency = pitch.0 * tuning_ratio;
+                    let src = super::sources::get_source(
+                        duration_ms,
+                        frequency,
+                        timbre,
+                        volume,
+                        seed,
+                        self.envelope_curves,
+                        self.oscillator_phase,
+                    );
                     let native_channels = src.channels() as usize;
                     if native_channels > max_channels {
                         max_channels = native_channels;
@@ -87,27 +421,60 @@ impl MusicPlayer<FileOutputConfig> {
         }
 
         // Allocate output buffers
-        let mut samples: Vec<Vec<f32>> = vec![vec![0.0; total_samples]; max_channels];
+        let mut mix = MixBuffer::new(mix_precision, max_channels, total_samples);
 
         // Step 2: Render and mix
         for instant in 0..length {
             let notes: Vec<_> = piece.get_notes_at_instant(instant).collect();
-            let start_ms = (instant as u64).saturating_mul(beat_duration_ms);
-            for note in notes {
+            let start_idx = self.sample_at_time(instant);
+            for (index, note) in notes.into_iter().enumerate() {
+                if cancelled() {
+                    return None;
+                }
                 match note.1 {
-                    crate::note::NoteKind::Pitched { pitch, timbre, volume } => {
-                        let duration_ms = (note.0 .0 as u64).saturating_mul(beat_duration_ms);
-                        let frequency = pitch.0;
-                        let src = super::sources::get_source(duration_ms, frequency, timbre, volume);
+                    crate::note::NoteKind::Pitched {
+                        pitch,
+                        timbre,
+                        volume,
+                        pan,
+                        probability,
+                        nudge_ms,
+                        glide,
+                        lfo,
+                    } => {
+                        // Give every note its own seed, so that simultaneous noise notes don't cancel out or double up
+                        let note_seed = mix_seed(mix_seed(seed, instant as u64), index as u64);
+                        if probability < 1.0 && seed_to_unit(mix_seed(note_seed, 1)) >= probability {
+                            continue;
+                        }
+                        if only.is_some_and(|only| only != timbre) {
+                            continue;
+                        }
+
+                        let end_idx = self.sample_at_time(instant.saturating_add(note.0 .0 as usize));
+                        let note_samples = end_idx.saturating_sub(start_idx).saturating_add(overlap_samples);
+                        // Nudging moves the note without changing its length, but it can't start before the piece
+                        let nudge_samples = (f64::from(nudge_ms) * f64::from(sample_rate) / 1000.0).round() as i64;
+                        let note_start = (start_idx as i64).saturating_add(nudge_samples).max(0) as usize;
+                        // Long enough to cover every sample of the note
+                        let duration_ms = (note_samples as u64)
+                            .saturating_mul(1000)
+                            .div_ceil(u64::from(sample_rate.max(1)));
+                        let frequency = pitch.0 * tuning_ratio;
+                        let src = super::sources::with_glide(duration_ms, pitch, glide, |duration_ms| {
+                            super::sources::get_source(
+                                duration_ms,
+                                frequency,
+                                timbre,
+                                volume,
+                                note_seed,
+                                self.envelope_curves,
+                                self.oscillator_phase,
+                            )
+                        });
                         let native_sample_rate = src.sample_rate();
                         let native_channels = src.channels() as usize;
 
-                        let note_samples = (sample_rate as u64)
-                            .saturating_mul(duration_ms)
-                            .div(1000)
-                            .try_into()
-                            .unwrap_or(usize::MAX);
-
                         let native_samples = (native_sample_rate as u64)
                             .saturating_mul(duration_ms)
                             .div(1000)
@@ -124,48 +491,49 @@ impl MusicPlayer<FileOutputConfig> {
 
                         // For each input channel, determine which output channel(s) to map to
                         for in_ch in 0..native_channels {
-                            // Map input channel to output channel(s)
+                            // Map input channel to output channel(s), along with the gain for each
                             let out_ch = if native_channels == 1 {
-                                // Mono: spread to all output channels
-                                (0..max_channels).collect::<Vec<_>>()
+                                match pan {
+                                    // Panned mono: place between the two nearest output channels
+                                    Some(pan) => pan_to_channel_gains(pan, max_channels, pan_law),
+                                    // Mono: spread to all output channels.
+                                    // Divide by number of output channels to avoid boosting volume
+                                    None => (0..max_channels).map(|ch| (ch, 1.0 / max_channels as f32)).collect(),
+                                }
                             } else {
                                 // N-channel: map to proportional output channel
                                 let idx = ((in_ch as f32) * (max_channels as f32 - 1.0)
                                     / (native_channels as f32 - 1.0))
                                     .round() as usize;
-                                vec![idx]
+                                vec![(idx, 1.0)]
                             };
-                            let buf = if sample_rate != native_sample_rate {
+                            let mut buf = if sample_rate != native_sample_rate {
                                 // If you don't resample, the source will play slightly too fast / slow, causing pitch issues
                                 resample_to_target_rate(
                                     chans[in_ch].clone().into_iter(),
                                     native_sample_rate,
                                     sample_rate,
                                     note_samples,
+                                    interpolation,
                                 )
                             } else {
                                 chans[in_ch].clone()
                             };
 
+                            if let Some(lfo) = lfo {
+                                super::modulation::modulate(&mut buf, lfo, note_start, samples_per_beat, sample_rate);
+                            }
+
+                            // Ramp the note in and out, using no more than a quarter of it for each ramp
+                            let written = buf.len().min(note_samples);
+                            let ramp = declick_samples.min(written / 4);
+
                             // Append all the samples to the output channels
-                            let start_idx = (sample_rate as u64)
-                                .saturating_mul(start_ms)
-                                .div(1000)
-                                .try_into()
-                                .unwrap_or(usize::MAX);
-
-                            for (i, &s) in buf.iter().enumerate() {
-                                if let Some(idx) = start_idx.checked_add(i) {
-                                    for &ch in &out_ch {
-                                        if idx < samples[ch].len() {
-                                            // For mono, divide by number of output channels to avoid boosting volume
-                                            let val = if native_channels == 1 {
-                                                s / max_channels as f32
-                                            } else {
-                                                s
-                                            };
-                                            samples[ch][idx] += val;
-                                        }
+                            for (i, &s) in buf.iter().take(note_samples).enumerate() {
+                                let s = s * declick_gain(i, written, ramp);
+                                if let Some(idx) = note_start.checked_add(i) {
+                                    for &(ch, gain) in &out_ch {
+                                        mix.add(ch, idx, s * gain);
                                     }
                                 }
                             }
@@ -176,8 +544,57 @@ impl MusicPlayer<FileOutputConfig> {
             }
         }
 
+        let mut samples = mix.into_samples();
+
+        // Shift the pitch of the mixed audio, if requested
+        if pitch_shift != 0.0 {
+            let ratio = 2.0f32.powf(pitch_shift / 12.0);
+            for channel in &mut samples {
+                if cancelled() {
+                    return None;
+                }
+                *channel = pitch_shift_buffer(channel, ratio, sample_rate);
+            }
+        }
+
+        for channel in &mut samples {
+            if !self.output_config.effects.process_until(channel, sample_rate, cancel) {
+                return None;
+            }
+        }
+
+        if cancelled() {
+            return None;
+        }
+        if max_channels == 2 && stereo_width > 0.0 {
+            widen_stereo(&mut samples, stereo_width, sample_rate);
+        }
+
+        Some(samples)
+    }
+
+    /// Brings mixed channels to their final level: normalizing them, then applying any loudness target and limiter
+    pub(super) fn master(&self, samples: &mut [Vec<f32>]) {
+        // Nothing else can see this flag, so every stage runs
+        self.master_until(samples, &AtomicBool::new(false));
+    }
+
+    /// Masters the channels like [`MusicPlayer::master`], or returns `None` as soon as `cancel` is set
+    fn master_until(&self, samples: &mut [Vec<f32>], cancel: &AtomicBool) -> Option<()> {
+        let cancelled = || cancel.load(Ordering::Relaxed);
+        let FileOutputConfig {
+            output_gain,
+            sample_rate,
+            loudness_target,
+            limiter_threshold,
+            ..
+        } = self.output_config;
+
         // Normalize all channels
-        for ch in 0..max_channels {
+        for ch in 0..samples.len() {
+            if cancelled() {
+                return None;
+            }
             // It seems like this normalizes all channels separately, which seems strange but I trust the process.
             let max = samples[ch].iter().cloned().fold(0.0_f32, |a, b| a.abs().max(b.abs()));
             if max > 0.0 {
@@ -187,31 +604,983 @@ impl MusicPlayer<FileOutputConfig> {
             }
         }
 
-        // Write to WAV (interleaved)
-        let spec = hound::WavSpec {
-            channels: max_channels as u16,
+        if cancelled() {
+            return None;
+        }
+        if let Some(target) = loudness_target {
+            super::loudness::normalize_loudness(samples, sample_rate, target);
+        }
+
+        if cancelled() {
+            return None;
+        }
+        if let Some(threshold) = limiter_threshold {
+            super::loudness::limit(samples, sample_rate, 10.0f32.powf(threshold / 20.0));
+        }
+        Some(())
+    }
+}
+
+#[cfg(feature = "live-output")]
+impl Piece {
+    /// Renders this piece to a WAV file with `player`, and then plays the rendered audio through `output`.
+    ///
+    /// The piece is only rendered once: the samples that are written to the file are the same ones
+    /// that are played, so what's heard is exactly what was saved. This blocks until playback has finished,
+    /// or until live playback is stopped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let (_stream, handle) = rodio::OutputStream::try_default().unwrap();
+    /// let player = MusicPlayer::new_file(300, 1.0, 44100);
+    /// let piece = Piece::from(piano(quarter(C4) + quarter(A4)));
+    /// piece.render_and_play(&player, "output.wav", &handle).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be created or written to, in which case nothing is played, or if
+    /// `output` can't open a stream to play the rendered audio.
+    pub fn render_and_play(
+        &self,
+        player: &MusicPlayer<FileOutputConfig>,
+        path: &str,
+        output: &dyn super::AudioOutput,
+    ) -> io::Result<()> {
+        let samples = player.render_channels(self.clone());
+        let mut writer = BufWriter::new(File::create(path)?);
+        player.write_samples_to(&samples, &mut writer)?;
+        writer.flush()?;
+
+        play_samples(&samples, player.output_config.sample_rate, output)
+    }
+}
+
+/// Plays rendered channels through `output`, blocking until they've finished or live playback is stopped
+#[cfg(feature = "live-output")]
+fn play_samples(samples: &[Vec<f32>], sample_rate: u32, output: &dyn super::AudioOutput) -> io::Result<()> {
+    let channels = samples.len();
+    let total_samples = samples.first().map(Vec::len).unwrap_or_default();
+    let interleaved: Vec<f32> = (0..total_samples)
+        .flat_map(|i| samples.iter().map(move |channel| channel[i]))
+        .collect();
+
+    let mut stream = output.open_stream(channels as u16, sample_rate)?;
+    for block in interleaved.chunks((super::LIVE_BLOCK_FRAMES * channels).max(1)) {
+        stream.push_samples(block);
+    }
+
+    let duration = std::time::Duration::from_secs_f64(total_samples as f64 / f64::from(sample_rate.max(1)));
+    if super::sleep_unless_stopped(duration) {
+        stream.wait_until_played();
+    } else {
+        stream.stop();
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "live-output")]
+fn test_render_and_play_share_one_render() {
+    use crate::play::{AudioOutput, AudioStream};
+    use std::sync::{Arc, Mutex};
+
+    type Pushed = Arc<Mutex<Vec<(u16, u32, Vec<f32>)>>>;
+
+    /// Stores every stream and the samples pushed to it, instead of playing them
+    struct MockOutput(Pushed);
+
+    impl AudioOutput for MockOutput {
+        fn open_stream(&self, channels: u16, sample_rate: u32) -> io::Result<Box<dyn AudioStream>> {
+            self.0.lock().unwrap().push((channels, sample_rate, Vec::new()));
+            Ok(Box::new(MockStream(self.0.clone())))
+        }
+    }
+
+    struct MockStream(Pushed);
+
+    impl AudioStream for MockStream {
+        fn push_samples(&mut self, samples: &[f32]) {
+            self.0.lock().unwrap().last_mut().unwrap().2.extend_from_slice(samples);
+        }
+    }
+
+    // Noise is random, so rendering twice with different seeds would give different samples
+    let piece = crate::prelude::noise(crate::prelude::quarter(crate::C4))
+        * crate::prelude::piano(crate::prelude::quarter(crate::A4)).pan(0.5);
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2);
+    let path = std::env::temp_dir().join("symphoxy_render_and_play_test.wav");
+    let path = path.to_str().unwrap();
+
+    let pushed = Pushed::default();
+    piece
+        .render_and_play(&player, path, &MockOutput(pushed.clone()))
+        .unwrap();
+
+    // The file holds the same samples that were played, after being converted to 16 bits
+    let written: Vec<i16> = hound::WavReader::open(path)
+        .unwrap()
+        .samples::<i16>()
+        .map(Result::unwrap)
+        .collect();
+    std::fs::remove_file(path).unwrap();
+
+    let pushed = pushed.lock().unwrap();
+    assert_eq!(pushed.len(), 1);
+    let (channels, sample_rate, played) = &pushed[0];
+    assert_eq!((*channels, *sample_rate), (2, 8000));
+    assert_eq!(played.len(), 2 * 6400);
+    let quantized: Vec<i16> = played.iter().map(|&s| quantize_sample(s, ClipMode::Hard)).collect();
+    assert_eq!(quantized, written);
+}
+
+impl Timbre {
+    /// Renders a single note of this timbre on its own, such as for previewing the timbre or building a sample library.
+    ///
+    /// The note is rendered at the player's sample rate, reference pitch, envelope curves and seed, mixed down
+    /// to one channel, and normalized to the player's output gain. The rest of the player's processing,
+    /// such as pitch shifting and loudness normalization, is only applied to whole pieces.
+    ///
+    /// The result always has exactly as many samples as the duration at the player's sample rate.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use symphoxy::{MusicPlayer, Timbre, C4};
+    ///
+    /// let player = MusicPlayer::new_file(120, 0.8, 8000);
+    /// let samples = Timbre::Piano.render_note(C4, Duration::from_millis(500), &player);
+    ///
+    /// assert_eq!(samples.len(), 4000);
+    /// let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    /// assert!((peak - 0.8).abs() < 1e-6);
+    /// ```
+    pub fn render_note(
+        self,
+        pitch: NotePitch,
+        duration: std::time::Duration,
+        player: &MusicPlayer<FileOutputConfig>,
+    ) -> Vec<f32> {
+        let FileOutputConfig {
+            output_gain,
             sample_rate,
-            // This is apparently CD quality
-            bits_per_sample: 16,
-            sample_format: hound::SampleFormat::Int,
+            interpolation,
+            seed,
+            ..
+        } = player.output_config;
+
+        let note_samples = (duration.as_secs_f64() * f64::from(sample_rate)).round() as usize;
+        // Long enough to cover every sample of the note
+        let duration_ms = (note_samples as u64)
+            .saturating_mul(1000)
+            .div_ceil(u64::from(sample_rate.max(1)));
+        let src = super::sources::get_source(
+            duration_ms,
+            pitch.0 * player.tuning_ratio(),
+            self,
+            1.0,
+            seed,
+            player.envelope_curves,
+            player.oscillator_phase,
+        );
+        let native_sample_rate = src.sample_rate();
+        let native_channels = usize::from(src.channels()).max(1);
+        let native_samples = (u64::from(native_sample_rate).saturating_mul(duration_ms) / 1000)
+            .try_into()
+            .unwrap_or(usize::MAX);
+
+        // Separate the interleaved channels, then mix them down to one
+        let mut chans: Vec<Vec<f32>> = vec![vec![]; native_channels];
+        for (i, s) in src.take(native_samples.saturating_mul(native_channels)).enumerate() {
+            chans[i % native_channels].push(s);
+        }
+        let mut mono = vec![0.0; note_samples];
+        for chan in chans {
+            let buf = resample_to_target_rate(
+                chan.into_iter(),
+                native_sample_rate,
+                sample_rate,
+                note_samples,
+                interpolation,
+            );
+            for (sum, s) in mono.iter_mut().zip(buf) {
+                *sum += s / native_channels as f32;
+            }
+        }
+
+        let max = mono.iter().fold(0.0_f32, |a, b| a.max(b.abs()));
+        if max > 0.0 {
+            for s in &mut mono {
+                *s = (*s / max) * output_gain;
+            }
+        }
+        mono
+    }
+}
+
+#[test]
+fn test_render_note_length_and_pitch() {
+    use std::time::Duration;
+
+    let energy_at = |samples: &[f32], frequency: f32| super::spectrum::energy_at(samples, frequency, 8000);
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    for timbre in [Timbre::Sine, Timbre::Piano, Timbre::Bass] {
+        for millis in [1, 250, 1234] {
+            let samples = timbre.render_note(crate::C4, Duration::from_millis(millis), &player);
+            assert_eq!(samples.len(), millis as usize * 8);
+        }
+
+        // The note's own pitch is much stronger than the pitches a semitone either side
+        let samples = timbre.render_note(crate::A4, Duration::from_millis(500), &player);
+        let energy = energy_at(&samples, crate::A4.0);
+        for other in [crate::A4.0 * 0.943_874_3, crate::A4.0 * 1.059_463_1] {
+            assert!(energy > 10.0 * energy_at(&samples, other), "{timbre:?}");
+        }
+    }
+
+    // The reference pitch moves the note along with it
+    let player = player.with_reference_pitch(NotePitch::new(432.0));
+    let samples = Timbre::Sine.render_note(crate::A4, Duration::from_millis(500), &player);
+    assert!(energy_at(&samples, 432.0) > 10.0 * energy_at(&samples, crate::A4.0));
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Gets the index of the sample that the given time unit starts at, rounded according to the player's [`RoundMode`].
+    pub(crate) fn sample_at_time(&self, time: usize) -> usize {
+        let tempo = u64::from(self.tempo_bpm);
+        if tempo == 0 {
+            return if time == 0 { 0 } else { usize::MAX };
+        }
+
+        // The exact number of samples per time unit is (sample_rate * 60) / tempo
+        let samples_per_minute = u64::from(self.output_config.sample_rate).saturating_mul(60);
+        let time = time as u64;
+        let sample = match self.output_config.round_mode {
+            RoundMode::Milliseconds => {
+                let ms = time.saturating_mul(self.beat_duration_ms());
+                u64::from(self.output_config.sample_rate).saturating_mul(ms) / 1000
+            }
+            RoundMode::Floor => (samples_per_minute / tempo).saturating_mul(time),
+            RoundMode::Round => ((samples_per_minute + tempo / 2) / tempo).saturating_mul(time),
+            RoundMode::Ceil => samples_per_minute.div_ceil(tempo).saturating_mul(time),
+            // Rounding the position, rather than each unit, spreads the error out instead of accumulating it
+            RoundMode::Compensated => (samples_per_minute.saturating_mul(time) + tempo / 2) / tempo,
         };
+        sample.try_into().unwrap_or(usize::MAX)
+    }
+}
 
-        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+#[test]
+fn test_round_mode_drift() {
+    // At this tempo, a time unit is 44100 * 60 / 320 = 8268.75 samples long
+    let drift_after = |round_mode, notes: usize| {
+        let player = MusicPlayer::new_file(320, 1.0, 44100).with_round_mode(round_mode);
+        player.sample_at_time(notes) as f64 - notes as f64 * 8268.75
+    };
 
-        // Convert to 16 bits per sample and int sample format
-        for i in 0..total_samples {
-            for ch in 0..max_channels {
-                #[expect(clippy::cast_possible_truncation, reason = "It's clamped, so it should be safe")]
-                let s: i16 = (samples[ch][i] * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
-                writer.write_sample(s).unwrap();
+    // Rounding every note the same way accumulates drift across the piece, by 187 ms rather than 187.5 at first
+    assert_eq!(drift_after(RoundMode::Milliseconds, 1000), -22050.0);
+    assert_eq!(drift_after(RoundMode::Floor, 1000), -750.0);
+    assert_eq!(drift_after(RoundMode::Round, 1000), 250.0);
+    assert_eq!(drift_after(RoundMode::Ceil, 1000), 250.0);
+
+    // Compensating keeps every note within a sample of where it should be
+    assert!((0..=1000).all(|notes| drift_after(RoundMode::Compensated, notes).abs() < 1.0));
+
+    // And none of them change the length of evenly divisible time units
+    for round_mode in [
+        RoundMode::Milliseconds,
+        RoundMode::Floor,
+        RoundMode::Round,
+        RoundMode::Ceil,
+        RoundMode::Compensated,
+    ] {
+        let player = MusicPlayer::new_file(300, 1.0, 44100).with_round_mode(round_mode);
+        assert_eq!(player.sample_at_time(1000), 1000 * 8820);
+    }
+}
+
+#[test]
+fn test_render_stats_count_every_note() {
+    use crate::prelude::*;
+
+    let piece =
+        (piano(quarter(C4) + quarter(REST) + eighth(A4)) * 2) * bass(whole(C4) + half(A4)) * drums(sixteenth(C4) * 8);
+    let path = std::env::temp_dir().join("symphoxy_test_render_stats.wav");
+    let stats = MusicPlayer::new_file(300, 1.0, 8000)
+        .render_to_wav_with_stats(piece.clone(), path.to_str().unwrap())
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(stats.note_count, piece.notes().count());
+    assert_eq!(stats.peak_voices, 3);
+    assert!(stats.peak_amplitude > 0.0 && stats.peak_amplitude <= 1.0);
+}
+
+#[test]
+fn test_maybe_plays_notes_with_their_probability() {
+    let piece = crate::prelude::maybe(crate::prelude::piano(crate::prelude::sixteenth(crate::C4)), 0.3);
+    let renders = 500;
+    let played = (0..renders)
+        .filter(|&seed| {
+            let player = MusicPlayer::new_file(300, 1.0, 1000).with_seed(seed);
+            player.render_channels(piece.clone())[0].iter().any(|&s| s != 0.0)
+        })
+        .count();
+
+    let frequency = played as f32 / renders as f32;
+    assert!((frequency - 0.3).abs() < 0.05, "played {frequency} of the time");
+
+    // The extremes are exact
+    for (probability, expected) in [(0.0, false), (1.0, true)] {
+        let piece = crate::prelude::maybe(crate::prelude::piano(crate::prelude::sixteenth(crate::C4)), probability);
+        let player = MusicPlayer::new_file(300, 1.0, 1000).with_seed(7);
+        assert_eq!(player.render_channels(piece)[0].iter().any(|&s| s != 0.0), expected);
+    }
+}
+
+#[test]
+fn test_nudge_moves_rendered_onsets() {
+    use crate::prelude::{nudge, quarter, sine};
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    let onset = |line: crate::Line| {
+        let samples = &player.render_channels(line)[0];
+        samples.iter().position(|&sample| sample != 0.0).unwrap()
+    };
+
+    // Each quarter note is 6400 samples, and each millisecond is 8 samples
+    let rest = quarter(crate::REST);
+    let plain = onset(rest + sine(quarter(crate::A4)));
+    assert!((6400..6410).contains(&plain));
+    assert_eq!(onset(rest + nudge(sine(quarter(crate::A4)), 50.0)), plain + 400);
+    assert_eq!(onset(rest + nudge(sine(quarter(crate::A4)), -50.0)), plain - 400);
+    assert_eq!(onset(rest + sine(quarter(crate::A4)).nudge(12.5)), plain + 100);
+
+    // Notes can't be nudged before the start of the piece
+    let first = onset(sine(quarter(crate::A4)) + rest);
+    assert_eq!(onset(nudge(sine(quarter(crate::A4)), -100.0) + rest), first);
+}
+
+#[test]
+fn test_fermata_holds_the_note_and_delays_what_follows() {
+    use crate::prelude::{fermata, half, quarter, sine};
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    let held = player.render_channels(fermata(sine(quarter(crate::A4)), 2.0) + sine(quarter(crate::C4)));
+    let written = player.render_channels(sine(half(crate::A4)) + sine(quarter(crate::C4)));
+
+    // The held quarter note renders exactly like a half note, with the next note 6400 samples later
+    assert_eq!(held[0].len(), 3 * 6400);
+    assert_eq!(held, written);
+    let plain = player.render_channels(sine(quarter(crate::A4)) + sine(quarter(crate::C4)));
+    assert_eq!(held[0][12800..], plain[0][6400..]);
+}
+
+#[test]
+fn test_longer_declick_ramps_more_samples() {
+    use crate::prelude::{electric_guitar, quarter, sixteenth, Line};
+
+    // Electric guitar notes start at full level, so every sample of a ramp is quieter than without one
+    let onset_ramp = |line: Line, ms: f32| {
+        let plain = MusicPlayer::new_file(300, 1.0, 8000)
+            .without_limiter()
+            .render_channels(line.clone());
+        let declicked = MusicPlayer::new_file(300, 1.0, 8000)
+            .without_limiter()
+            .with_declick(ms)
+            .render_channels(line);
+        // Normalize the second render to the first, since its peak may differ slightly
+        let scale = plain[0][800] / declicked[0][800];
+        (0..800)
+            .filter(|&i| (declicked[0][i] * scale).abs() < plain[0][i].abs() - 1e-6)
+            .count()
+    };
+    let note = || electric_guitar(Line::from(quarter(crate::A4)));
+
+    assert_eq!(onset_ramp(note(), 0.0), 0);
+    let short = onset_ramp(note(), 2.0);
+    let long = onset_ramp(note(), 10.0);
+    assert!((8..=16).contains(&short), "{short} samples ramped over 2 ms");
+    assert!((60..=80).contains(&long), "{long} samples ramped over 10 ms");
+
+    // A ramp too long for the note is cut to a quarter of it: the note is 1600 samples, so 400 at each end
+    let ramped = onset_ramp(electric_guitar(Line::from(sixteenth(crate::A4))), 1000.0);
+    assert!(
+        (380..=400).contains(&ramped),
+        "{ramped} samples ramped in a sixteenth note"
+    );
+}
+
+#[test]
+fn test_cancelling_stops_a_render() {
+    use crate::prelude::{piano, whole};
+    use std::{sync::Arc, thread, time::Duration};
+
+    let player = MusicPlayer::new_file(300, 1.0, 44100);
+    let path = std::env::temp_dir().join("symphoxy_cancel_test.wav");
+    let _ = std::fs::remove_file(&path);
+
+    // Long enough that it's still rendering when the flag is set
+    let cancel = Arc::new(AtomicBool::new(false));
+    let canceller = {
+        let cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            cancel.store(true, Ordering::Relaxed);
+        })
+    };
+    let start = Instant::now();
+    let result = player.render_to_wav_cancelable(piano(whole(crate::C4)) * 2000, path.to_str().unwrap(), &cancel);
+    canceller.join().unwrap();
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::Interrupted);
+    assert!(error.get_ref().is_some_and(|error| error.is::<RenderCancelled>()));
+    // The render stops within a note of the flag being set, long before the 2000 notes could all be mixed
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(50));
+    assert!(
+        elapsed < Duration::from_millis(1000),
+        "cancelled render took {elapsed:?}"
+    );
+    assert!(!path.exists());
+
+    // Without cancelling, the same file as `render_to_wav` is written
+    player
+        .render_to_wav_cancelable(piano(whole(crate::C4)), path.to_str().unwrap(), &AtomicBool::new(false))
+        .unwrap();
+    let written = std::fs::read(&path).unwrap();
+    let mut expected = io::Cursor::new(Vec::new());
+    player.write_wav_to(piano(whole(crate::C4)), &mut expected).unwrap();
+    assert_eq!(written, expected.into_inner());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_offset_delays_rendered_notes() {
+    use crate::prelude::{quarter, sine, NoteLength};
+
+    let player = MusicPlayer::new_file(300, 1.0, 8000);
+    let melody = sine(quarter(crate::A4)) + sine(quarter(crate::C4));
+    let alone = &player.render_channels(melody.clone())[0];
+
+    // Two units are 3200 samples, which are silent before the line starts
+    let delayed = &player.render_channels(melody.clone().offset(NoteLength(2)))[0];
+    assert!(delayed[..3200].iter().all(|&sample| sample == 0.0));
+    assert_eq!(delayed[3200..], alone[..]);
+
+    // Played alongside a voice that isn't offset, the piece ends that much later too
+    let canon = player.render_channels(melody.clone() * melody.offset(NoteLength(2)));
+    assert_eq!(canon[0].len(), alone.len() + 3200);
+}
+
+#[test]
+#[expect(clippy::cast_precision_loss, reason = "Sample indices are small")]
+fn test_portamento_slides_smoothly_between_notes() {
+    use crate::prelude::{portamento, quarter, sine, Tet12};
+    use std::time::Duration;
+
+    let (c4, e4) = (crate::C4, crate::C4.semitone(4));
+    let player = MusicPlayer::new_file(300, 1.0, 48000);
+    // The frequency over 20ms from `start_ms` into the second note, from the time between upward zero crossings
+    let frequency_at = |samples: &[f32], start_ms: usize| {
+        let start = 38400 + start_ms * 48;
+        let crossings: Vec<f32> = (start..start + 960)
+            .filter(|&i| samples[i - 1] < 0.0 && samples[i] >= 0.0)
+            .map(|i| i as f32 - samples[i] / (samples[i] - samples[i - 1]))
+            .collect();
+        let (first, last) = (crossings[0], crossings[crossings.len() - 1]);
+        (crossings.len() - 1) as f32 * 48000.0 / (last - first)
+    };
+
+    let line = sine(quarter(c4) + quarter(e4));
+    let stepped = &player.render_channels(portamento(line.clone(), Duration::ZERO))[0];
+    let glided = &player.render_channels(portamento(line, Duration::from_millis(200)))[0];
+
+    // Without a glide, the second note starts on its own pitch
+    assert!((frequency_at(stepped, 10) / e4.0 - 1.0).abs() < 0.01);
+
+    // With one, it starts near the first note's pitch, and rises steadily to its own
+    let rising: Vec<f32> = [10, 50, 90, 130, 170].map(|ms| frequency_at(glided, ms)).to_vec();
+    assert!(rising[0] < c4.0 * 1.05);
+    for pair in rising.windows(2) {
+        assert!(pair[0] < pair[1] && pair[1] - pair[0] < 20.0, "{rising:?}");
+    }
+    assert!((frequency_at(glided, 300) / e4.0 - 1.0).abs() < 0.01);
+
+    // The note still lasts as long as it did
+    let end = |samples: &[f32]| samples.iter().rposition(|&sample| sample != 0.0).unwrap();
+    assert!(end(glided).abs_diff(end(stepped)) < 48 * 5);
+}
+
+#[test]
+fn test_release_overlap_blends_into_the_next_note() {
+    use crate::prelude::{quarter, sine};
+    use std::time::Duration;
+
+    let energy_at = |samples: &[f32], frequency: f32| super::spectrum::energy_at(samples, frequency, 8000);
+
+    // Each quarter note is 6400 samples, so the A4 starts at sample 6400
+    let piece = sine(quarter(crate::C4) + quarter(crate::A4));
+    let onset = |overlap_ms| {
+        let player = MusicPlayer::new_file(300, 1.0, 8000).with_release_overlap(Duration::from_millis(overlap_ms));
+        player.render_channels(piece.clone())[0][6450..6850].to_vec()
+    };
+
+    // With the overlap, the C4 is still playing during the start of the A4
+    let (plain, overlapped) = (onset(0), onset(100));
+    assert!(energy_at(&overlapped, crate::C4.0) > 100.0 * energy_at(&plain, crate::C4.0));
+    assert!(energy_at(&overlapped, crate::A4.0) > 0.1 * energy_at(&plain, crate::A4.0));
+}
+
+#[test]
+fn test_stereo_width_decorrelates_channels() {
+    let piece = crate::prelude::noise(crate::prelude::quarter(crate::prelude::C4));
+    let render = |stereo_width| {
+        MusicPlayer::new_file(300, 1.0, 8000)
+            .with_channels(2)
+            .with_stereo_width(stereo_width)
+            .render_channels(piece)
+    };
+
+    // With no width, the output is the same as dual mono
+    let mono = render(0.0);
+    assert_eq!(mono[0], mono[1]);
+
+    // Otherwise, the channels differ enough that they're only loosely correlated
+    let correlation = |channels: &[Vec<f32>]| {
+        let dot = |a: &[f32], b: &[f32]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        let (left, right) = (&channels[0], &channels[1]);
+        dot(left, right) / (dot(left, left) * dot(right, right)).sqrt()
+    };
+    assert!((correlation(&mono) - 1.0).abs() < 1e-6);
+    let half = correlation(&render(0.5));
+    let full = correlation(&render(1.0));
+    assert!(half < 0.9);
+    assert!(full < half);
+}
+
+/// Samples quieter than this (-80 dBFS) are considered silent when trimming
+const SILENCE_THRESHOLD: f32 = 0.0001;
+
+/// The channels notes are added into while mixing, at the precision set with [`MusicPlayer::with_mix_precision`]
+enum MixBuffer {
+    Single(Vec<Vec<f32>>),
+    Double(Vec<Vec<f64>>),
+}
+
+impl MixBuffer {
+    fn new(precision: MixPrecision, channels: usize, len: usize) -> Self {
+        match precision {
+            MixPrecision::Single => MixBuffer::Single(vec![vec![0.0; len]; channels]),
+            MixPrecision::Double => MixBuffer::Double(vec![vec![0.0; len]; channels]),
+        }
+    }
+
+    /// Adds a sample into a channel. Samples past the end of the mix are dropped
+    fn add(&mut self, channel: usize, index: usize, sample: f32) {
+        match self {
+            MixBuffer::Single(channels) => {
+                if let Some(mixed) = channels[channel].get_mut(index) {
+                    *mixed += sample;
+                }
+            }
+            MixBuffer::Double(channels) => {
+                if let Some(mixed) = channels[channel].get_mut(index) {
+                    *mixed += f64::from(sample);
+                }
             }
         }
+    }
+
+    fn into_samples(self) -> Vec<Vec<f32>> {
+        match self {
+            MixBuffer::Single(channels) => channels,
+            MixBuffer::Double(channels) => channels
+                .into_iter()
+                .map(|channel| channel.into_iter().map(|sample| sample as f32).collect())
+                .collect(),
+        }
+    }
+}
+
+/// Gets the level of sample `i` of a note `len` samples long, which ramps up from and back down to silence over `ramp` samples
+fn declick_gain(i: usize, len: usize, ramp: usize) -> f32 {
+    let from_edge = i.min(len.saturating_sub(i + 1));
+    if from_edge < ramp {
+        from_edge as f32 / ramp as f32
+    } else {
+        1.0
+    }
+}
+
+/// Removes the silence from the start and end of the buffers, keeping any silence in between.
+fn trim_silent_edges(samples: &mut [Vec<f32>]) {
+    let audible = audible_range(samples);
+    for channel in samples {
+        channel.truncate(audible.end);
+        channel.drain(..audible.start);
+    }
+}
+
+/// Gets the samples from the first audible one to the last, which are kept when trimming silence
+pub(super) fn audible_range(samples: &[Vec<f32>]) -> std::ops::Range<usize> {
+    let total_samples = samples.first().map(Vec::len).unwrap_or_default();
+    let is_audible = |i: &usize| samples.iter().any(|channel| channel[*i].abs() >= SILENCE_THRESHOLD);
+
+    let start = (0..total_samples).find(is_audible).unwrap_or(total_samples);
+    let end = (start..total_samples).rfind(is_audible).map_or(start, |last| last + 1);
+    start..end
+}
+
+/// Appends a `smpl` chunk with a single forward loop to a finalized WAV file that starts at `start`,
+/// and updates the RIFF header to include it.
+///
+/// Samplers and game engines read the loop from this chunk. Sample positions count frames, so they're
+/// the same for every channel, and the end is the last sample that's played before looping.
+fn write_smpl_chunk<W: Write + Seek>(
+    writer: &mut W,
+    start: u64,
+    sample_period_ns: u32,
+    loop_start: usize,
+    loop_end: usize,
+) -> io::Result<()> {
+    let position = |sample: usize| u32::try_from(sample).unwrap_or(u32::MAX);
+    let fields: [u32; 15] = [
+        0,                // Manufacturer
+        0,                // Product
+        sample_period_ns, // Sample period
+        60,               // MIDI unity note, middle C
+        0,                // MIDI pitch fraction
+        0,                // SMPTE format
+        0,                // SMPTE offset
+        1,                // Number of loops
+        0,                // Sampler data length
+        0,                // Loop cue point ID
+        0,                // Loop type: forward
+        position(loop_start),
+        position(loop_end),
+        0, // Loop fraction
+        0, // Play count: forever
+    ];
+
+    writer.seek(SeekFrom::End(0))?;
+    writer.write_all(b"smpl")?;
+    writer.write_all(&(fields.len() as u32 * 4).to_le_bytes())?;
+    for field in fields {
+        writer.write_all(&field.to_le_bytes())?;
+    }
+    let file_end = writer.stream_position()?;
+
+    // The RIFF size doesn't include the "RIFF" tag and the size itself
+    let riff_size = u32::try_from(file_end - start - 8).unwrap_or(u32::MAX);
+    writer.seek(SeekFrom::Start(start + 4))?;
+    writer.write_all(&riff_size.to_le_bytes())?;
+    writer.seek(SeekFrom::Start(file_end))?;
+    Ok(())
+}
+
+#[test]
+fn test_loop_region_writes_smpl_chunk() {
+    use crate::prelude::{label, piano, quarter};
+    use std::io::Cursor;
 
-        writer.finalize().unwrap();
+    let render = |region: Option<LoopRegion>| {
+        let mut player = MusicPlayer::new_file(300, 1.0, 8000);
+        player.output_config.loop_region = region;
+        let piece = piano(quarter(crate::C4)) + label("loop", piano(quarter(crate::A4) + quarter(crate::C4)));
+        let mut buffer = Cursor::new(Vec::new());
+        player.write_wav_to(piece, &mut buffer).unwrap();
+        buffer.into_inner()
+    };
+    let field = |bytes: &[u8], offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+    let smpl_offset = |bytes: &[u8]| bytes.windows(4).position(|tag| tag == b"smpl");
+
+    // Each quarter note is 6400 samples, and the label covers the last two
+    for (region, expected) in [
+        (LoopRegion::TimeUnits(4..12), (6400, 19199)),
+        (LoopRegion::WholePiece, (0, 19199)),
+        (LoopRegion::TimeUnits(10..100), (16000, 19199)),
+    ] {
+        let bytes = render(Some(region));
+        let offset = smpl_offset(&bytes).unwrap();
+        assert_eq!(field(&bytes, offset + 4), 60);
+        assert_eq!(field(&bytes, offset + 8 + 8), 125_000);
+        assert_eq!(field(&bytes, offset + 8 + 28), 1);
+        assert_eq!(
+            (field(&bytes, offset + 8 + 44), field(&bytes, offset + 8 + 48)),
+            expected
+        );
+        assert_eq!(offset + 68, bytes.len());
+
+        // The RIFF header covers the new chunk, and the audio is unchanged
+        assert_eq!(field(&bytes, 4) as usize, bytes.len() - 8);
+        let mut reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.samples::<i16>().count(), 19200);
     }
+
+    // Without a loop region, or with an empty one, there's no chunk
+    assert_eq!(smpl_offset(&render(None)), None);
+    assert_eq!(smpl_offset(&render(Some(LoopRegion::TimeUnits(8..8)))), None);
+    assert_eq!(smpl_offset(&render(Some(LoopRegion::TimeUnits(50..60)))), None);
 }
 
-// This was originally a linear interpolation, but I changed it to cubic for better quality.
+/// Where the channel mask is in a WAV file written with `WAVE_FORMAT_EXTENSIBLE`: after the RIFF header (12 bytes),
+/// the fmt chunk header (8 bytes), the basic format fields (18 bytes), and the valid bits per sample (2 bytes)
+const CHANNEL_MASK_OFFSET: u64 = 40;
+
+#[test]
+fn test_channel_layout_writes_channel_mask() {
+    use crate::play::ChannelLayout;
+    use std::io::Cursor;
+
+    let render = |layout| {
+        let player = MusicPlayer::new_file(300, 1.0, 8000).with_channel_layout(layout);
+        let mut buffer = Cursor::new(Vec::new());
+        player
+            .write_wav_to(crate::prelude::piano(crate::prelude::quarter(crate::C4)), &mut buffer)
+            .unwrap();
+        buffer.into_inner()
+    };
+    let format_tag = |bytes: &[u8]| u16::from_le_bytes([bytes[20], bytes[21]]);
+    let mask_offset = CHANNEL_MASK_OFFSET as usize;
+
+    // 5.1 is written as WAVE_FORMAT_EXTENSIBLE with L, R, C, LFE, Ls, and Rs
+    let surround = render(ChannelLayout::Surround5_1);
+    assert_eq!(format_tag(&surround), 0xFFFE);
+    let mask = u32::from_le_bytes(surround[mask_offset..mask_offset + 4].try_into().unwrap());
+    assert_eq!(mask, 0x1 | 0x2 | 0x4 | 0x8 | 0x200 | 0x400);
+    let mut reader = hound::WavReader::new(Cursor::new(surround)).unwrap();
+    assert_eq!(reader.spec().channels, 6);
+    assert_eq!(reader.samples::<i16>().count(), 6 * 6400);
+
+    // Mono and stereo are still written as plain WAVE_FORMAT_PCM
+    assert_eq!(format_tag(&render(ChannelLayout::Mono)), 1);
+    assert_eq!(format_tag(&render(ChannelLayout::Stereo)), 1);
+}
+
+/// Gets the dither noise added to the sample at `index` (counting every channel), as a fraction of full scale.
+fn dither_offset(dither: DitherMode, seed: u64, index: usize) -> f32 {
+    // Keep the dither noise independent from the noise in the music
+    let seed = mix_seed(seed, 0xD17E_D17E);
+    let random = |n: u64| seed_to_unit(mix_seed(seed, (index as u64).wrapping_mul(2).wrapping_add(n)));
+    let lsb = 1.0 / i16::MAX as f32;
+    match dither {
+        DitherMode::None => 0.0,
+        DitherMode::Rectangular => (random(0) - 0.5) * lsb,
+        // The sum of two rectangular distributions is triangular
+        DitherMode::Triangular => (random(0) + random(1) - 1.0) * lsb,
+    }
+}
+
+#[test]
+fn test_dither_only_changes_output_when_enabled() {
+    use std::io::Cursor;
+
+    let piece = crate::prelude::piano(crate::prelude::quarter(crate::C4));
+    let render = |dither| {
+        let player = MusicPlayer::new_file(300, 0.01, 8000).with_dither(dither);
+        let mut buffer = Cursor::new(Vec::new());
+        player.write_wav_to(piece, &mut buffer).unwrap();
+        let mut reader = hound::WavReader::new(Cursor::new(buffer.into_inner())).unwrap();
+        reader.samples::<i16>().map(Result::unwrap).collect::<Vec<_>>()
+    };
+
+    // Without dither, the output is just the quantized samples
+    let plain = render(DitherMode::None);
+    let channel = &MusicPlayer::new_file(300, 0.01, 8000).render_channels(piece)[0];
+    let quantized: Vec<i16> = channel.iter().map(|&s| quantize_sample(s, ClipMode::Hard)).collect();
+    assert_eq!(plain, quantized);
+
+    // Dither nudges many samples to a neighbouring value, but never further
+    let dithered = render(DitherMode::Triangular);
+    let changed = plain.iter().zip(&dithered).filter(|(a, b)| a != b).count();
+    assert!(changed > plain.len() / 4);
+    assert!(plain
+        .iter()
+        .zip(&dithered)
+        .all(|(&a, &b)| (i32::from(a) - i32::from(b)).abs() <= 1));
+    assert_eq!(dithered, render(DitherMode::Triangular));
+}
+
+#[test]
+fn test_float_output_round_trips_exactly() {
+    use std::io::Cursor;
+
+    let piece = crate::prelude::piano(crate::prelude::quarter(crate::C4)).pan(-0.5);
+    let player = MusicPlayer::new_file(300, 0.3, 8000)
+        .with_channels(2)
+        .with_bit_depth(BitDepth::Float32)
+        .with_dither(DitherMode::Triangular);
+    let mut buffer = Cursor::new(Vec::new());
+    player.write_wav_to(piece, &mut buffer).unwrap();
+    let bytes = buffer.into_inner();
+
+    // The fmt chunk is WAVE_FORMAT_EXTENSIBLE, with 4 bytes per sample and 8 per frame, and its
+    // subformat GUID starts with the WAVE_FORMAT_IEEE_FLOAT tag
+    assert_eq!(u16::from_le_bytes([bytes[20], bytes[21]]), 0xFFFE);
+    assert_eq!(u16::from_le_bytes([bytes[44], bytes[45]]), 3);
+    assert_eq!(u32::from_le_bytes(bytes[28..32].try_into().unwrap()), 8000 * 8);
+    assert_eq!(u16::from_le_bytes([bytes[32], bytes[33]]), 8);
+    assert_eq!(u16::from_le_bytes([bytes[34], bytes[35]]), 32);
+
+    // Every sample is exactly the one that was rendered, without dither
+    let mut reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+    let written: Vec<u32> = reader.samples::<f32>().map(|s| s.unwrap().to_bits()).collect();
+    let channels = player.render_channels(piece);
+    let interleaved: Vec<u32> = (0..channels[0].len())
+        .flat_map(|i| channels.iter().map(move |channel| channel[i].to_bits()))
+        .collect();
+    assert_eq!(written, interleaved);
+}
+
+/// Converts a sample to a 16 bit integer, handling out-of-range samples according to `clip_mode`.
+pub(super) fn quantize_sample(sample: f32, clip_mode: ClipMode) -> i16 {
+    let sample = match clip_mode {
+        ClipMode::Hard => sample.clamp(-1.0, 1.0),
+        ClipMode::Soft => sample.tanh(),
+        // Go through a wider integer and truncate, so that it wraps instead of saturating
+        ClipMode::None => return (sample * i16::MAX as f32) as i32 as i16,
+    };
+    (sample * i16::MAX as f32) as i16
+}
+
+#[test]
+fn test_soft_clip_is_smoother_than_hard_clip() {
+    // A sine wave overdriven well past full scale
+    let signal: Vec<f32> = (0..1000)
+        .map(|i| 4.0 * (std::f32::consts::TAU * 440.0 * i as f32 / 44100.0).sin())
+        .collect();
+
+    // Summing the squared second differences approximates the energy in the high frequencies
+    let high_frequency_energy = |clip_mode| {
+        let quantized: Vec<f32> = signal.iter().map(|&s| quantize_sample(s, clip_mode) as f32).collect();
+        quantized
+            .windows(3)
+            .map(|w| (w[0] - 2.0 * w[1] + w[2]).powi(2))
+            .sum::<f32>()
+    };
+
+    assert!(signal
+        .iter()
+        .all(|&s| (-1.0..=1.0).contains(&(quantize_sample(s, ClipMode::Soft) as f32 / i16::MAX as f32))));
+    assert!(high_frequency_energy(ClipMode::Soft) < high_frequency_energy(ClipMode::Hard));
+}
+
+#[test]
+fn test_center_pan_levels_follow_the_pan_law() {
+    use crate::prelude::{quarter, sine};
+    use crate::PanLaw;
+
+    let decibels = |gain: f32| 20.0 * gain.log10();
+    for (pan_law, expected) in [
+        (PanLaw::Linear, -6.02),
+        (PanLaw::ConstantPower, -3.01),
+        (PanLaw::NegThreeDb, -3.01),
+    ] {
+        let gains = pan_to_channel_gains(0.0, 2, pan_law);
+        assert_eq!(gains.len(), 2);
+        for (_, gain) in gains {
+            assert!((decibels(gain) - expected).abs() < 0.01, "{pan_law:?}");
+        }
+    }
+
+    // The constant power laws keep the same total power wherever the note is panned
+    for pan_law in [PanLaw::ConstantPower, PanLaw::NegThreeDb] {
+        for pan in [-0.9, -0.3, 0.2, 0.75] {
+            let power: f32 = pan_to_channel_gains(pan, 2, pan_law).iter().map(|(_, g)| g * g).sum();
+            assert!((power - 1.0).abs() < 1e-5);
+        }
+    }
+
+    // Rendered: a hard left note sets the left channel's level, so the centered note after it is 3 dB quieter
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_channels(2).without_limiter();
+    let samples = player.render_channels(sine(quarter(crate::A4)).pan(-1.0) + sine(quarter(crate::A4)).pan(0.0));
+    let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    assert!((decibels(peak(&samples[0][6400..])) - -3.01).abs() < 0.05);
+}
+
+/// Shifts the pitch of a buffer by `ratio` without changing its length, using synchronized overlap-add.
+///
+/// The output is built from Hann windowed grains with a 50% overlap, so their windows sum to one.
+/// Each grain steps through the input `ratio` times as fast, and starts reading near the point in
+/// time it is written to. Within a small tolerance, the start is chosen to best match where the
+/// previous grain left off, so that overlapping grains don't cancel each other out.
+fn pitch_shift_buffer(input: &[f32], ratio: f32, sample_rate: u32) -> Vec<f32> {
+    let grain = ((sample_rate as usize) / 20).max(2);
+    let hop = grain / 2;
+    let tolerance = (sample_rate as isize / 100).max(1);
+    let mut output = vec![0.0; input.len()];
+
+    let sample_at = |position: f32| {
+        if position < 0.0 {
+            return 0.0;
+        }
+        let idx = position.floor() as usize;
+        let frac = position - idx as f32;
+        let a = input.get(idx).copied().unwrap_or(0.0);
+        let b = input.get(idx + 1).copied().unwrap_or(0.0);
+        a + (b - a) * frac
+    };
+
+    // Start one hop early so the first samples get full window coverage
+    let mut previous_start: Option<f32> = None;
+    for grain_index in 0..=input.len() / hop + 1 {
+        let nominal = (grain_index * hop) as isize - hop as isize;
+
+        let read_start = match previous_start {
+            None => nominal as f32,
+            Some(previous) => {
+                // Where the previous grain would have kept reading from
+                let continuation = previous + hop as f32 * ratio;
+                let similarity = |start: f32| {
+                    (0..hop)
+                        .step_by(4)
+                        .map(|j| sample_at(start + j as f32 * ratio) * sample_at(continuation + j as f32 * ratio))
+                        .sum::<f32>()
+                };
+                (nominal - tolerance..=nominal + tolerance)
+                    .map(|start| start as f32)
+                    .map(|start| (start, similarity(start)))
+                    .fold((nominal as f32, f32::MIN), |best, candidate| {
+                        if candidate.1 > best.1 {
+                            candidate
+                        } else {
+                            best
+                        }
+                    })
+                    .0
+            }
+        };
+        previous_start = Some(read_start);
+
+        for i in 0..grain {
+            let Some(out_idx) = (grain_index * hop + i).checked_sub(hop) else {
+                continue;
+            };
+            let Some(out) = output.get_mut(out_idx) else {
+                break;
+            };
+            let window = 0.5 - 0.5 * (std::f32::consts::TAU * i as f32 / grain as f32).cos();
+            *out += window * sample_at(read_start + i as f32 * ratio);
+        }
+    }
+
+    output
+}
+
+pub(super) fn hound_to_io_error(err: hound::Error) -> io::Error {
+    match err {
+        hound::Error::IoError(err) => err,
+        err => io::Error::new(io::ErrorKind::Other, err),
+    }
+}
+
+// Used for `Interpolation::Cubic`, which gives the smoothest result.
 fn cubic_interp(y0: f32, y1: f32, y2: f32, y3: f32, t: f32) -> f32 {
     let a0 = y3 - y2 - y0 + y1;
     let a1 = y0 - y1 - a0;
@@ -234,6 +1603,7 @@ fn resample_to_target_rate<I: Iterator<Item = f32>>(
     input_rate: u32,
     output_rate: u32,
     num_samples: usize,
+    interpolation: Interpolation,
 ) -> Vec<f32> {
     if input_rate == output_rate {
         return input.take(num_samples).collect();
@@ -254,7 +1624,108 @@ fn resample_to_target_rate<I: Iterator<Item = f32>>(
         let y3 = *input
             .get((idx + 2).min((input_len - 1) as isize) as usize)
             .unwrap_or(&0.0);
-        output.push(cubic_interp(y0, y1, y2, y3, frac));
+        output.push(match interpolation {
+            Interpolation::NearestNeighbor => {
+                if frac < 0.5 {
+                    y1
+                } else {
+                    y2
+                }
+            }
+            Interpolation::Linear => y1 + (y2 - y1) * frac,
+            Interpolation::Cubic => cubic_interp(y0, y1, y2, y3, frac),
+        });
     }
     output
 }
+
+#[test]
+fn test_cubic_resampling_is_smoother_than_nearest() {
+    // A low sample rate sine, pitched up by playing it back faster than it was recorded
+    let input: Vec<f32> = (0..800)
+        .map(|i| (std::f32::consts::TAU * 440.0 * i as f32 / 8000.0).sin())
+        .collect();
+
+    // Summing the squared second differences approximates the energy in the high frequencies
+    let high_frequency_energy = |interpolation| {
+        let output = resample_to_target_rate(input.clone().into_iter(), 12000, 44100, 2940, interpolation);
+        output
+            .windows(3)
+            .map(|w| (w[0] - 2.0 * w[1] + w[2]).powi(2))
+            .sum::<f32>()
+    };
+
+    let nearest = high_frequency_energy(Interpolation::NearestNeighbor);
+    let linear = high_frequency_energy(Interpolation::Linear);
+    let cubic = high_frequency_energy(Interpolation::Cubic);
+
+    assert!(cubic < nearest);
+    assert!(linear < nearest);
+}
+
+#[test]
+fn test_lfo_cycles_once_per_beat_at_any_tempo() {
+    use crate::prelude::{double_whole, lfo, sine, ModTarget};
+
+    for tempo in [300, 240] {
+        let player = MusicPlayer::new_file(tempo, 1.0, 8000).without_limiter();
+        let samples = &player.render_channels(lfo(sine(double_whole(crate::A4)), ModTarget::Amplitude, 1.0, 0.5))[0];
+        // The loudest sample in each 20 ms, a few cycles of the sine
+        let envelope: Vec<f32> = samples
+            .chunks(160)
+            .map(|window| window.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs())))
+            .collect();
+
+        // Sixteenth notes per minute, so four of them make a beat
+        let windows_per_beat = 8000 * 60 * 4 / tempo as usize / 160;
+        for beat in envelope.chunks(windows_per_beat).skip(1).take(6) {
+            let loudest = (0..beat.len()).max_by(|&a, &b| beat[a].total_cmp(&beat[b])).unwrap();
+            let quietest = (0..beat.len()).min_by(|&a, &b| beat[a].total_cmp(&beat[b])).unwrap();
+            // Loudest a quarter of the way through each beat, and quietest three quarters of the way
+            assert!(
+                loudest.abs_diff(windows_per_beat / 4) <= 1,
+                "loudest at {loudest} of {windows_per_beat}"
+            );
+            assert!(
+                quietest.abs_diff(windows_per_beat * 3 / 4) <= 1,
+                "quietest at {quietest} of {windows_per_beat}"
+            );
+            assert!(beat[loudest] > beat[quietest] * 2.5);
+        }
+    }
+}
+
+#[test]
+fn test_double_precision_mixes_many_quiet_voices_more_accurately() {
+    use crate::prelude::*;
+
+    let cancel = AtomicBool::new(false);
+    let mix = |player: &MusicPlayer<FileOutputConfig>, piece: Piece| {
+        player.mix_channels_until(piece, &cancel, None).unwrap().remove(0)
+    };
+    let single = MusicPlayer::new_file(300, 1.0, 8000);
+    let double = single.clone().with_mix_precision(MixPrecision::Double);
+
+    // Hundreds of quiet sines, all at different pitches
+    let voices: Vec<Line> = (0..300u16)
+        .map(|voice| sine(Line::from(quarter(NotePitch(100.0 + f32::from(voice) * 3.7)))).volume(0.003))
+        .collect();
+    // Each voice mixed on its own is exact, so adding them up at 64 bits gives the reference
+    let mut reference = vec![0.0f64; 6400];
+    for voice in &voices {
+        for (total, sample) in reference.iter_mut().zip(mix(&single, Piece::from(voice.clone()))) {
+            *total += f64::from(sample);
+        }
+    }
+
+    let error = |mixed: Vec<f32>| -> f64 {
+        mixed
+            .iter()
+            .zip(&reference)
+            .map(|(&sample, &total)| (f64::from(sample) - total).abs())
+            .sum()
+    };
+    let single_error = error(mix(&single, Piece::from(voices.clone())));
+    let double_error = error(mix(&double, Piece::from(voices)));
+    assert!(double_error * 10.0 < single_error, "{double_error} vs {single_error}");
+}
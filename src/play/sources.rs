@@ -1,25 +1,283 @@
 use std::{io::BufReader, path::Path, time::Duration};
 
-use rodio::{source::SineWave, Decoder, Source};
+use rodio::{Decoder, Source};
 
-use crate::{note::Timbre, Tet12, C4};
+use crate::{
+    note::{Glide, Instrument, Timbre},
+    play::{CurveShape, EnvelopeCurves, OscillatorPhase},
+    NotePitch, Tet12, C4,
+};
 
 pub type SymphoxySource = Box<dyn Source<Item = f32> + Send>;
 
-pub fn get_source(duration_ms: u64, frequency: f32, timbre: Timbre, volume: f32) -> SymphoxySource {
-    Box::new(get_dyn_source(duration_ms, frequency, timbre).amplify(volume))
+/// Gets the source for a note. `seed` seeds any randomness in the source, such as noise.
+pub fn get_source(
+    duration_ms: u64,
+    frequency: f32,
+    timbre: Timbre,
+    volume: f32,
+    seed: u64,
+    curves: EnvelopeCurves,
+    phase: OscillatorPhase,
+) -> SymphoxySource {
+    Box::new(get_dyn_source(duration_ms, frequency, timbre, seed, curves, phase).amplify(volume))
 }
 
-fn get_dyn_source(duration_ms: u64, frequency: f32, timbre: Timbre) -> SymphoxySource {
+fn get_dyn_source(
+    duration_ms: u64,
+    frequency: f32,
+    timbre: Timbre,
+    seed: u64,
+    curves: EnvelopeCurves,
+    phase: OscillatorPhase,
+) -> SymphoxySource {
+    let start_phase = match phase {
+        OscillatorPhase::Fixed(cycles) => cycles,
+        OscillatorPhase::Random => seed_to_unit(mix_seed(seed, 2)),
+    };
     match timbre {
-        Timbre::Sine => get_sine_source(duration_ms, frequency),
-        Timbre::Bass => get_bass_source(duration_ms, frequency),
-        Timbre::Piano => get_piano_source(duration_ms, frequency),
-        Timbre::ElectricGuitar => get_electric_guitar_source(duration_ms, frequency),
+        Timbre::Noise => get_noise_source(duration_ms, seed, curves),
+        Timbre::BandpassNoise { q } => get_bandpass_noise_source(duration_ms, frequency, q, seed, curves),
+        Timbre::Sine => get_sine_source(duration_ms, frequency, start_phase, curves),
+        Timbre::Bass => get_bass_source(duration_ms, frequency, start_phase, curves),
+        Timbre::Piano => get_piano_source(duration_ms, frequency, start_phase, curves),
+        Timbre::ElectricGuitar => get_electric_guitar_source(duration_ms, frequency, start_phase, curves),
         Timbre::Drums => get_drum_source(duration_ms, frequency),
         Timbre::CustomSourceUnpitched(file) => get_custom_source_unpitched(Path::new(file), duration_ms),
         Timbre::CustomSourcePitched(file) => get_custom_source_pitched(Path::new(file), duration_ms, frequency),
+        Timbre::Layered(timbres) => get_layered_source(duration_ms, frequency, timbres, seed, curves, phase),
+        Timbre::Instrument(instrument) => Box::new(InstrumentVoice::new(
+            duration_ms,
+            frequency,
+            instrument,
+            start_phase,
+            curves,
+        )),
+    }
+}
+
+/// Gets the source for a note that may glide into its pitch from another one.
+///
+/// Gliding changes the speed of the source, so `source` is asked for a little more or less than `duration_ms`
+/// of audio, so that the glided source still lasts `duration_ms`.
+pub fn with_glide(
+    duration_ms: u64,
+    pitch: NotePitch,
+    glide: Option<Glide>,
+    source: impl FnOnce(u64) -> SymphoxySource,
+) -> SymphoxySource {
+    let Some(glide) = glide.filter(|glide| !glide.duration.is_zero() && glide.from.0 > 0.0 && pitch.0 > 0.0) else {
+        return source(duration_ms);
+    };
+    let ratio = glide.from.0 / pitch.0;
+    // The source is read at `ratio ^ (1 - t)` times its speed over the glide, which averages out to this
+    let average_speed = if (ratio - 1.0).abs() < 1e-6 {
+        1.0
+    } else {
+        (ratio - 1.0) / ratio.ln()
+    };
+    #[expect(clippy::cast_precision_loss, reason = "Note lengths in milliseconds fit well enough in an f64")]
+    let duration = duration_ms as f64;
+    let glide_ms = (glide.duration.as_secs_f64() * 1000.0).min(duration);
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss, reason = "Rounded, and never negative")]
+    let source_ms = (duration + glide_ms * (f64::from(average_speed) - 1.0))
+        .round()
+        .max(0.0) as u64;
+    Box::new(GlideSource::new(source(source_ms), ratio, glide.duration))
+}
+
+/// Plays a source at `ratio` times its speed at the start, sliding evenly in pitch to its own speed over `duration`
+struct GlideSource {
+    source: SymphoxySource,
+    /// The natural log of the ratio of the starting speed to the source's own
+    log_ratio: f32,
+    /// How many frames the glide lasts
+    glide_frames: f32,
+    /// The frames either side of the read position, and how far between them it is
+    current: Vec<f32>,
+    next: Vec<f32>,
+    fraction: f32,
+    /// The frame being output, and which of its channels is next
+    frame: u64,
+    channel: usize,
+}
+
+impl GlideSource {
+    fn new(mut source: SymphoxySource, ratio: f32, duration: Duration) -> Self {
+        let channels = usize::from(source.channels().max(1));
+        let current = source.by_ref().take(channels).collect();
+        let next = source.by_ref().take(channels).collect();
+        #[expect(clippy::cast_precision_loss, reason = "Sample rates fit in an f32")]
+        let glide_frames = duration.as_secs_f32() * source.sample_rate() as f32;
+        GlideSource {
+            source,
+            log_ratio: ratio.ln(),
+            glide_frames,
+            current,
+            next,
+            fraction: 0.0,
+            frame: 0,
+            channel: 0,
+        }
+    }
+}
+
+impl Iterator for GlideSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let channels = self.current.len();
+        if channels == 0 || self.next.len() < channels {
+            return None;
+        }
+        let (current, next) = (self.current[self.channel], self.next[self.channel]);
+        let sample = current + (next - current) * self.fraction;
+
+        self.channel = self.channel.saturating_add(1);
+        if self.channel == channels {
+            self.channel = 0;
+            #[expect(clippy::cast_precision_loss, reason = "Frames within a note fit well enough in an f32")]
+            let progress = (self.frame as f32 / self.glide_frames).min(1.0);
+            self.frame = self.frame.saturating_add(1);
+            self.fraction += (self.log_ratio * (1.0 - progress)).exp();
+            while self.fraction >= 1.0 {
+                self.fraction -= 1.0;
+                self.current = std::mem::take(&mut self.next);
+                self.next = self.source.by_ref().take(channels).collect();
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl Source for GlideSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Fades the source in over `duration`, following the shape of `curve`
+fn fade_in<S: Source<Item = f32> + Send + 'static>(source: S, duration: Duration, curve: CurveShape) -> SymphoxySource {
+    match curve {
+        CurveShape::Linear => Box::new(source.fade_in(duration)),
+        _ => Box::new(ShapedFade::new(source, duration, curve, false)),
+    }
+}
+
+/// Fades the source out over `duration`, following the shape of `curve`, and ends it once the fade is over
+fn fade_out<S: Source<Item = f32> + Send + 'static>(
+    source: S,
+    duration: Duration,
+    curve: CurveShape,
+) -> SymphoxySource {
+    match curve {
+        // Rodio's fade out stays silent forever afterwards, rather than ending
+        CurveShape::Linear => Box::new(source.fade_out(duration).take_duration(duration)),
+        _ => Box::new(ShapedFade::new(source, duration, curve, true).take_duration(duration)),
+    }
+}
+
+/// Fades a source in or out along a [`CurveShape`]
+struct ShapedFade<S: Source<Item = f32>> {
+    source: S,
+    curve: CurveShape,
+    fade_out: bool,
+    /// How many samples, across all channels, the fade lasts
+    total_samples: f32,
+    elapsed_samples: f32,
+}
+
+impl<S: Source<Item = f32>> ShapedFade<S> {
+    fn new(source: S, duration: Duration, curve: CurveShape, fade_out: bool) -> Self {
+        #[expect(clippy::cast_precision_loss, reason = "Sample rates and channel counts fit in an f32")]
+        let samples_per_second = (source.sample_rate() as f32) * f32::from(source.channels());
+        ShapedFade {
+            total_samples: duration.as_secs_f32() * samples_per_second,
+            source,
+            curve,
+            fade_out,
+            elapsed_samples: 0.0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for ShapedFade<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.source.next()?;
+        let progress = if self.total_samples > 0.0 {
+            self.elapsed_samples / self.total_samples
+        } else {
+            1.0
+        };
+        self.elapsed_samples += 1.0;
+        let level = self.curve.apply(progress);
+        Some(sample * if self.fade_out { 1.0 - level } else { level })
+    }
+}
+
+impl<S: Source<Item = f32>> Source for ShapedFade<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
     }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+/// Sums the sources of every timbre in a layer
+pub fn get_layered_source(
+    duration_ms: u64,
+    frequency: f32,
+    timbres: &[Timbre],
+    seed: u64,
+    curves: EnvelopeCurves,
+    phase: OscillatorPhase,
+) -> SymphoxySource {
+    timbres
+        .iter()
+        .enumerate()
+        // Give each layer its own seed, so that layered noise doesn't just double in volume
+        .map(|(index, &timbre)| {
+            get_dyn_source(
+                duration_ms,
+                frequency,
+                timbre,
+                mix_seed(seed, index as u64),
+                curves,
+                phase,
+            )
+        })
+        .reduce(|layers, layer| Box::new(layers.mix(layer)))
+        .unwrap_or_else(|| {
+            Box::new(
+                rodio::source::Zero::<f32>::new(1, 44100)
+                    .convert_samples()
+                    .take_duration(Duration::from_millis(duration_ms)),
+            )
+        })
 }
 
 pub fn get_custom_source_pitched(file: &Path, duration_ms: u64, frequency: f32) -> SymphoxySource {
@@ -66,19 +324,365 @@ pub fn get_custom_source_unpitched(file: &Path, duration_ms: u64) -> SymphoxySou
     }
 }
 
-pub fn get_sine_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
+pub fn get_sine_source(duration_ms: u64, frequency: f32, phase: f32, curves: EnvelopeCurves) -> SymphoxySource {
     let sources: Vec<Box<dyn Source<Item = f32> + Send>> = vec![
-        Box::new(
-            SineWave::new(frequency)
-                .take_duration(Duration::from_millis(duration_ms.saturating_sub(40)))
-                .fade_in(Duration::from_millis(40)),
+        fade_in(
+            SineWave::new(frequency, phase).take_duration(Duration::from_millis(duration_ms.saturating_sub(40))),
+            Duration::from_millis(40),
+            curves.attack,
+        ),
+        fade_out(
+            SineWave::new(frequency, phase),
+            Duration::from_millis(40),
+            curves.release,
         ),
-        Box::new(SineWave::new(frequency).fade_out(Duration::from_millis(40))),
     ];
 
     Box::new(rodio::source::from_iter(sources).amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0)))
 }
 
+pub fn get_noise_source(duration_ms: u64, seed: u64, curves: EnvelopeCurves) -> SymphoxySource {
+    let source = NoiseSource::new(seed)
+        .take_duration(Duration::from_millis(duration_ms))
+        .amplify(0.25);
+    fade_out(
+        fade_in(source, Duration::from_millis(5), curves.attack),
+        Duration::from_millis(duration_ms),
+        curves.release,
+    )
+}
+
+pub fn get_bandpass_noise_source(
+    duration_ms: u64,
+    frequency: f32,
+    q: u8,
+    seed: u64,
+    curves: EnvelopeCurves,
+) -> SymphoxySource {
+    let q = f32::from(q.max(1));
+    let filtered = BandpassFilter::new(NoiseSource::new(seed), frequency, q);
+    // Filtering removes most of the noise's power, so make up for the part of the spectrum that's left
+    #[expect(clippy::cast_precision_loss, reason = "Sample rates fit in an f32")]
+    let bandwidth_fraction = frequency / q / (filtered.sample_rate() as f32 / 2.0);
+    let makeup_gain = bandwidth_fraction.recip().sqrt().max(1.0);
+    let source = filtered
+        .take_duration(Duration::from_millis(duration_ms))
+        .amplify(0.25 * makeup_gain);
+    fade_out(
+        fade_in(source, Duration::from_millis(5), curves.attack),
+        Duration::from_millis(duration_ms),
+        curves.release,
+    )
+}
+
+/// A biquad bandpass filter, with a peak gain of 1 at its center frequency
+struct BandpassFilter<S: Source<Item = f32>> {
+    source: S,
+    /// The coefficients b0, b2, a1 and a2, normalized by a0. b1 is always 0
+    coefficients: [f32; 4],
+    /// The last two inputs and outputs
+    history: [f32; 4],
+}
+
+impl<S: Source<Item = f32>> BandpassFilter<S> {
+    fn new(source: S, frequency: f32, q: f32) -> Self {
+        // From the Audio EQ Cookbook, for a constant 0 dB peak gain
+        #[expect(clippy::cast_precision_loss, reason = "Sample rates fit in an f32")]
+        let nyquist = source.sample_rate() as f32 / 2.0;
+        let omega = std::f32::consts::PI * (frequency / nyquist).clamp(0.0001, 0.9999);
+        let alpha = omega.sin() / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        BandpassFilter {
+            source,
+            coefficients: [alpha / a0, -alpha / a0, -2.0 * omega.cos() / a0, (1.0 - alpha) / a0],
+            history: [0.0; 4],
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for BandpassFilter<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let input = self.source.next()?;
+        let [b0, b2, a1, a2] = self.coefficients;
+        let [x1, x2, y1, y2] = self.history;
+        let output = b0 * input + b2 * x2 - a1 * y1 - a2 * y2;
+        self.history = [input, x1, output, y1];
+        Some(output)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for BandpassFilter<S> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+/// Mixes a value into a seed, producing a new seed. This is one step of the `SplitMix64` generator.
+pub fn mix_seed(seed: u64, value: u64) -> u64 {
+    let mut z = seed.wrapping_add(value).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Turns a seed into a random number from 0.0 (inclusive) to 1.0 (exclusive)
+pub fn seed_to_unit(seed: u64) -> f32 {
+    // Use the top 24 bits, which fit exactly in an f32
+    #[expect(clippy::cast_precision_loss, reason = "24 bits fit in an f32 without loss")]
+    let unit = (mix_seed(seed, 0) >> 40) as f32 / (1u64 << 24) as f32;
+    unit
+}
+
+/// White noise from a xorshift random number generator, so that it's reproducible from its seed
+struct NoiseSource {
+    state: u64,
+}
+
+impl NoiseSource {
+    fn new(seed: u64) -> Self {
+        // Xorshift gets stuck on zero, and similar seeds should still sound different
+        NoiseSource {
+            state: mix_seed(seed, 0).max(1),
+        }
+    }
+}
+
+impl Iterator for NoiseSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        // Use the top 24 bits, which fit exactly in an f32
+        #[expect(clippy::cast_precision_loss, reason = "24 bits fit in an f32 without loss")]
+        let unit = (self.state >> 40) as f32 / (1u64 << 24) as f32;
+        Some(unit * 2.0 - 1.0)
+    }
+}
+
+impl Source for NoiseSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        44100
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// A sine wave at 48 kHz, like rodio's `SineWave`, but starting at any point in its cycle
+struct SineWave {
+    /// How many samples one cycle lasts
+    period: f32,
+    /// Where in its cycle the wave starts, in cycles
+    phase: f32,
+    index: u64,
+}
+
+impl SineWave {
+    const SAMPLE_RATE: u32 = 48000;
+
+    fn new(frequency: f32, phase: f32) -> Self {
+        #[expect(clippy::cast_precision_loss, reason = "The sample rate fits in an f32")]
+        let period = Self::SAMPLE_RATE as f32 / frequency;
+        SineWave {
+            period,
+            phase: phase.rem_euclid(1.0),
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for SineWave {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // Computed from the sample index rather than accumulated, so that long notes don't drift
+        #[expect(clippy::cast_precision_loss, reason = "Same as rodio's sine wave")]
+        let cycles = self.index as f32 / self.period + self.phase;
+        self.index = self.index.wrapping_add(1);
+        Some((std::f32::consts::TAU * cycles).sin())
+    }
+}
+
+impl Source for SineWave {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        Self::SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// One note of an [`Instrument`]: its partials summed at 48 kHz, then enveloped and filtered
+struct InstrumentVoice {
+    /// The frequency of each partial in cycles per sample, where in its cycle it starts, and its level
+    partials: Vec<(f32, f32, f32)>,
+    /// The length of the attack, decay, and release, and of the whole note, in samples
+    attack: f32,
+    decay: f32,
+    release: f32,
+    total: u64,
+    sustain: f32,
+    curves: EnvelopeCurves,
+    /// The low-pass filter's coefficients b0, b1, a1, and a2, normalized by a0, if it has one. b2 is always b0
+    low_pass: Option<[f32; 4]>,
+    /// The last two inputs and outputs of the filter
+    history: [f32; 4],
+    index: u64,
+}
+
+impl InstrumentVoice {
+    const SAMPLE_RATE: u32 = 48000;
+
+    #[expect(clippy::cast_precision_loss, reason = "The sample rate and note lengths fit in an f32")]
+    fn new(duration_ms: u64, frequency: f32, instrument: Instrument, phase: f32, curves: EnvelopeCurves) -> Self {
+        // Notes are written at the pitch the player reads, and sound at concert pitch
+        let frequency = frequency * 2.0f32.powf(f32::from(instrument.transpose) / 12.0);
+        let sample_rate = Self::SAMPLE_RATE as f32;
+        let nyquist = sample_rate / 2.0;
+        let partials: Vec<(f32, f32, f32)> = instrument
+            .partials
+            .iter()
+            .map(|partial| (2.0f32.powf(f32::from(partial.cents) / 1200.0), f32::from(partial.level)))
+            // Partials above the Nyquist frequency would alias down to unrelated pitches
+            .filter(|&(ratio, _)| frequency * ratio < nyquist)
+            .map(|(ratio, level)| (frequency * ratio / sample_rate, (phase * ratio).rem_euclid(1.0), level))
+            .collect();
+        // Keep the sum of the partials between -1.0 and 1.0
+        let total_level = partials.iter().map(|&(_, _, level)| level).sum::<f32>().max(1.0);
+        let partials = partials
+            .into_iter()
+            .map(|(step, phase, level)| (step, phase, level / total_level))
+            .collect();
+
+        let low_pass = instrument.low_pass_hz.map(|cutoff| {
+            // A Butterworth low-pass from the Audio EQ Cookbook
+            let omega = std::f32::consts::PI * (f32::from(cutoff) / nyquist).clamp(0.0001, 0.9999);
+            let alpha = omega.sin() * std::f32::consts::FRAC_1_SQRT_2;
+            let a0 = 1.0 + alpha;
+            let b1 = (1.0 - omega.cos()) / a0;
+            [b1 / 2.0, b1, -2.0 * omega.cos() / a0, (1.0 - alpha) / a0]
+        });
+
+        let to_samples = |ms: u16| f32::from(ms) * sample_rate / 1000.0;
+        InstrumentVoice {
+            partials,
+            attack: to_samples(instrument.attack_ms),
+            decay: to_samples(instrument.decay_ms),
+            release: to_samples(instrument.release_ms).min(duration_ms as f32 * sample_rate / 1000.0),
+            total: duration_ms.saturating_mul(u64::from(Self::SAMPLE_RATE)) / 1000,
+            sustain: f32::from(instrument.sustain.min(100)) / 100.0,
+            curves,
+            low_pass,
+            history: [0.0; 4],
+            index: 0,
+        }
+    }
+
+    /// The level of the envelope at a sample, from 0.0 to 1.0
+    fn envelope(&self, index: f32, total: f32) -> f32 {
+        let attack = if index < self.attack {
+            self.curves.attack.apply(index / self.attack)
+        } else {
+            1.0
+        };
+        let decay = if index < self.attack {
+            1.0
+        } else if index < self.attack + self.decay {
+            1.0 - (1.0 - self.sustain) * (index - self.attack) / self.decay
+        } else {
+            self.sustain
+        };
+        let release_start = total - self.release;
+        let release = if index >= release_start && self.release > 0.0 {
+            1.0 - self.curves.release.apply((index - release_start) / self.release)
+        } else {
+            1.0
+        };
+        attack * decay * release
+    }
+}
+
+impl Iterator for InstrumentVoice {
+    type Item = f32;
+
+    #[expect(clippy::cast_precision_loss, reason = "Sample indices within a note fit well enough in an f32")]
+    fn next(&mut self) -> Option<f32> {
+        if self.index >= self.total {
+            return None;
+        }
+        let index = self.index as f32;
+        let sample: f32 = self
+            .partials
+            .iter()
+            .map(|&(step, phase, level)| level * (std::f32::consts::TAU * (index * step + phase)).sin())
+            .sum();
+        let sample = sample * self.envelope(index, self.total as f32);
+        self.index = self.index.saturating_add(1);
+
+        let Some([b0, b1, a1, a2]) = self.low_pass else {
+            return Some(sample);
+        };
+        let [x1, x2, y1, y2] = self.history;
+        let output = b0 * sample + b1 * x1 + b0 * x2 - a1 * y1 - a2 * y2;
+        self.history = [sample, x1, output, y1];
+        Some(output)
+    }
+}
+
+impl Source for InstrumentVoice {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        Self::SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        #[expect(clippy::cast_precision_loss, reason = "Note lengths in samples fit well enough in an f64")]
+        let seconds = self.total as f64 / f64::from(Self::SAMPLE_RATE);
+        Some(Duration::from_secs_f64(seconds))
+    }
+}
+
 fn decibels_to_amplitude_ratio(dec: f32) -> f32 {
     10.0f32.powf(dec / 20.0)
 }
@@ -103,57 +707,203 @@ pub fn get_drum_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
     }
 }
 
-pub fn get_electric_guitar_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
-    use rodio::source::SineWave;
-
-    Box::new(
-        SineWave::new(frequency)
-            .mix(SineWave::new(frequency * 2.0).amplify(decibels_to_amplitude_ratio(0.0)))
-            .mix(SineWave::new(frequency * 3.0).amplify(decibels_to_amplitude_ratio(8.0)))
-            .mix(SineWave::new(frequency * 4.0).amplify(decibels_to_amplitude_ratio(3.0)))
-            .mix(SineWave::new(frequency * 5.0).amplify(decibels_to_amplitude_ratio(-7.0)))
-            .mix(SineWave::new(frequency * 6.0).amplify(decibels_to_amplitude_ratio(-12.0)))
-            .mix(SineWave::new(frequency * 7.0).amplify(decibels_to_amplitude_ratio(-8.0)))
-            .mix(SineWave::new(frequency * 8.0).amplify(decibels_to_amplitude_ratio(-10.0)))
+pub fn get_electric_guitar_source(
+    duration_ms: u64,
+    frequency: f32,
+    phase: f32,
+    curves: EnvelopeCurves,
+) -> SymphoxySource {
+    fade_out(
+        SineWave::new(frequency, phase)
+            .mix(SineWave::new(frequency * 2.0, phase * 2.0).amplify(decibels_to_amplitude_ratio(0.0)))
+            .mix(SineWave::new(frequency * 3.0, phase * 3.0).amplify(decibels_to_amplitude_ratio(8.0)))
+            .mix(SineWave::new(frequency * 4.0, phase * 4.0).amplify(decibels_to_amplitude_ratio(3.0)))
+            .mix(SineWave::new(frequency * 5.0, phase * 5.0).amplify(decibels_to_amplitude_ratio(-7.0)))
+            .mix(SineWave::new(frequency * 6.0, phase * 6.0).amplify(decibels_to_amplitude_ratio(-12.0)))
+            .mix(SineWave::new(frequency * 7.0, phase * 7.0).amplify(decibels_to_amplitude_ratio(-8.0)))
+            .mix(SineWave::new(frequency * 8.0, phase * 8.0).amplify(decibels_to_amplitude_ratio(-10.0)))
             .take_duration(Duration::from_millis(duration_ms))
-            .amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_out(Duration::from_millis(duration_ms)),
+            .amplify((3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
+        Duration::from_millis(duration_ms),
+        curves.release,
     )
 }
 
-pub fn get_bass_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
-    use rodio::source::SineWave;
-
-    Box::new(
-        SineWave::new(frequency)
-            .mix(SineWave::new(frequency * 2.0).amplify(1.0 / 10.0))
-            .mix(SineWave::new(frequency * 3.0).amplify(2.0))
-            .mix(SineWave::new(frequency * 4.0).amplify(1.0 / 5.0))
-            .mix(SineWave::new(frequency * 5.0))
-            .mix(SineWave::new(frequency * 6.0))
-            .mix(SineWave::new(frequency * 7.0).amplify(1.0 / 3.0))
-            .mix(SineWave::new(frequency * 8.0).amplify(1.0 / 10.0))
+pub fn get_bass_source(duration_ms: u64, frequency: f32, phase: f32, curves: EnvelopeCurves) -> SymphoxySource {
+    fade_out(
+        SineWave::new(frequency, phase)
+            .mix(SineWave::new(frequency * 2.0, phase * 2.0).amplify(1.0 / 10.0))
+            .mix(SineWave::new(frequency * 3.0, phase * 3.0).amplify(2.0))
+            .mix(SineWave::new(frequency * 4.0, phase * 4.0).amplify(1.0 / 5.0))
+            .mix(SineWave::new(frequency * 5.0, phase * 5.0))
+            .mix(SineWave::new(frequency * 6.0, phase * 6.0))
+            .mix(SineWave::new(frequency * 7.0, phase * 7.0).amplify(1.0 / 3.0))
+            .mix(SineWave::new(frequency * 8.0, phase * 8.0).amplify(1.0 / 10.0))
             .take_duration(Duration::from_millis(duration_ms))
-            .amplify(12.0 * (3.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_out(Duration::from_millis(duration_ms)),
+            .amplify(12.0 * (3.0 * 44.0 / frequency).clamp(0.0, 1.0)),
+        Duration::from_millis(duration_ms),
+        curves.release,
     )
 }
 
-pub fn get_piano_source(duration_ms: u64, frequency: f32) -> SymphoxySource {
-    use rodio::source::SineWave;
-
-    Box::new(
-        SineWave::new(frequency)
-            .mix(SineWave::new(frequency * 2.0).amplify(1.0 / 4.0))
-            .mix(SineWave::new(frequency * 3.0).amplify(1.0 / 6.0))
-            .mix(SineWave::new(frequency * 4.0).amplify(1.0 / 10.0))
-            .mix(SineWave::new(frequency * 5.0).amplify(1.0 / 12.0))
-            .mix(SineWave::new(frequency * 6.0).amplify(1.0 / 12.0))
-            .mix(SineWave::new(frequency * 7.0).amplify(1.0 / 36.0))
-            .mix(SineWave::new(frequency * 8.0).amplify(1.0 / 72.0))
-            .take_duration(Duration::from_millis(duration_ms))
-            .amplify((12.0 * 44.0 / frequency).clamp(0.0, 1.0))
-            .fade_in(Duration::from_millis(5))
-            .fade_out(Duration::from_millis(duration_ms)),
+pub fn get_piano_source(duration_ms: u64, frequency: f32, phase: f32, curves: EnvelopeCurves) -> SymphoxySource {
+    let source = SineWave::new(frequency, phase)
+        .mix(SineWave::new(frequency * 2.0, phase * 2.0).amplify(1.0 / 4.0))
+        .mix(SineWave::new(frequency * 3.0, phase * 3.0).amplify(1.0 / 6.0))
+        .mix(SineWave::new(frequency * 4.0, phase * 4.0).amplify(1.0 / 10.0))
+        .mix(SineWave::new(frequency * 5.0, phase * 5.0).amplify(1.0 / 12.0))
+        .mix(SineWave::new(frequency * 6.0, phase * 6.0).amplify(1.0 / 12.0))
+        .mix(SineWave::new(frequency * 7.0, phase * 7.0).amplify(1.0 / 36.0))
+        .mix(SineWave::new(frequency * 8.0, phase * 8.0).amplify(1.0 / 72.0))
+        .take_duration(Duration::from_millis(duration_ms))
+        .amplify((12.0 * 44.0 / frequency).clamp(0.0, 1.0));
+    fade_out(
+        fade_in(source, Duration::from_millis(5), curves.attack),
+        Duration::from_millis(duration_ms),
+        curves.release,
     )
 }
+
+#[test]
+fn test_layered_source_sums_its_layers() {
+    let frequency = 220.0;
+    let samples = |timbre| -> Vec<f32> {
+        get_dyn_source(
+            500,
+            frequency,
+            timbre,
+            0,
+            EnvelopeCurves::default(),
+            OscillatorPhase::default(),
+        )
+        .take(24000)
+        .collect()
+    };
+
+    let sine = samples(Timbre::Sine);
+    let guitar = samples(Timbre::ElectricGuitar);
+    let layered = samples(Timbre::Layered(&[Timbre::Sine, Timbre::ElectricGuitar]));
+
+    for ((s, g), l) in sine.iter().zip(&guitar).zip(&layered) {
+        assert!((s + g - l).abs() < 1e-4);
+    }
+
+    let energy_at = |samples: &[f32], frequency: f32| super::spectrum::energy_at(samples, frequency, 48000);
+
+    // The sine layer adds its fundamental, and the guitar layer adds harmonics the sine doesn't have
+    assert!(energy_at(&layered, frequency) > energy_at(&guitar, frequency));
+    assert!(energy_at(&layered, 3.0 * frequency) > 100.0 * energy_at(&sine, 3.0 * frequency));
+}
+
+#[test]
+#[expect(clippy::cast_precision_loss, reason = "Sample indices are small")]
+fn test_bandpass_noise_centroid_tracks_pitch() {
+    // The average frequency of the spectrum, weighted by its energy
+    let spectral_centroid = |frequency: f32| {
+        let samples: Vec<f32> = get_dyn_source(
+            500,
+            frequency,
+            Timbre::BandpassNoise { q: 20 },
+            0,
+            EnvelopeCurves::default(),
+            OscillatorPhase::default(),
+        )
+        .take(4096)
+        .collect();
+        let (mut weighted, mut total) = (0.0, 0.0);
+        for bin in 1..samples.len() / 2 {
+            let omega = std::f32::consts::TAU * bin as f32 / samples.len() as f32;
+            let (re, im) = samples.iter().enumerate().fold((0.0, 0.0), |(re, im), (i, &sample)| {
+                let phase = omega * i as f32;
+                (re + sample * phase.cos(), im - sample * phase.sin())
+            });
+            let energy = re * re + im * im;
+            weighted += energy * bin as f32 * 44100.0 / samples.len() as f32;
+            total += energy;
+        }
+        weighted / total
+    };
+
+    let centroids: Vec<f32> = [220.0, 880.0, 3520.0].into_iter().map(spectral_centroid).collect();
+    for (centroid, frequency) in centroids.iter().zip([220.0, 880.0, 3520.0]) {
+        assert!(
+            (centroid / frequency - 1.0).abs() < 0.25,
+            "centroid {centroid} for {frequency} Hz"
+        );
+    }
+}
+
+#[test]
+fn test_exponential_attack_rises_faster_than_linear() {
+    // The loudest sample in the first 10ms of the sine's 40ms attack
+    let early_peak = |attack| {
+        let curves = EnvelopeCurves {
+            attack,
+            release: CurveShape::Linear,
+        };
+        get_dyn_source(500, 440.0, Timbre::Sine, 0, curves, OscillatorPhase::default())
+            .take(441)
+            .fold(0.0_f32, |peak, sample| peak.max(sample.abs()))
+    };
+
+    let linear = early_peak(CurveShape::Linear);
+    assert!(early_peak(CurveShape::Exponential) > 2.0 * linear);
+    assert!(early_peak(CurveShape::Logarithmic) < linear);
+}
+
+#[test]
+fn test_offset_phases_dont_cancel_when_inverted() {
+    // Two voices of the same note, with one inverted, so identical phases cancel completely
+    let residual = |first: f32, second: f32| {
+        let voice = |phase| get_dyn_source(500, 220.0, Timbre::Piano, 0, EnvelopeCurves::default(), phase);
+        voice(OscillatorPhase::Fixed(first))
+            .zip(voice(OscillatorPhase::Fixed(second)))
+            .take(24000)
+            .fold(0.0_f32, |peak, (a, b)| peak.max((a - b).abs()))
+    };
+
+    assert!(residual(0.0, 0.0) < 1e-6);
+    assert!(residual(0.0, 0.25) > 0.1);
+
+    // Random phases are chosen from the seed, so layers and separate notes start at different phases
+    let random = |seed| -> Vec<f32> {
+        get_dyn_source(
+            500,
+            220.0,
+            Timbre::Sine,
+            seed,
+            EnvelopeCurves::default(),
+            OscillatorPhase::Random,
+        )
+        .take(2400)
+        .collect()
+    };
+    assert_eq!(random(1), random(1));
+    assert_ne!(random(1), random(2));
+}
+
+#[test]
+fn test_transposed_instrument_sounds_at_concert_pitch() {
+    let samples = |instrument: Instrument, frequency: f32| -> Vec<f32> {
+        get_dyn_source(
+            500,
+            frequency,
+            Timbre::Instrument(instrument),
+            0,
+            EnvelopeCurves::default(),
+            OscillatorPhase::default(),
+        )
+        .take(24000)
+        .collect()
+    };
+    let strings = crate::instrument_tools::presets::strings();
+
+    // Up a whole step, a written C sounds as the D above it
+    let written = samples(strings.with_transpose(2), C4.0);
+    let concert = samples(strings, C4.semitone(2).0);
+    assert!(written.iter().zip(&concert).all(|(a, b)| (a - b).abs() < 1e-3));
+    assert!(written
+        .iter()
+        .zip(samples(strings, C4.0))
+        .any(|(a, b)| (a - b).abs() > 0.1));
+}
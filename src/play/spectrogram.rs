@@ -0,0 +1,254 @@
+//! Spectrograms of rendered pieces, from a short-time Fourier transform.
+//!
+//! The rendered channels are mixed down to mono and split into overlapping frames. Each frame is
+//! shaped with a Hann window and transformed with a radix-2 FFT, and the magnitude of each frequency bin
+//! is kept. Frames don't depend on each other, so they can be spread across threads.
+
+#![expect(
+    clippy::arithmetic_side_effects,
+    clippy::cast_precision_loss,
+    reason = "Audio processing on floating point samples"
+)]
+
+use std::f32::consts::TAU;
+
+use crate::{play::FileOutputConfig, MusicPlayer, Piece};
+
+/// Options for analysing a piece with [`Piece::spectrogram_with`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::SpectrogramOptions;
+///
+/// let options = SpectrogramOptions::new().with_window_size(1000).with_threads(4);
+///
+/// // Window sizes are rounded up to a power of two
+/// assert_eq!(options.window_size(), 1024);
+/// assert_eq!(options.hop_size(), 256);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpectrogramOptions {
+    window_size: usize,
+    hop_size: Option<usize>,
+    threads: usize,
+}
+
+impl Default for SpectrogramOptions {
+    fn default() -> Self {
+        SpectrogramOptions {
+            window_size: 2048,
+            hop_size: None,
+            threads: 1,
+        }
+    }
+}
+
+impl SpectrogramOptions {
+    /// Creates the default options: 2048 sample windows, a quarter of a window apart, on one thread.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the number of samples in each frame, which is rounded up to a power of two.
+    ///
+    /// Longer windows tell frequencies apart more finely, but blur together changes over time.
+    pub fn with_window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size
+            .max(2)
+            .checked_next_power_of_two()
+            .unwrap_or(self.window_size);
+        self
+    }
+
+    /// Sets the number of samples from the start of one frame to the start of the next.
+    ///
+    /// The default is a quarter of the window size. Zero is treated as one.
+    pub fn with_hop_size(mut self, hop_size: usize) -> Self {
+        self.hop_size = Some(hop_size.max(1));
+        self
+    }
+
+    /// Sets how many threads the frames are computed on. The default is one, which doesn't start any threads.
+    ///
+    /// The result is exactly the same for any number of threads. Zero is treated as one.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Gets the number of samples in each frame.
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Gets the number of samples from the start of one frame to the start of the next.
+    pub fn hop_size(&self) -> usize {
+        self.hop_size.unwrap_or(self.window_size / 4).max(1)
+    }
+
+    /// Gets the number of threads the frames are computed on.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+}
+
+impl Piece {
+    /// Renders this piece with `player` and measures the strength of each frequency over time.
+    ///
+    /// This uses the default [`SpectrogramOptions`]. See [`Piece::spectrogram_with`] for details.
+    pub fn spectrogram(&self, player: &MusicPlayer<FileOutputConfig>) -> Vec<Vec<f32>> {
+        self.spectrogram_with(player, &SpectrogramOptions::new())
+    }
+
+    /// Renders this piece with `player` and measures the strength of each frequency over time.
+    ///
+    /// The result has one frame for every hop through the rendered audio, and each frame has
+    /// `window_size / 2 + 1` frequency bins. Bin `k` is centered on `k * sample_rate / window_size` Hz.
+    /// Magnitudes are scaled so that a sine wave centered on a bin has the same magnitude as its amplitude.
+    /// All channels are mixed together first, and audio shorter than one window is padded with silence.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{MusicPlayer, SpectrogramOptions};
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let piece = Piece::from(sine(whole(NotePitch::new(1000.0))));
+    ///
+    /// let options = SpectrogramOptions::new().with_window_size(256).with_threads(2);
+    /// let frames = piece.spectrogram_with(&player, &options);
+    /// assert_eq!(frames[0].len(), 129);
+    ///
+    /// // Bins are 8000 / 256 = 31.25 Hz apart, so 1000 Hz is bin 32
+    /// let middle = &frames[frames.len() / 2];
+    /// let loudest = (0..middle.len()).max_by(|&a, &b| middle[a].total_cmp(&middle[b])).unwrap();
+    /// assert_eq!(loudest, 32);
+    /// ```
+    pub fn spectrogram_with(
+        &self,
+        player: &MusicPlayer<FileOutputConfig>,
+        options: &SpectrogramOptions,
+    ) -> Vec<Vec<f32>> {
+        spectrogram(&mixdown(player.render_channels(self.clone())), options)
+    }
+}
+
+/// Averages the channels into one
+fn mixdown(channels: Vec<Vec<f32>>) -> Vec<f32> {
+    let channel_count = channels.len().max(1) as f32;
+    let mut channels = channels.into_iter();
+    let mut mono = channels.next().unwrap_or_default();
+    for channel in channels {
+        for (sum, sample) in mono.iter_mut().zip(channel) {
+            *sum += sample;
+        }
+    }
+    mono.iter_mut().for_each(|sample| *sample /= channel_count);
+    mono
+}
+
+/// Computes the magnitudes of every frame, spread across the requested number of threads
+fn spectrogram(samples: &[f32], options: &SpectrogramOptions) -> Vec<Vec<f32>> {
+    let window_size = options.window_size();
+    let hop_size = options.hop_size();
+    let frame_count = samples.len().saturating_sub(window_size) / hop_size + 1;
+    let frames: Vec<usize> = (0..frame_count).map(|frame| frame * hop_size).collect();
+
+    let window: Vec<f32> = (0..window_size)
+        .map(|i| 0.5 - 0.5 * (TAU * i as f32 / window_size as f32).cos())
+        .collect();
+    let analyse = |start: &usize| analyse_frame(samples.get(*start..).unwrap_or_default(), &window);
+
+    if options.threads() == 1 || frames.len() < 2 {
+        return frames.iter().map(analyse).collect();
+    }
+
+    let chunk_size = frames.len().div_ceil(options.threads());
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = frames
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(analyse).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Spectrogram thread panicked"))
+            .collect()
+    })
+}
+
+/// Computes the magnitude of each frequency bin in one windowed frame, starting at the start of `samples`
+fn analyse_frame(samples: &[f32], window: &[f32]) -> Vec<f32> {
+    let mut real: Vec<f32> = window
+        .iter()
+        .enumerate()
+        .map(|(i, w)| samples.get(i).copied().unwrap_or_default() * w)
+        .collect();
+    let mut imaginary = vec![0.0; window.len()];
+    fft(&mut real, &mut imaginary);
+
+    // The Hann window halves the amplitude of a sine wave, and only half of its energy is in the positive bins
+    let scale = 2.0 / window.iter().sum::<f32>();
+    (0..=window.len() / 2)
+        .map(|bin| real[bin].hypot(imaginary[bin]) * scale)
+        .collect()
+}
+
+/// An in-place iterative radix-2 FFT. The length must be a power of two.
+fn fft(real: &mut [f32], imaginary: &mut [f32]) {
+    let n = real.len();
+    let bits = n.trailing_zeros();
+    if bits == 0 {
+        return;
+    }
+
+    // Put the samples in bit-reversed order
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            real.swap(i, j);
+            imaginary.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -TAU / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let twiddled_real = real[b] * cos - imaginary[b] * sin;
+                let twiddled_imaginary = real[b] * sin + imaginary[b] * cos;
+                real[b] = real[a] - twiddled_real;
+                imaginary[b] = imaginary[a] - twiddled_imaginary;
+                real[a] += twiddled_real;
+                imaginary[a] += twiddled_imaginary;
+            }
+        }
+        len *= 2;
+    }
+}
+
+#[test]
+fn test_parallel_spectrogram_matches_serial() {
+    let piece = Piece::from(crate::prelude::piano(
+        crate::prelude::quarter(crate::C4) + crate::prelude::quarter(crate::A4),
+    )) * crate::prelude::noise(crate::prelude::half(crate::C4));
+    let player = MusicPlayer::new_file(300, 1.0, 8000).with_seed(3);
+    let options = SpectrogramOptions::new().with_window_size(512).with_hop_size(100);
+
+    let serial = piece.spectrogram_with(&player, &options);
+    assert_eq!(serial.len(), (12800 - 512) / 100 + 1);
+    for threads in [2, 3, 8, 1000] {
+        assert_eq!(piece.spectrogram_with(&player, &options.with_threads(threads)), serial);
+    }
+
+    // Short pieces still have one frame
+    let short = Piece::from(crate::prelude::sine(crate::prelude::sixteenth(crate::A4)));
+    assert_eq!(
+        short
+            .spectrogram_with(&player, &options.with_window_size(2048).with_threads(4))
+            .len(),
+        1
+    );
+}
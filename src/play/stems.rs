@@ -0,0 +1,197 @@
+//! Rendering each instrument in a piece to its own file, for mixing elsewhere.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use crate::{
+    play::{render_to_wav::audible_range, FileOutputConfig},
+    MusicPlayer, Piece, Timbre,
+};
+
+impl Piece {
+    /// Renders each instrument in the piece to its own WAV file in `dir`, and returns their paths.
+    ///
+    /// There's one stem for every distinct [`Timbre`], in the order they first appear, holding every note played
+    /// with it and silence in place of the others. Each file is named after its timbre, such as `piano.wav`, or
+    /// an [`Instrument`](crate::Instrument)'s name if it has one. `dir` is created if it doesn't exist yet, and
+    /// files already in it with the same names are replaced.
+    ///
+    /// The stems are rendered with `player`'s settings, and are levelled together rather than one at a time: adding
+    /// them up gives the same audio as rendering the whole piece with [`MusicPlayer::render_to_wav`]. Every stem
+    /// has the same length and number of channels as the full render. The player's effects and pitch shift are
+    /// applied to each stem separately, so ones that depend on how loud the sound is, like [`Effect::Clip`](crate::Effect::Clip),
+    /// can make the sum differ slightly.
+    ///
+    /// # Example
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::MusicPlayer;
+    ///
+    /// let player = MusicPlayer::new_file(300, 1.0, 8000);
+    /// let piece = piano(quarter(C4) + quarter(A4)) * bass(half(C4.octave(-1))) * piano(half(C4));
+    ///
+    /// let dir = std::env::temp_dir().join("symphoxy_stems_example");
+    /// let stems = piece.render_stems(&dir, &player).unwrap();
+    /// assert_eq!(stems, [dir.join("piano.wav"), dir.join("bass.wav")]);
+    /// # std::fs::remove_dir_all(&dir).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    /// Returns an error if `dir` can't be created, or a stem can't be written to it.
+    pub fn render_stems(
+        &self,
+        dir: impl AsRef<Path>,
+        player: &MusicPlayer<FileOutputConfig>,
+    ) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let mut names: Vec<String> = Vec::new();
+        let mut paths = Vec::new();
+        for (timbre, samples) in player.render_stem_channels(self) {
+            let name = stem_name(timbre);
+            // Timbres that would share a name, like two unnamed instruments, are numbered
+            let mut unique = name.clone();
+            let mut count = 1u32;
+            while names.contains(&unique) {
+                count = count.saturating_add(1);
+                unique = format!("{name}_{count}");
+            }
+
+            let path = dir.join(format!("{unique}.wav"));
+            let mut writer = BufWriter::new(File::create(&path)?);
+            player.write_samples_to(&samples, &mut writer)?;
+            writer.flush()?;
+            names.push(unique);
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+impl MusicPlayer<FileOutputConfig> {
+    /// Renders the channels of every timbre's stem, levelled so they add up to the full render
+    fn render_stem_channels(&self, piece: &Piece) -> Vec<(Timbre, Vec<Vec<f32>>)> {
+        // Nothing else can see this flag, so the renders are never cancelled
+        let never = AtomicBool::new(false);
+        let mix = |only| self.mix_channels_until(piece.clone(), &never, only).unwrap_or_default();
+
+        let mut stems: Vec<(Timbre, Vec<Vec<f32>>)> = piece
+            .summary()
+            .timbres
+            .into_iter()
+            .map(|timbre| (timbre, mix(Some(timbre))))
+            .collect();
+
+        // Levelling is a gain on each sample of the full mix, which is applied to every stem in turn
+        let unlevelled = mix(None);
+        let mut levelled = unlevelled.clone();
+        self.master(&mut levelled);
+        for (channel, (unlevelled, levelled)) in unlevelled.iter().zip(&levelled).enumerate() {
+            let mut gain = 1.0;
+            for (i, (&before, &after)) in unlevelled.iter().zip(levelled).enumerate() {
+                // Where the mix is silent, the stems cancel out, so any gain keeps them adding up
+                if before != 0.0 {
+                    gain = after / before;
+                }
+                for (_, samples) in &mut stems {
+                    samples[channel][i] *= gain;
+                }
+            }
+        }
+
+        if self.output_config.trim_silence {
+            let audible = audible_range(&levelled);
+            for channel in stems.iter_mut().flat_map(|(_, samples)| samples) {
+                channel.truncate(audible.end);
+                channel.drain(..audible.start);
+            }
+        }
+        stems
+    }
+}
+
+/// Gets the name of a timbre's stem file, without its extension
+fn stem_name(timbre: Timbre) -> String {
+    let name = match timbre {
+        Timbre::Sine => "sine",
+        Timbre::Bass => "bass",
+        Timbre::Piano => "piano",
+        Timbre::ElectricGuitar => "electric_guitar",
+        Timbre::Noise => "noise",
+        Timbre::BandpassNoise { .. } => "bandpass_noise",
+        Timbre::Drums => "drums",
+        Timbre::CustomSourceUnpitched(path) | Timbre::CustomSourcePitched(path) => Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("custom"),
+        Timbre::Layered(_) => "layered",
+        Timbre::Instrument(instrument) if !instrument.name.is_empty() => instrument.name,
+        Timbre::Instrument(_) => "instrument",
+    };
+    // Keep the name safe to use as a file name on any system
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+#[test]
+fn test_stems_add_up_to_the_full_mix() {
+    use crate::{instrument_tools::presets, prelude::*, BitDepth};
+
+    let read = |path: &Path| -> Vec<f32> {
+        hound::WavReader::open(path)
+            .unwrap()
+            .into_samples::<f32>()
+            .map(Result::unwrap)
+            .collect()
+    };
+
+    let strings = Timbre::Instrument(presets::strings().with_name("Violins"));
+    let piece = piano(quarter(C4) + quarter(A4)).pan(-0.5)
+        * bass(half(C4.octave(-1)))
+        * noise(Line::from(eighth(C4)))
+        * piano(eighth(REST) + dotted(quarter)(C4))
+        * (quarter(A4) + quarter(C4)).with_timbre(strings);
+    let dir = std::env::temp_dir().join("symphoxy_stems_test");
+    let full_path = dir.join("full.wav");
+
+    for player in [
+        MusicPlayer::new_file(300, 1.0, 8000).with_bit_depth(BitDepth::Float32),
+        MusicPlayer::new_file(300, 0.9, 8000)
+            .with_channels(2)
+            .with_bit_depth(BitDepth::Float32)
+            .with_loudness_target(-14.0)
+            .with_trim_silence(true),
+    ] {
+        let stems = piece.render_stems(&dir, &player).unwrap();
+        let names: Vec<_> = stems
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["piano.wav", "bass.wav", "noise.wav", "Violins.wav"]);
+        assert_eq!(stems.len(), piece.summary().timbres.len());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        player.render_to_wav(piece.clone(), full_path.to_str().unwrap());
+        let full = read(&full_path);
+        let mut sum = vec![0.0; full.len()];
+        for stem in &stems {
+            let samples = read(stem);
+            assert_eq!(samples.len(), full.len());
+            sum.iter_mut().zip(samples).for_each(|(sum, sample)| *sum += sample);
+        }
+        assert!(full.iter().any(|&sample| sample != 0.0));
+        for (summed, mixed) in sum.iter().zip(&full) {
+            assert!(
+                (summed - mixed).abs() < 1e-4,
+                "stems add up to {summed}, but the mix is {mixed}"
+            );
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
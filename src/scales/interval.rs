@@ -2,48 +2,95 @@ use std::ops::Add;
 
 use crate::{Chord, Line, Note, NoteKind, NotePitch, Piece};
 
+/// A musical interval measured in semitones.
+///
+/// Intervals are stored as a (possibly fractional) number of semitones, so they
+/// can be used with any pitch in the 12-tone equal temperament system.
+///
+/// # Examples
+/// ```
+/// use symphoxy::scales::interval::Interval;
+///
+/// let fifth = Interval::PERFECT_FIFTH;
+/// assert_eq!(fifth.0, 7.0);
+/// ```
 #[derive(Clone, Copy)]
 pub struct Interval(pub f32);
 
 impl Interval {
+    /// Unison - the same pitch (0 semitones)
     pub const UNISON: Interval = Interval(0.0);
 
+    /// Minor second (1 semitone)
     pub const MINOR_SECOND: Interval = Interval(1.0);
+    /// Augmented unison (1 semitone), enharmonic to a minor second
     pub const AUGMENTED_UNISON: Interval = Self::MINOR_SECOND;
 
+    /// Major second (2 semitones)
     pub const MAJOR_SECOND: Interval = Interval(2.0);
 
+    /// Minor third (3 semitones)
     pub const MINOR_THIRD: Interval = Interval(3.0);
+    /// Augmented second (3 semitones), enharmonic to a minor third
     pub const AUGMENTED_SECOND: Interval = Self::MINOR_THIRD;
 
+    /// Major third (4 semitones)
     pub const MAJOR_THIRD: Interval = Interval(4.0);
 
+    /// Perfect fourth (5 semitones)
     pub const PERFECT_FOURTH: Interval = Interval(5.0);
+    /// Augmented third (5 semitones), enharmonic to a perfect fourth
     pub const AUGMENTED_THIRD: Interval = Self::PERFECT_FOURTH;
 
+    /// Tritone (6 semitones)
     pub const TRITONE: Interval = Interval(6.0);
+    /// Augmented fourth (6 semitones), enharmonic to a tritone
     pub const AUGMENTED_FOURTH: Interval = Self::TRITONE;
+    /// Diminished fifth (6 semitones), enharmonic to a tritone
     pub const DIMINISHED_FIFTH: Interval = Self::TRITONE;
 
+    /// Perfect fifth (7 semitones)
     pub const PERFECT_FIFTH: Interval = Interval(7.0);
 
+    /// Minor sixth (8 semitones)
     pub const MINOR_SIXTH: Interval = Interval(8.0);
+    /// Augmented fifth (8 semitones), enharmonic to a minor sixth
     pub const AUGMENTED_FIFTH: Interval = Self::MINOR_SIXTH;
 
+    /// Major sixth (9 semitones)
     pub const MAJOR_SIXTH: Interval = Interval(9.0);
 
+    /// Minor seventh (10 semitones)
     pub const MINOR_SEVENTH: Interval = Interval(10.0);
+    /// Augmented sixth (10 semitones), enharmonic to a minor seventh
     pub const AUGMENTED_SIXTH: Interval = Self::MINOR_SEVENTH;
 
+    /// Major seventh (11 semitones)
     pub const MAJOR_SEVENTH: Interval = Interval(11.0);
+    /// Diminished octave (11 semitones), enharmonic to a major seventh
     pub const DIMINISHED_OCTAVE: Interval = Self::MAJOR_SEVENTH;
 
+    /// Octave (12 semitones)
     pub const OCTAVE: Interval = Interval(12.0);
 }
 
+/// The shape of a chord, described as intervals above its root.
+///
+/// A chord shape can be transposed onto any root pitch to produce a [`Chord`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{scales::interval::Interval, ChordShape};
+///
+/// let major = ChordShape::from_intervals([Interval::UNISON, Interval::MAJOR_THIRD, Interval::PERFECT_FIFTH]);
+/// let c_major = major.transpose_to(C4);
+/// assert_eq!(c_major.len(), 3);
+/// ```
 pub struct ChordShape(pub Vec<Interval>);
 
 impl ChordShape {
+    /// Creates a chord by placing this shape on the given root pitch.
     pub fn transpose_to(&self, root: NotePitch) -> Chord {
         let pitches = self
             .0
@@ -56,10 +103,15 @@ impl ChordShape {
         Chord(pitches)
     }
 
+    /// Creates a chord shape from intervals measured from the root.
     pub fn from_intervals(intervals: impl IntoIterator<Item = Interval>) -> Self {
         ChordShape(intervals.into_iter().collect())
     }
 
+    /// Creates a chord shape from intervals stacked on top of each other.
+    ///
+    /// Each interval is measured from the previous one, so stacking a major third
+    /// and a minor third produces a major triad without its root.
     pub fn from_stacked_intervals(
         intervals: impl IntoIterator<Item = Interval, IntoIter = impl ExactSizeIterator<Item = Interval>>,
     ) -> Self {
@@ -99,15 +151,25 @@ impl ChordFluid for Note {
 
     fn with_chord_shape(self, chord_shape: &ChordShape) -> Self::Output {
         match self.1 {
-            NoteKind::Rest => Piece(vec![Line {
+            NoteKind::Rest => Piece::from(vec![Line {
                 notes: vec![self],
                 pickup: vec![],
                 hold_pickup: false,
+                labels: vec![],
             }]),
-            NoteKind::Pitched { pitch, timbre, volume } => {
+            NoteKind::Pitched {
+                pitch,
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide,
+                lfo,
+            } => {
                 let chord = pitch.with_chord_shape(chord_shape);
 
-                Piece(
+                Piece::from(
                     chord
                         .0
                         .into_iter()
@@ -118,12 +180,18 @@ impl ChordFluid for Note {
                                     pitch: note_pitch,
                                     timbre,
                                     volume,
+                                    pan,
+                                    probability,
+                                    nudge_ms,
+                                    glide,
+                                    lfo,
                                 },
                             )],
                             pickup: vec![],
                             hold_pickup: false,
+                            labels: vec![],
                         })
-                        .collect(),
+                        .collect::<Vec<_>>(),
                 )
             }
         }
@@ -138,6 +206,6 @@ impl ChordFluid for Line {
             .into_iter()
             .map(|note| note.with_chord_shape(chord_shape))
             .reduce(Add::add)
-            .unwrap_or_else(|| Piece(vec![]))
+            .unwrap_or_else(Piece::new)
     }
 }
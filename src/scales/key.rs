@@ -0,0 +1,132 @@
+use std::fmt::Display;
+
+use crate::{
+    note::{NoteKind, Timbre},
+    scales::tet12::get_note_name,
+    NotePitch, Piece, Tet12, A4, C4,
+};
+
+/// Whether a key is major or minor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+    /// A major key, such as C major
+    Major,
+    /// A minor key, such as A minor
+    Minor,
+}
+
+/// A musical key: a tonic and whether it's major or minor, such as C major.
+///
+/// See [`Piece::detect_key`].
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+/// use symphoxy::{Key, KeyMode};
+///
+/// let key = Key { tonic: A4, mode: KeyMode::Minor };
+/// assert_eq!(key.to_string(), "A minor");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Key {
+    /// The tonic of the key. Only its pitch class matters, the octave is arbitrary
+    pub tonic: NotePitch,
+    /// Whether the key is major or minor
+    pub mode: KeyMode,
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self.mode {
+            KeyMode::Major => "major",
+            KeyMode::Minor => "minor",
+        };
+        write!(f, "{} {mode}", get_note_name(self.tonic, A4))
+    }
+}
+
+/// How well each scale degree fits a major key, from Krumhansl and Kessler's probe tone experiments
+const MAJOR_PROFILE: [f32; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+
+/// How well each scale degree fits a minor key, from Krumhansl and Kessler's probe tone experiments
+const MINOR_PROFILE: [f32; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+impl Piece {
+    /// Guesses the key of the piece, such as for transposing a piece imported from elsewhere.
+    ///
+    /// This uses the Krumhansl-Schmuckler algorithm: the length of time spent on each pitch class
+    /// is compared against how strongly each pitch class suggests every major and minor key, and
+    /// the closest match is returned. Unpitched timbres (noise and drums) are ignored. A piece with
+    /// no pitched notes is reported as C major.
+    ///
+    /// The tonic of the returned key is between C4 and B4.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    /// use symphoxy::{Key, KeyMode};
+    ///
+    /// let scale = MajorScale(C4);
+    /// let melody = piano(
+    ///     quarter(scale.get_degree(1)) + quarter(scale.get_degree(3)) + quarter(scale.get_degree(5))
+    ///         + eighth(scale.get_degree(4)) + eighth(scale.get_degree(2)) + half(scale.get_degree(7))
+    ///         + whole(scale.get_degree(8)),
+    /// );
+    /// let piece = melody * bass(whole(C4.octave(-1)) + half(C4.semitone(-5)) + half(C4.octave(-1)));
+    ///
+    /// assert_eq!(piece.detect_key().to_string(), "C major");
+    ///
+    /// // Transposing the piece changes the key it's detected in
+    /// let key = piece.semitone(7).detect_key();
+    /// assert_eq!(key.to_string(), "G major");
+    /// assert_eq!(key.mode, KeyMode::Major);
+    /// ```
+    pub fn detect_key(&self) -> Key {
+        let mut durations = [0.0f32; 12];
+        for timed in self.notes() {
+            if let NoteKind::Pitched { pitch, timbre, .. } = timed.note.1 {
+                if matches!(timbre, Timbre::Noise | Timbre::Drums) {
+                    continue;
+                }
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "Rounded, and audible pitches are well within range"
+                )]
+                let semitones = (12.0 * (pitch.0 / C4.0).log2()).round() as i32;
+                let pitch_class = semitones.rem_euclid(12) as usize;
+                durations[pitch_class] += f32::from(timed.note.0 .0);
+            }
+        }
+
+        let mut best = (KeyMode::Major, 0, f32::NEG_INFINITY);
+        for (mode, profile) in [(KeyMode::Major, MAJOR_PROFILE), (KeyMode::Minor, MINOR_PROFILE)] {
+            for tonic in 0..12u8 {
+                let mut rotated = profile;
+                rotated.rotate_right(usize::from(tonic));
+                let score = correlation(&durations, &rotated);
+                if score > best.2 {
+                    best = (mode, tonic, score);
+                }
+            }
+        }
+
+        let (mode, tonic, _) = best;
+        Key {
+            tonic: C4.semitone(i16::from(tonic)),
+            mode,
+        }
+    }
+}
+
+/// The Pearson correlation coefficient of two sets of values. Returns NaN if either is constant.
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
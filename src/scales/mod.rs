@@ -1,15 +1,25 @@
-use crate::{
-    note::NotePitch,
-    scales::{interval::Interval, tet12::A4},
-};
+use crate::{note::NotePitch, scales::interval::Interval};
 
 /// 12-tone equal temperament system and related scales.
 ///
 /// Contains scale implementations and pitch manipulation functions.
 pub mod tet12;
 
+/// Musical intervals and chord shapes.
+///
+/// Contains the `Interval` type and `ChordShape` for building chords from intervals.
 pub mod interval;
 
+/// Musical keys and key detection.
+///
+/// Contains the `Key` type returned by `Piece::detect_key`.
+pub mod key;
+
+/// Pitch-class sets and their transformations.
+///
+/// Contains the `PitchClassSet` type, for analyzing and generating atonal music.
+pub mod pitch_class;
+
 pub use tet12::modes::*;
 
 /// A trait for musical scales that can generate pitches from scale degrees.
@@ -30,6 +40,7 @@ pub use tet12::modes::*;
 /// let triad = c_major.get_degrees([1, 3, 5]); // C-E-G chord
 /// ```
 pub trait Scale {
+    /// Returns the intervals of each scale degree, measured from the root.
     fn intervals() -> &'static [Interval];
 
     /// Gets the pitch at the specified scale degree.
@@ -70,6 +81,16 @@ pub trait Scale {
         degrees.map(|degree| self.get_degree(degree))
     }
 
+    /// Creates a chord from the pitches at the specified scale degrees.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let scale = MajorScale(C4);
+    /// let c_major = scale.get_chord(&[1, 3, 5]); // C-E-G
+    /// assert_eq!(c_major.len(), 3);
+    /// ```
     fn get_chord(&self, degrees: &[isize]) -> crate::note::chord::Chord {
         let pitches = degrees.iter().map(|&degree| self.get_degree(degree)).collect();
         crate::note::chord::Chord(pitches)
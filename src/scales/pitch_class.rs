@@ -0,0 +1,200 @@
+use crate::{NotePitch, Tet12, C4};
+
+/// An unordered set of pitch classes, for analyzing and generating music with pitch-class set theory.
+///
+/// Pitch classes are numbered in semitones from C, so C is 0, C# is 1, and B is 11. Octaves and spelling
+/// don't matter, so every C is pitch class 0, and pitch class 13 is the same as 1.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// // The C major triad, C E G
+/// let triad = PitchClassSet::new([0, 4, 7]);
+///
+/// assert_eq!(triad.transpose(2).pitch_classes(), [2, 6, 9]); // D major
+/// assert_eq!(triad.invert(7).pitch_classes(), [0, 3, 7]); // C minor
+/// assert_eq!(triad.prime_form(), [0, 3, 7]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PitchClassSet(u16);
+
+impl PitchClassSet {
+    /// Creates a set from pitch classes, reducing each one to between 0 and 11.
+    pub fn new(pitch_classes: impl IntoIterator<Item = i16>) -> Self {
+        PitchClassSet(
+            pitch_classes
+                .into_iter()
+                .fold(0, |set, pitch_class| set | 1 << pitch_class.rem_euclid(12)),
+        )
+    }
+
+    /// Creates a set from the pitch class of each pitch, rounded to the nearest semitone.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = PitchClassSet::from_pitches([C4.octave(-1), C4.semitone(3), C4.semitone(7), C4.octave(1)]);
+    /// assert_eq!(chord, PitchClassSet::new([0, 3, 7]));
+    /// ```
+    pub fn from_pitches(pitches: impl IntoIterator<Item = NotePitch>) -> Self {
+        PitchClassSet::new(pitches.into_iter().filter(|pitch| pitch.0 > 0.0).map(|pitch| {
+            #[expect(clippy::cast_possible_truncation, reason = "Rounded, and audible pitches are well within range")]
+            let semitones = (12.0 * (pitch.0 / C4.0).log2()).round() as i16;
+            semitones
+        }))
+    }
+
+    /// Gets the pitch classes in the set, from lowest to highest.
+    pub fn pitch_classes(&self) -> Vec<u8> {
+        (0..12u8)
+            .filter(|&pitch_class| self.contains(i16::from(pitch_class)))
+            .collect()
+    }
+
+    /// Gets whether the set contains a pitch class, which is reduced to between 0 and 11 first.
+    pub fn contains(&self, pitch_class: i16) -> bool {
+        self.0 & 1 << pitch_class.rem_euclid(12) != 0
+    }
+
+    /// Gets the number of pitch classes in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Gets whether the set has no pitch classes.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Gets the pitches of the set within the octave starting at `root`, which is pitch class 0, from lowest to highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let chord = piano(whole_chord(PitchClassSet::new([0, 4, 7]).pitches(C4)));
+    /// ```
+    pub fn pitches(&self, root: NotePitch) -> Vec<NotePitch> {
+        self.pitch_classes()
+            .into_iter()
+            .map(|pitch_class| root.semitone(i16::from(pitch_class)))
+            .collect()
+    }
+
+    /// Transposes every pitch class up `n` semitones, the operation written T<sub>n</sub>.
+    pub fn transpose(&self, n: i16) -> Self {
+        PitchClassSet::new(
+            self.classes()
+                .map(|pitch_class| pitch_class.saturating_add(n.rem_euclid(12))),
+        )
+    }
+
+    /// Inverts the set, the operation written T<sub>n</sub>I with `axis` as n.
+    ///
+    /// Each pitch class `x` becomes `axis - x`, so pitch classes that add up to `axis` swap places. An axis of 0
+    /// mirrors the set around C, and an odd axis mirrors it around a point between two pitch classes.
+    pub fn invert(&self, axis: i16) -> Self {
+        PitchClassSet::new(
+            self.classes()
+                .map(|pitch_class| axis.rem_euclid(12).saturating_sub(pitch_class)),
+        )
+    }
+
+    /// Gets the normal form of the set: its pitch classes in the most compact order, going up from the first.
+    ///
+    /// Of the orders that start on each pitch class in turn, the normal form spans the smallest interval from the
+    /// first pitch class to the last. Ties are broken by the interval from the first to the second to last, then
+    /// the third to last, and so on, and then by the lowest first pitch class.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // B, C, E spans a fourth, but C, E, B spans a seventh
+    /// assert_eq!(PitchClassSet::new([0, 4, 11]).normal_form(), [11, 0, 4]);
+    /// ```
+    pub fn normal_form(&self) -> Vec<u8> {
+        let pitch_classes = self.pitch_classes();
+        (0..pitch_classes.len())
+            .map(|start| {
+                let mut rotation = pitch_classes.clone();
+                rotation.rotate_left(start);
+                rotation
+            })
+            .min_by_key(|rotation| packing(rotation))
+            .unwrap_or_default()
+    }
+
+    /// Gets the prime form of the set, which every set that's a transposition or inversion of it shares.
+    ///
+    /// This is the more compact of the normal forms of the set and its inversion, compared the same way as in
+    /// [`PitchClassSet::normal_form`], and transposed to start on 0. Some textbooks break ties between orders
+    /// differently, so give different prime forms for a few sets, such as 5-20.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// // Major and minor triads are the same set class, 3-11
+    /// assert_eq!(PitchClassSet::new([4, 8, 11]).prime_form(), [0, 3, 7]);
+    /// assert_eq!(PitchClassSet::new([9, 0, 4]).prime_form(), [0, 3, 7]);
+    /// ```
+    pub fn prime_form(&self) -> Vec<u8> {
+        [*self, self.invert(0)]
+            .into_iter()
+            .map(|set| intervals_from_first(&set.normal_form()))
+            .min_by_key(|form| packing(form))
+            .unwrap_or_default()
+    }
+
+    /// Iterates over the pitch classes in the set, from lowest to highest
+    fn classes(&self) -> impl Iterator<Item = i16> + '_ {
+        self.pitch_classes().into_iter().map(i16::from)
+    }
+}
+
+/// Gets the interval up from the first pitch class of an order to each pitch class in it
+fn intervals_from_first(order: &[u8]) -> Vec<u8> {
+    let first = order.first().copied().unwrap_or_default();
+    order
+        .iter()
+        .map(|&pitch_class| (pitch_class.saturating_add(12).saturating_sub(first)) % 12)
+        .collect()
+}
+
+/// Gets the intervals from the first pitch class of an order to each of the others, from the last one back,
+/// which are smaller for a more compact order
+fn packing(order: &[u8]) -> Vec<u8> {
+    let mut intervals = intervals_from_first(order);
+    intervals.reverse();
+    intervals
+}
+
+#[test]
+fn test_transformations_and_prime_forms_match_textbook_sets() {
+    // T2 and T7I of the C major triad are D major and C minor
+    let c_major = PitchClassSet::new([0, 4, 7]);
+    assert_eq!(c_major.transpose(2), PitchClassSet::new([2, 6, 9]));
+    assert_eq!(c_major.transpose(-5), PitchClassSet::new([7, 11, 2]));
+    assert_eq!(c_major.invert(0), PitchClassSet::new([0, 8, 5]));
+    assert_eq!(c_major.invert(7), PitchClassSet::new([7, 3, 0]));
+    assert_eq!(c_major.invert(7).invert(7), c_major);
+
+    // Every transposition and inversion of a set has the same prime form
+    for (set, prime_form) in [
+        (vec![11, 0, 4], vec![0, 1, 5]),
+        (vec![0, 5, 6], vec![0, 1, 6]),
+        (vec![1, 5, 6, 7], vec![0, 1, 2, 6]),
+        (vec![0, 2, 4, 6, 8, 10], vec![0, 2, 4, 6, 8, 10]),
+    ] {
+        let set = PitchClassSet::new(set);
+        for n in 0..12 {
+            assert_eq!(set.transpose(n).prime_form(), prime_form);
+            assert_eq!(set.invert(n).prime_form(), prime_form);
+        }
+    }
+    assert_eq!(PitchClassSet::new([7, 1, 5, 6]).normal_form(), [1, 5, 6, 7]);
+    assert!(PitchClassSet::default().prime_form().is_empty());
+}
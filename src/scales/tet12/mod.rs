@@ -1,8 +1,11 @@
 pub use modes::*;
 
+use std::str::FromStr;
+
 use crate::{
     instrument_tools::strings::StringTuning,
-    note::{chord::Chord, NotePitch},
+    note::{chord::Chord, Glide, Note, NoteKind, NotePitch},
+    Line, Piece,
 };
 
 /// Gets the note name (without octave) for a given pitch.
@@ -22,14 +25,23 @@ use crate::{
 /// assert_eq!(sharp_name, "C#");
 /// ```
 pub fn get_note_name(note: NotePitch, a4: NotePitch) -> String {
-    let name = get_note_name_with_octave(note, a4);
-    name.trim_end_matches(char::is_numeric).to_string()
+    let (pitch_class, _) = pitch_class_and_octave(note, a4);
+    String::from(NOTE_NAMES[pitch_class])
 }
 
+/// The names of each pitch class, starting from C
+const NOTE_NAMES: [&str; 12] = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+
+/// The fixed do solfège syllables of each pitch class, starting from C
+const SOLFEGE_NAMES: [&str; 12] = [
+    "do", "do#", "re", "re#", "mi", "fa", "fa#", "sol", "sol#", "la", "la#", "si",
+];
+
 /// Gets the note name with octave number for a given pitch.
 ///
 /// Returns the note name with octave in standard Western notation (e.g., "C4", "A#5")
-/// relative to the provided A4 reference pitch.
+/// relative to the provided A4 reference pitch. This is always in scientific pitch notation;
+/// see [`get_note_name_with_naming`] for other conventions.
 ///
 /// # Examples
 /// ```
@@ -43,25 +55,198 @@ pub fn get_note_name(note: NotePitch, a4: NotePitch) -> String {
 /// assert_eq!(higher_note, "A5");
 /// ```
 pub fn get_note_name_with_octave(note: NotePitch, a4: NotePitch) -> String {
+    get_note_name_with_naming(note, a4, PitchNaming::Scientific)
+}
+
+/// Gets the name of a pitch in the given naming convention.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// assert_eq!(get_note_name_with_naming(C4, A4, PitchNaming::Scientific), "C4");
+/// assert_eq!(get_note_name_with_naming(C4, A4, PitchNaming::Helmholtz), "c′");
+/// assert_eq!(get_note_name_with_naming(C4.octave(-2), A4, PitchNaming::Helmholtz), "C");
+/// assert_eq!(get_note_name_with_naming(C4.semitone(7), A4, PitchNaming::Solfege), "sol");
+/// ```
+pub fn get_note_name_with_naming(note: NotePitch, a4: NotePitch, naming: PitchNaming) -> String {
+    let (pitch_class, octave) = pitch_class_and_octave(note, a4);
+    match naming {
+        PitchNaming::Scientific => format!("{}{octave}", NOTE_NAMES[pitch_class]),
+        PitchNaming::Helmholtz => {
+            // The octave below middle C is written in lowercase, and the one below that in uppercase.
+            // Higher octaves add primes, and lower octaves add commas.
+            let count = |octaves: i16| usize::from(octaves.unsigned_abs());
+            if octave >= 3 {
+                NOTE_NAMES[pitch_class].to_lowercase() + &"′".repeat(count(octave.saturating_sub(3)))
+            } else {
+                NOTE_NAMES[pitch_class].to_string() + &",".repeat(count(octave.saturating_sub(2)))
+            }
+        }
+        PitchNaming::Solfege => String::from(SOLFEGE_NAMES[pitch_class]),
+    }
+}
+
+/// Finds the nearest pitch class (0 for C, up to 11 for B) to a pitch, and the octave it's in
+fn pitch_class_and_octave(note: NotePitch, a4: NotePitch) -> (usize, i16) {
     let c4 = a4.semitone(3).octave(-1);
+    let semitones = (12.0 * f32::log2(note.0 / c4.0)).round();
+
+    // Saturates for pitches of zero or infinite frequency, and NaN is treated as C4
+    #[expect(clippy::cast_possible_truncation, reason = "Saturates, which is fine for nonsense pitches")]
+    let semitones = semitones as i32;
+    let pitch_class = semitones.rem_euclid(12) as usize;
+    let octave = i16::try_from(semitones.div_euclid(12))
+        .unwrap_or(i16::MAX)
+        .saturating_add(4);
+    (pitch_class, octave)
+}
 
-    let note_names = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"];
+/// Conventions for writing the names of pitches.
+///
+/// The `Display` and `Debug` output of pitches, notes, and pieces always uses scientific pitch notation.
+/// To write them in another convention, pass it to [`get_note_name_with_naming`], or to the `display_with`
+/// method of [`NotePitch`], [`Piece`] (for the piano roll), or [`Lint`](crate::Lint).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub enum PitchNaming {
+    /// Scientific pitch notation, where middle C is C4 and octaves start at C
+    #[default]
+    Scientific,
+    /// Helmholtz pitch notation, where middle C is c′, the C below it is c, and the C below that is C
+    Helmholtz,
+    /// Fixed do solfège, where every C is do. This doesn't name octaves.
+    Solfege,
+}
 
-    let diff = f32::log2(note.0 / c4.0);
+/// Writes a value like its `Display` output, but with its pitches named in the given convention
+pub(crate) struct DisplayWith<'a, T>(pub(crate) &'a T, pub(crate) PitchNaming);
 
-    #[expect(clippy::cast_possible_truncation, reason = "log_2 of a non-infinite f32 has at most 7 bits")]
-    let (octave_diff, semitone_diff) = (diff.floor() as i16, ((diff * 12.0).round() as i16).rem_euclid(12));
+/// Parses a pitch written in scientific pitch notation, such as `"A4"`, `"C#4"`, or `"Db4"`, tuned to A4 at 440 Hz.
+///
+/// A note is a letter from A to G in either case, then an optional sharp (`#` or `♯`) or flat (`b` or `♭`),
+/// then an octave number, which can be negative. Octaves start at C, so B3 is one semitone below C4.
+///
+/// # Errors
+/// Returns an error if the letter isn't a note name, the octave is missing or isn't a whole number,
+/// or there's more than one accidental, such as in `"C##4"`.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let a4: NotePitch = "A4".parse().unwrap();
+/// assert_eq!(a4, A4);
+///
+/// // Sharps and flats of the same note are the same pitch
+/// let c_sharp: NotePitch = "C#4".parse().unwrap();
+/// assert_eq!(c_sharp, "Db4".parse().unwrap());
+/// assert_eq!(get_note_name_with_octave(c_sharp, A4), "C#4");
+///
+/// assert_eq!("H4".parse::<NotePitch>(), Err(PitchParseError::InvalidLetter('H')));
+/// assert_eq!("C".parse::<NotePitch>(), Err(PitchParseError::MissingOctave));
+/// ```
+impl FromStr for NotePitch {
+    type Err = PitchParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let letter = chars.next().ok_or(PitchParseError::Empty)?;
+        let semitones_above_c: i16 = match letter.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return Err(PitchParseError::InvalidLetter(letter)),
+        };
+
+        let accidental = |c: char| match c {
+            '#' | '♯' => Some(1),
+            'b' | '♭' => Some(-1),
+            _ => None,
+        };
+        let rest = chars.as_str();
+        let (alteration, octave) = match rest.chars().next().and_then(accidental) {
+            Some(alteration) => (alteration, &rest[rest.chars().next().map_or(0, char::len_utf8)..]),
+            None => (0, rest),
+        };
+        if octave.chars().next().and_then(accidental).is_some() {
+            return Err(PitchParseError::DoubleAccidental);
+        }
+        if octave.is_empty() {
+            return Err(PitchParseError::MissingOctave);
+        }
+        let octave: i16 = octave
+            .parse()
+            .map_err(|_| PitchParseError::InvalidOctave(octave.to_string()))?;
+
+        // Counted from A4, which is 9 semitones above C4
+        #[expect(clippy::arithmetic_side_effects, reason = "All three are within a single octave")]
+        let semitones_above_a = semitones_above_c + alteration - 9;
+        let semitones = octave
+            .checked_sub(4)
+            .and_then(|octaves| octaves.checked_mul(12))
+            .and_then(|semitones| semitones.checked_add(semitones_above_a))
+            .ok_or_else(|| PitchParseError::InvalidOctave(octave.to_string()))?;
+        Ok(A4.semitone(semitones))
+    }
+}
 
-    #[expect(clippy::cast_sign_loss, reason = "semitone_diff is always in range 0..12")]
-    let note_name = String::from(note_names[semitone_diff as usize]);
+/// An error from parsing a [`NotePitch`] from a string.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PitchParseError {
+    /// The string was empty
+    Empty,
+    /// The note letter wasn't one of A to G
+    InvalidLetter(char),
+    /// The note had no octave number, such as `"C#"`
+    MissingOctave,
+    /// The octave wasn't a whole number, or was too far from middle C to represent
+    InvalidOctave(String),
+    /// The note had more than one sharp or flat, such as `"C##4"` or `"Dbb4"`
+    DoubleAccidental,
+}
 
-    #[expect(clippy::arithmetic_side_effects, reason = "This is guaranteed to fit in i16.")]
-    let octave_number = octave_diff + 4;
+impl std::fmt::Display for PitchParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PitchParseError::Empty => write!(f, "pitch is empty"),
+            PitchParseError::InvalidLetter(letter) => write!(f, "'{letter}' is not a note name from A to G"),
+            PitchParseError::MissingOctave => write!(f, "pitch is missing an octave number, such as the 4 in C4"),
+            PitchParseError::InvalidOctave(octave) => write!(f, "'{octave}' is not a valid octave number"),
+            PitchParseError::DoubleAccidental => write!(f, "double sharps and flats are not supported"),
+        }
+    }
+}
 
-    #[expect(clippy::arithmetic_side_effects, reason = "This is a simple string concatenation")]
-    let out = note_name + &(octave_number).to_string();
+impl std::error::Error for PitchParseError {}
 
-    out
+#[test]
+fn test_parse_pitches() {
+    let parse = |s: &str| s.parse::<NotePitch>();
+    let close = |a: NotePitch, b: NotePitch| (a.0 / b.0 - 1.0).abs() < 1e-5;
+
+    assert!(close(parse("C4").unwrap(), C4));
+    assert!(close(parse("c4").unwrap(), C4));
+    assert!(close(parse("C#4").unwrap(), C4.semitone(1)));
+    assert!(close(parse("C♯4").unwrap(), C4.semitone(1)));
+    assert!(close(parse("Db4").unwrap(), C4.semitone(1)));
+    assert!(close(parse("B♭3").unwrap(), C4.semitone(-2)));
+    assert!(close(parse("Cb4").unwrap(), parse("B3").unwrap()));
+    assert!(close(parse("A-1").unwrap(), A4.octave(-5)));
+    assert!(close(parse(" G10 ").unwrap(), C4.semitone(7).octave(6)));
+
+    assert_eq!(parse(""), Err(PitchParseError::Empty));
+    assert_eq!(parse("X4"), Err(PitchParseError::InvalidLetter('X')));
+    assert_eq!(parse("4C"), Err(PitchParseError::InvalidLetter('4')));
+    assert_eq!(parse("C#"), Err(PitchParseError::MissingOctave));
+    assert_eq!(parse("C##4"), Err(PitchParseError::DoubleAccidental));
+    assert_eq!(parse("Dbb4"), Err(PitchParseError::DoubleAccidental));
+    assert_eq!(parse("C4.5"), Err(PitchParseError::InvalidOctave("4.5".to_string())));
+    assert_eq!(parse("Cx4"), Err(PitchParseError::InvalidOctave("x4".to_string())));
+    assert_eq!(parse("C9999"), Err(PitchParseError::InvalidOctave("9999".to_string())));
 }
 
 #[test]
@@ -78,6 +263,86 @@ fn test_get_note_name() {
     }
 }
 
+#[test]
+fn test_pitch_naming_conventions() {
+    let name = |pitch, naming| get_note_name_with_naming(pitch, A4, naming);
+    assert_eq!(name(C4, PitchNaming::Scientific), "C4");
+    assert_eq!(name(C4, PitchNaming::Helmholtz), "c′");
+    assert_eq!(name(C4, PitchNaming::Solfege), "do");
+
+    let helmholtz = C4
+        .semitones([-36, -24, -13, -12, 1, 12, 24])
+        .map(|pitch| name(pitch, PitchNaming::Helmholtz));
+    assert_eq!(helmholtz, ["C,", "C", "B", "c", "c#′", "c′′", "c′′′"]);
+    assert_eq!(name(A4.semitone(-1), PitchNaming::Solfege), "sol#");
+}
+
+#[test]
+fn test_frequency_snapping_between_semitones() {
+    use crate::PitchRounding;
+
+    let close = |a: NotePitch, b: NotePitch| (a.0 / b.0 - 1.0).abs() < 1e-5;
+    let snap = |hz, rounding| NotePitch::from_frequency_with_cents(hz, A4, rounding);
+
+    // Exactly halfway between A4 and A#4, both below and above A4
+    for (below, above) in [(A4, A4.semitone(1)), (C4.semitone(-1), C4)] {
+        let halfway = below.0 * 2.0f32.powf(0.5 / 12.0);
+        let (down, down_cents) = snap(halfway, PitchRounding::Down);
+        let (up, up_cents) = snap(halfway, PitchRounding::Up);
+        let (nearest, nearest_cents) = snap(halfway, PitchRounding::Nearest);
+        assert!(close(down, below) && (down_cents - 50.0).abs() < 0.01);
+        assert!(close(up, above) && (up_cents + 50.0).abs() < 0.01);
+        assert!(close(nearest, above) && (nearest_cents + 50.0).abs() < 0.01);
+    }
+
+    // Frequencies on a semitone stay on it, whichever way they're rounded
+    for rounding in [PitchRounding::Nearest, PitchRounding::Down, PitchRounding::Up] {
+        for pitch in C4.semitones([-25, -1, 0, 7, 30]) {
+            let (snapped, cents) = snap(pitch.0, rounding);
+            assert!(close(snapped, pitch), "{pitch:?} snapped to {snapped:?}");
+            assert!(cents.abs() < 0.1);
+        }
+    }
+
+    // A different tuning moves every semitone with it
+    let a442 = NotePitch(442.0);
+    assert!(close(
+        NotePitch::from_frequency(442.0 * 2.0f32.powf(3.1 / 12.0), a442),
+        a442.semitone(3)
+    ));
+    assert_eq!(snap(0.0, PitchRounding::Nearest), (NotePitch(0.0), 0.0));
+    assert!(snap(f32::NAN, PitchRounding::Up).0 .0.is_nan());
+}
+
+#[test]
+fn test_midi_range_extremes() {
+    // Both ends of the MIDI range, and the octave numbers below zero
+    assert!((NotePitch::from_midi(0).0 - NotePitch::MIDI_LOWEST.0).abs() < 1e-4);
+    assert!((NotePitch::from_midi(127).0 - NotePitch::MIDI_HIGHEST.0).abs() < 1e-2);
+    assert_eq!(get_note_name_with_octave(NotePitch::MIDI_LOWEST, A4), "C-1");
+    assert_eq!(get_note_name_with_octave(NotePitch::MIDI_HIGHEST, A4), "G9");
+    assert_eq!(get_note_name(NotePitch::MIDI_LOWEST, A4), "C");
+    assert!((NotePitch::from_midi(0).0 - 8.1758).abs() < 1e-3);
+    assert!((NotePitch::from_midi(127).0 - 12543.85).abs() < 1e-1);
+
+    // Every note number survives a round trip, and the frequencies never overflow
+    for note_number in 0..=u8::MAX {
+        let pitch = NotePitch::from_midi(note_number);
+        assert!(pitch.0.is_finite() && pitch.0 > 0.0);
+        assert_eq!(pitch.to_midi(), note_number.min(127));
+    }
+
+    // Pitches far outside the range are clamped rather than wrapping around
+    for pitch in [0.0, -1.0, 1e-30, f32::NAN, f32::NEG_INFINITY] {
+        assert_eq!(NotePitch(pitch).to_midi(), 0);
+    }
+    for pitch in [1e5, f32::MAX, f32::INFINITY] {
+        assert_eq!(NotePitch(pitch).to_midi(), 127);
+    }
+    let _ = get_note_name_with_octave(NotePitch(0.0), A4);
+    let _ = get_note_name_with_octave(NotePitch(f32::INFINITY), A4);
+}
+
 /// Standard pitch reference - A above middle C at 440 Hz.
 ///
 /// This is the international standard tuning reference pitch.
@@ -181,6 +446,283 @@ impl Tet12 for Chord {
     }
 }
 
+/// Transposes every pitched note, keeping its length, timbre, volume, and pan.
+/// Rests are unaffected.
+impl Tet12 for Note {
+    fn octave(&self, change: i32) -> Self {
+        map_note_pitch(self, |pitch| pitch.octave(change))
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        map_note_pitch(self, |pitch| pitch.semitone(change))
+    }
+}
+
+/// Transposes every pitched note in the line, including the pickup.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let melody = piano(quarter(C4) + quarter(REST) + quarter(A4));
+/// let higher = melody.semitone(2);
+///
+/// assert_eq!(higher.notes[0], piano(quarter(C4.semitone(2))));
+/// assert_eq!(higher.notes[1], quarter(REST));
+/// assert_eq!(higher.length(), melody.length());
+///
+/// // Transposing one line of a piece, such as a harmony, leaves the others as they are
+/// let harmony = piano(quarter(C4) + quarter(C4) + quarter(C4));
+/// let piece = melody.clone() * harmony.semitone(3);
+///
+/// assert_eq!(piece.lines[0], melody);
+/// assert_eq!(piece.lines[1], piano(quarter(C4.semitone(3)) + quarter(C4.semitone(3)) + quarter(C4.semitone(3))));
+/// ```
+impl Tet12 for Line {
+    fn octave(&self, change: i32) -> Self {
+        map_line_notes(self, |note| note.octave(change))
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        map_line_notes(self, |note| note.semitone(change))
+    }
+}
+
+/// Transposes every pitched note in every line of the piece.
+///
+/// This changes the notes themselves. To shift the pitch of the rendered audio instead,
+/// including custom sampled sources, see `MusicPlayer::with_pitch_shift`.
+impl Tet12 for Piece {
+    fn octave(&self, change: i32) -> Self {
+        Piece {
+            lines: self.lines.iter().map(|line| line.octave(change)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+
+    fn semitone(&self, change: i16) -> Self {
+        Piece {
+            lines: self.lines.iter().map(|line| line.semitone(change)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+}
+
+impl Line {
+    /// Transposes every pitched note in the line by `semitones`, the same as [`Tet12::semitone`].
+    ///
+    /// Only this line changes, so transposing a harmony before adding it to a piece leaves the melody as it is.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(C4) + quarter(A4));
+    /// let harmony = piano(quarter(C4) + quarter(C4));
+    /// let piece = melody.clone() * harmony.transpose(3);
+    ///
+    /// assert_eq!(piece.lines[0], melody);
+    /// assert_eq!(piece.lines[1], piano(quarter(C4.semitone(3)) + quarter(C4.semitone(3))));
+    /// ```
+    pub fn transpose(&self, semitones: i16) -> Line {
+        self.semitone(semitones)
+    }
+
+    /// Inverts the line around `axis`, so every interval up from it becomes the same interval down.
+    ///
+    /// A note a major third above the axis becomes a major third below it, and notes on the axis stay put.
+    /// Glides are mirrored along with the notes, and rests are unaffected. Pitches that aren't a finite
+    /// frequency above zero, such as NaN, can't be mirrored, so they're left as they are, and so is the
+    /// whole line if `axis` isn't one.
+    ///
+    /// # Examples
+    /// ```
+    /// use symphoxy::prelude::*;
+    ///
+    /// let melody = piano(quarter(A4) + quarter(A4.semitone(4)) + quarter(A4.octave(1)));
+    /// let inverted = melody.invert(A4);
+    ///
+    /// assert_eq!(inverted.notes[0], melody.notes[0]);
+    /// assert!((inverted.notes[1].pitch().unwrap().0 - A4.semitone(-4).0).abs() < 0.01);
+    /// assert_eq!(inverted.notes[2], piano(quarter(A4.octave(-1))));
+    /// ```
+    pub fn invert(&self, axis: NotePitch) -> Line {
+        map_line_notes(self, |note| map_note_pitch(note, |pitch| invert_pitch(pitch, axis)))
+    }
+}
+
+impl Piece {
+    /// Transposes every pitched note in every line of the piece by `semitones`, the same as [`Tet12::semitone`].
+    ///
+    /// To transpose only one voice, use [`Line::transpose`] on its line before adding it to the piece.
+    pub fn transpose(&self, semitones: i16) -> Piece {
+        self.semitone(semitones)
+    }
+
+    /// Inverts every line of the piece around `axis`, as in [`Line::invert`].
+    pub fn invert(&self, axis: NotePitch) -> Piece {
+        Piece {
+            lines: self.lines.iter().map(|line| line.invert(axis)).collect(),
+            metadata: self.metadata().clone(),
+        }
+    }
+}
+
+/// Raises every pitched note in a line by an octave, the same as [`Tet12::semitone`] with 12.
+///
+/// This reads well when doubling a melody an octave higher.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let melody = piano(quarter(C4) + quarter(A4));
+/// let doubled = melody.clone() * octave_up(melody.clone());
+///
+/// assert_eq!(doubled.lines[1], piano(quarter(C4.octave(1)) + quarter(A4.octave(1))));
+/// ```
+pub fn octave_up(line: impl Into<Line>) -> Line {
+    line.into().octave(1)
+}
+
+/// Lowers every pitched note in a line by an octave, the same as [`Tet12::semitone`] with -12.
+///
+/// # Examples
+/// ```
+/// use symphoxy::prelude::*;
+///
+/// let bass_line = octave_down(bass(quarter(C4) + quarter(A4)));
+///
+/// assert_eq!(bass_line.notes[0], bass(quarter(C4.octave(-1))));
+/// ```
+pub fn octave_down(line: impl Into<Line>) -> Line {
+    line.into().octave(-1)
+}
+
+#[test]
+fn test_octave_shifts_match_twelve_semitones() {
+    use crate::prelude::*;
+
+    let melody = piano(quarter(C4) + eighth(REST) + dotted(quarter)(A4.semitone(3))) + sine(half(C4.semitone(-5)));
+    let up = octave_up(melody.clone());
+    assert_eq!(up, melody.semitone(12));
+    assert_eq!(octave_down(melody.clone()), melody.semitone(-12));
+    assert_eq!(octave_down(up.clone()), melody);
+
+    // Every pitch is doubled, and rests and lengths are kept
+    for (raised, original) in up.notes.iter().zip(&melody.notes) {
+        assert_eq!(raised.0, original.0);
+        assert_eq!(
+            raised.pitch().map(|pitch| pitch.0),
+            original.pitch().map(|pitch| pitch.0 * 2.0)
+        );
+    }
+}
+
+#[test]
+fn test_transposing_one_line_leaves_the_others_unchanged() {
+    use crate::prelude::*;
+
+    let melody = piano(quarter(C4) + eighth(REST) + eighth(A4));
+    let harmony = piano(Line::from(half(C4.semitone(4))));
+    let piece = melody.clone() * harmony.transpose(3) * bass(Line::from(half(A4.octave(-1))).invert(A4));
+
+    assert_eq!(piece.lines[0], melody);
+    assert_eq!(piece.lines[1], harmony.semitone(3));
+    assert_eq!(piece.lines[2], bass(Line::from(half(A4.octave(1)))));
+    assert_eq!(piece.transpose(-3).lines[1], harmony);
+
+    // Inverting twice around the same axis gives back the original pitches, and rests stay put
+    let inverted = melody.invert(A4);
+    assert_eq!(inverted.notes[1], melody.notes[1]);
+    assert_eq!(inverted.notes[2], melody.notes[2]);
+    for (twice, original) in inverted.invert(A4).notes.iter().zip(&melody.notes) {
+        assert_eq!(twice.0, original.0);
+        let frequency = |note: &Note| note.pitch().map_or(0.0, |pitch| pitch.0);
+        assert!((frequency(twice) - frequency(original)).abs() < 1e-3);
+    }
+    assert_eq!(
+        piece.invert(A4).lines,
+        [
+            melody.invert(A4),
+            harmony.semitone(3).invert(A4),
+            piece.lines[2].invert(A4)
+        ]
+    );
+}
+
+#[test]
+fn test_inverting_leaves_unmirrorable_pitches_alone() {
+    use crate::prelude::*;
+
+    let odd_pitches = [0.0, -440.0, f32::INFINITY, f32::NAN];
+    let notes = odd_pitches.map(|frequency| quarter(NotePitch(frequency)));
+    let line = sine(Line::from(notes.to_vec()));
+    let frequencies = |line: &Line| -> Vec<f32> { line.notes.iter().map(|note| note.pitch().unwrap().0).collect() };
+
+    let inverted = line.invert(A4);
+    assert_eq!(inverted.notes.len(), 4);
+    assert_eq!(frequencies(&inverted)[..3], odd_pitches[..3]);
+    assert!(frequencies(&inverted)[3].is_nan());
+
+    // Nothing can be mirrored around an axis that isn't a real pitch either
+    let melody = piano(quarter(C4) + quarter(A4));
+    for axis in odd_pitches {
+        assert_eq!(melody.invert(NotePitch(axis)), melody);
+    }
+}
+
+fn map_note_pitch(note: &Note, f: impl Fn(NotePitch) -> NotePitch) -> Note {
+    match note.1 {
+        NoteKind::Pitched {
+            pitch,
+            timbre,
+            volume,
+            pan,
+            probability,
+            nudge_ms,
+            glide,
+            lfo,
+        } => Note(
+            note.0,
+            NoteKind::Pitched {
+                pitch: f(pitch),
+                timbre,
+                volume,
+                pan,
+                probability,
+                nudge_ms,
+                glide: glide.map(|glide| Glide {
+                    from: f(glide.from),
+                    ..glide
+                }),
+                lfo,
+            },
+        ),
+        NoteKind::Rest => *note,
+    }
+}
+
+/// Mirrors a pitch around `axis`, which is halfway between them in semitones.
+/// Pitches and axes that aren't finite and positive have no mirror image, so the pitch is returned unchanged.
+fn invert_pitch(pitch: NotePitch, axis: NotePitch) -> NotePitch {
+    let valid = |pitch: NotePitch| pitch.0.is_finite() && pitch.0 > 0.0;
+    if valid(pitch) && valid(axis) {
+        NotePitch(axis.0 * axis.0 / pitch.0)
+    } else {
+        pitch
+    }
+}
+
+fn map_line_notes(line: &Line, f: impl Fn(&Note) -> Note) -> Line {
+    Line {
+        notes: line.notes.iter().map(&f).collect(),
+        pickup: line.pickup.iter().map(&f).collect(),
+        hold_pickup: line.hold_pickup,
+        labels: line.labels.clone(),
+    }
+}
+
 fn get_degree_with_pattern_and_root(degree: isize, root: NotePitch, intervals: &'static [Interval]) -> NotePitch {
     #[expect(clippy::cast_possible_wrap, reason = "Only used internally, and correctly")]
     let len = intervals.len() as isize;